@@ -1,7 +1,293 @@
+// Errors are only built on the failure path of an event, their size does not
+// matter compared to the context they carry.
+#![allow(clippy::result_large_err)]
+
 use kore_contract_sdk as sdk;
+use regex_lite::Regex;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Value, json};
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+/// Classification of the failures reported by the contract.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The event cannot be applied in the current version of the subject.
+    InvalidEvent,
+    /// The event does not provide any of its optional parameters or a list is empty.
+    MissingParameter,
+    /// A name (type, field, unit process, element...) is empty.
+    EmptyName,
+    /// A custom type uses a reserved name.
+    ReservedName,
+    /// A type definition is not valid.
+    InvalidType,
+    /// A type name is not a built-in type nor a custom type.
+    UnknownType,
+    /// Custom types reference themselves directly or indirectly.
+    CircularDependency,
+    /// Two elements share the same name.
+    Duplicate,
+    /// The referenced element does not exist.
+    NotFound,
+    /// A registered name does not match the expected one.
+    NameMismatch,
+    /// A value or a registered type does not match the expected type.
+    TypeMismatch,
+    /// An object has less or more fields than its type allows.
+    FieldCount,
+    /// An object lacks a required field.
+    MissingField,
+    /// An object has fields that are not defined in its type.
+    UnexpectedField,
+    /// An enum value uses a variant that is not defined in its type.
+    UnknownVariant,
+    /// A value does not satisfy the constraints of its type.
+    Constraint,
+    /// A quantity uses an unknown unit or a unit of another dimension.
+    UnitMismatch,
+    /// A target of a data element is not valid.
+    InvalidTarget,
+    /// The issuer of the event is not allowed to emit it.
+    Unauthorized,
+    /// A custom type cannot be deleted while other elements use it.
+    InUse,
+    /// Several errors were found, they are listed in `errors`.
+    Multiple,
+    /// A value nests custom types deeper than `MAX_DEPTH`.
+    TooDeep,
+    /// A JSON Schema construct has no equivalent custom type.
+    Unsupported,
+    /// The outputs of a unit process do not balance its inputs.
+    Unbalanced,
+}
+
+/// Error returned by the contract. It is serialized as JSON into the
+/// `error` of the contract result.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub message: String,
+    /// JSON path of the offending value, `$` is the root of the value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub received: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit_process: Option<String>,
+    /// Name of the data element, property or custom type involved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub element: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<Error>,
+}
+
+impl Error {
+    fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Error {
+            kind,
+            message: message.into(),
+            path: None,
+            expected: None,
+            received: None,
+            unit_process: None,
+            element: None,
+            errors: vec![],
+        }
+    }
+
+    fn multiple(errors: Vec<Error>) -> Self {
+        let mut error = Error::new(
+            ErrorKind::Multiple,
+            format!("Check error: {} errors were found.", errors.len()),
+        );
+        error.errors = errors;
+        error
+    }
+
+    /// First error found, used when only one error has to be reported.
+    fn first(mut self) -> Self {
+        if self.kind == ErrorKind::Multiple && !self.errors.is_empty() {
+            self.errors.remove(0)
+        } else {
+            self
+        }
+    }
+
+    /// Applies `f` to the error, or to each of the errors it groups.
+    fn each(mut self, f: impl Fn(Error) -> Error + Copy) -> Self {
+        if self.kind == ErrorKind::Multiple {
+            self.errors = self.errors.into_iter().map(|e| e.each(f)).collect();
+            self
+        } else {
+            f(self)
+        }
+    }
+
+    /// Error of a value that does not match the expected type.
+    fn value(kind: ErrorKind, message: impl Into<String>, expected: &str, value: &Value) -> Self {
+        Error::new(kind, message)
+            .path("$")
+            .expected(expected)
+            .received(json_type(value))
+    }
+
+    fn path(mut self, path: &str) -> Self {
+        self.path = Some(path.to_owned());
+        self
+    }
+
+    fn expected(mut self, expected: &str) -> Self {
+        self.expected = Some(expected.to_owned());
+        self
+    }
+
+    fn received(mut self, received: &str) -> Self {
+        self.received = Some(received.to_owned());
+        self
+    }
+
+    /// Sets the unit process unless a more specific one is already set.
+    fn unit_process(self, unit_process: &str) -> Self {
+        self.each(|mut e| {
+            if e.unit_process.is_none() {
+                e.unit_process = Some(unit_process.to_owned());
+            }
+            e
+        })
+    }
+
+    /// Sets the element unless a more specific one is already set.
+    fn element(self, element: &str) -> Self {
+        self.each(|mut e| {
+            if e.element.is_none() {
+                e.element = Some(element.to_owned());
+            }
+            e
+        })
+    }
+
+    /// Prefixes the path with the field of the object that contains the value.
+    fn at_field(self, field: &str) -> Self {
+        let is_identifier = !field.is_empty()
+            && field.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !field.starts_with(|c: char| c.is_ascii_digit());
+
+        if is_identifier {
+            self.prefix(&format!(".{}", field))
+        } else {
+            self.prefix(&format!("[{}]", Value::String(field.to_owned())))
+        }
+    }
+
+    /// Prefixes the path with the index of the array that contains the value.
+    fn at_index(self, index: usize) -> Self {
+        self.prefix(&format!("[{}]", index))
+    }
+
+    fn prefix(self, segment: &str) -> Self {
+        self.each(|mut e| {
+            let rest = e
+                .path
+                .as_deref()
+                .and_then(|path| path.strip_prefix('$'))
+                .unwrap_or_default();
+            e.path = Some(format!("${}{}", segment, rest));
+            e
+        })
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+
+        let mut context = vec![];
+        if let Some(unit_process) = &self.unit_process {
+            context.push(format!("unit process '{}'", unit_process));
+        }
+        if let Some(element) = &self.element {
+            context.push(format!("element '{}'", element));
+        }
+        if let Some(path) = &self.path {
+            context.push(format!("path '{}'", path));
+        }
+
+        if !context.is_empty() {
+            write!(f, " [{}]", context.join(", "))?;
+        }
+
+        for error in &self.errors {
+            write!(f, "\n- {}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Gathers the errors found while walking a value or an event, so every
+/// violation can be reported at once.
+#[derive(Default)]
+struct Errors(Vec<Error>);
+
+impl Errors {
+    fn push(&mut self, error: Error) {
+        if error.kind == ErrorKind::Multiple {
+            self.0.extend(error.errors);
+        } else {
+            self.0.push(error);
+        }
+    }
+
+    /// Whether no error has been kept so far.
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Keeps the error of `result`, if any, and returns its value.
+    fn check<T>(&mut self, result: Result<T, Error>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.push(error);
+                None
+            }
+        }
+    }
+
+    fn finish(mut self) -> Result<(), Error> {
+        match self.0.len() {
+            0 => Ok(()),
+            1 => Err(self.0.remove(0)),
+            _ => Err(Error::multiple(self.0)),
+        }
+    }
+}
+
+/// How many errors are reported when an event fails.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// Only the first error found.
+    #[default]
+    FirstError,
+    /// Every error found while walking the event, with its location.
+    AllErrors,
+}
 
 fn has_cycle(
     node: &str,
@@ -31,38 +317,85 @@ fn has_cycle(
     false
 }
 
-fn count_options(hash_map: &HashMap<String, DynamicType>) -> (usize, usize) {
-    let mut count = 0;
-    for (_, c_type) in hash_map.clone() {
-        if c_type.is_option() {
-            count += 1;
-        }
-    }
-
-    (hash_map.len(), count)
-}
-
-fn check_cycle(cycle_types: HashMap<String, Vec<String>>) -> Result<(), String> {
+/// First of `nodes` from which a cycle of `graph` can be reached.
+fn find_cycle<'a>(
+    nodes: impl IntoIterator<Item = &'a String>,
+    graph: &HashMap<String, Vec<String>>,
+) -> Option<&'a String> {
     let mut visited = HashSet::new();
     let mut stack = HashSet::new();
 
-    for type_name in cycle_types.keys() {
-        if !visited.contains(type_name)
-            && has_cycle(type_name, &cycle_types, &mut visited, &mut stack)
-        {
-            return Err(format!(
-                "Check error: Circular dependency detected in type '{}'. Types cannot reference themselves directly or indirectly.",
+    nodes
+        .into_iter()
+        .find(|node| !visited.contains(*node) && has_cycle(node, graph, &mut visited, &mut stack))
+}
+
+fn check_cycle(cycle_types: HashMap<String, Vec<String>>) -> Result<(), Error> {
+    if let Some(type_name) = find_cycle(cycle_types.keys(), &cycle_types) {
+        return Err(Error::new(
+            ErrorKind::CircularDependency,
+            format!(
+                "Check error: Circular dependency detected in type '{}'. Types can only reference themselves through an Option, Vec or Map.",
                 type_name
-            ));
-        }
+            ),
+        )
+        .element(type_name));
     }
 
     Ok(())
 }
 
-fn add_types(state: &mut ProductionSystem, types: Vec<(String, Fields)>) -> Result<(), String> {
+/// How many custom types a value can nest, recursive types are
+/// finite but a value could still be arbitrarily deep.
+const MAX_DEPTH: usize = 32;
+
+/// How many fraction digits a decimal can have, registered values are padded
+/// to the scale.
+const MAX_SCALE: u32 = 38;
+
+/// Dialect of the exported JSON Schema documents.
+const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Built-in types that elements can take without a custom type.
+const BUILT_IN_TYPES: &[&str] = &[
+    "String",
+    "i64",
+    "u64",
+    "f64",
+    "bool",
+    "Timestamp",
+    "Date",
+    "Duration",
+];
+
+/// Names of the built-in types, custom types cannot use them.
+const RESERVED_NAMES: &[&str] = &[
+    "String",
+    "bool",
+    "i64",
+    "f64",
+    "u64",
+    "Dummy",
+    "Option",
+    "Enum",
+    "Type",
+    "Vec",
+    "Number",
+    "Text",
+    "Timestamp",
+    "Date",
+    "Duration",
+    "Decimal",
+    "Map",
+    "Tuple",
+    "Array",
+    "Default",
+    "Annotated",
+];
+
+fn add_types(state: &mut ProductionSystem, types: Vec<(String, Fields)>) -> Result<(), Error> {
     if types.is_empty() {
-        return Err("Check error: Cannot add types. The 'types' parameter must contain at least one type definition.".to_owned());
+        return Err(Error::new(ErrorKind::MissingParameter, "Check error: Cannot add types. The 'types' parameter must contain at least one type definition."));
     }
 
     let mut temporal_types: HashMap<String, Fields> = state.custom_types.clone();
@@ -71,41 +404,59 @@ fn add_types(state: &mut ProductionSystem, types: Vec<(String, Fields)>) -> Resu
         temporal_types.insert(name, fields);
     }
 
+    check_types(&temporal_types)?;
+
+    state.custom_types = temporal_types;
+
+    Ok(())
+}
+
+/// Checks every custom type: its name, its definition, the types it
+/// references and that no type references itself outside an Option, Vec or
+/// Map.
+fn check_types(custom_types: &HashMap<String, Fields>) -> Result<(), Error> {
     let mut cycle_types: HashMap<String, Vec<String>> = HashMap::new();
+    let mut errors = Errors::default();
 
-    for (name, fields) in temporal_types.clone() {
+    for (name, fields) in custom_types.iter() {
         if name.is_empty() {
-            return Err("Check error: Type name cannot be empty. Please provide a valid type name.".to_owned());
-        }
-        match name.as_str() {
-            "String" | "bool" | "i64" | "f64" | "u64" | "Dummy" | "Option" | "Enum" | "Type"
-            | "Vec" => {
-                return Err(format!(
-                    "Check error: The type name '{}' is reserved and cannot be used. Reserved names are: String, bool, i64, f64, u64, Dummy, Option, Enum, Type, Vec.",
-                    name
-                ));
-            }
-            _ => {}
+            errors.push(Error::new(ErrorKind::EmptyName, "Check error: Type name cannot be empty. Please provide a valid type name."));
+            continue;
+        }
+        if RESERVED_NAMES.contains(&name.as_str()) {
+            errors.push(Error::new(
+                ErrorKind::ReservedName,
+                format!(
+                    "Check error: The type name '{}' is reserved and cannot be used. Reserved names are: {}.",
+                    name,
+                    RESERVED_NAMES.join(", ")
+                ),
+            )
+            .element(name));
+            continue;
         }
 
-        let internal_types = fields.check_data(temporal_types.clone())?;
-        cycle_types.insert(name.clone(), internal_types);
+        if let Some(internal_types) = errors.check(
+            fields
+                .check_data(custom_types.clone())
+                .map_err(|e| e.element(name)),
+        ) {
+            cycle_types.insert(name.clone(), internal_types);
+        }
     }
 
-    check_cycle(cycle_types)?;
-
-    state.custom_types = temporal_types;
-
-    Ok(())
+    errors.check(check_cycle(cycle_types));
+    errors.finish()
 }
 
-fn add_unit_process(state: &mut ProductionSystem, add: Vec<UnitProcess>) -> Result<(), String> {
+fn add_unit_process(state: &mut ProductionSystem, add: Vec<UnitProcess>) -> Result<(), Error> {
     if add.is_empty() {
-        return Err("Check error: Cannot add unit processes. The 'add' parameter must contain at least one unit process definition.".to_owned());
+        return Err(Error::new(ErrorKind::MissingParameter, "Check error: Cannot add unit processes. The 'add' parameter must contain at least one unit process definition."));
     }
 
+    let mut errors = Errors::default();
     for unit_process in add {
-        unit_process.check_data(&state.custom_types)?;
+        errors.check(unit_process.check_data(&state.custom_types));
         state.unit_process.push(unit_process);
     }
 
@@ -117,21 +468,22 @@ fn add_unit_process(state: &mut ProductionSystem, add: Vec<UnitProcess>) -> Resu
     let hash_unit_name: HashSet<String> = HashSet::from_iter(unit_names.iter().cloned());
 
     if hash_unit_name.len() != unit_names.len() {
-        return Err("Check error: Duplicate unit process names detected. Each unit process must have a unique name.".to_owned());
+        errors.push(Error::new(ErrorKind::Duplicate, "Check error: Duplicate unit process names detected. Each unit process must have a unique name."));
     }
 
-    Ok(())
+    errors.finish()
 }
 
 fn add_new_properties(
     state: &mut ProductionSystem,
     properties: Vec<Properties>,
-) -> Result<(), String> {
+) -> Result<(), Error> {
     if properties.is_empty() {
-        return Err("Check error: Cannot add properties. The 'properties' parameter must contain at least one property definition.".to_owned());
+        return Err(Error::new(ErrorKind::MissingParameter, "Check error: Cannot add properties. The 'properties' parameter must contain at least one property definition."));
     }
+    let mut errors = Errors::default();
     for pro in properties.clone() {
-        pro.check_data(&state.custom_types)?;
+        errors.check(pro.check_data(&state.custom_types));
         state.properties.push(pro);
     }
 
@@ -144,19 +496,19 @@ fn add_new_properties(
         HashSet::from_iter(properties_names.iter().cloned());
 
     if hash_properties_names.len() != properties_names.len() {
-        return Err("Check error: Duplicate property names detected. Each property must have a unique name.".to_owned());
+        errors.push(Error::new(ErrorKind::Duplicate, "Check error: Duplicate property names detected. Each property must have a unique name."));
     }
 
-    Ok(())
+    errors.finish()
 }
 
 fn check_data(
     type_name: &str,
     content: Value,
     custom_types: &HashMap<String, Fields>,
-) -> Result<(), String> {
+) -> Result<(), Error> {
     if type_name.is_empty() {
-        return Err("Check error: Type name cannot be empty. Please provide a valid type name for the element.".to_owned());
+        return Err(Error::new(ErrorKind::EmptyName, "Check error: Type name cannot be empty. Please provide a valid type name for the element."));
     }
 
     if let Some(dynamic_type) = custom_types.get(type_name) {
@@ -168,47 +520,167 @@ fn check_data(
             "u64" => DynamicType::u64.deserialize(content, custom_types),
             "f64" => DynamicType::f64.deserialize(content, custom_types),
             "bool" => DynamicType::bool.deserialize(content, custom_types),
-            _ => Err(format!("Check error: Unknown type name '{}'. The type must be either a built-in type (String, i64, u64, f64, bool) or a custom type defined in the schema.", type_name)),
+            "Timestamp" => DynamicType::Timestamp.deserialize(content, custom_types),
+            "Date" => DynamicType::Date.deserialize(content, custom_types),
+            "Duration" => DynamicType::Duration.deserialize(content, custom_types),
+            _ => Err(Error::new(ErrorKind::UnknownType, format!("Check error: Unknown type name '{}'. The type must be either a built-in type (String, i64, u64, f64, bool, Timestamp, Date, Duration) or a custom type defined in the schema.", type_name)).received(type_name)),
         }
     }
 }
 
+/// Checks the registered data against the element and returns its content
+/// with the quantities converted into the units of the type.
 fn register_data(
     local_name: &str,
     local_type_name: &str,
     custom_types: &HashMap<String, Fields>,
     name: &str,
     type_name: &str,
-    content: Value,
-) -> Result<(), String> {
+    mut content: Value,
+) -> Result<Value, Error> {
     if local_name != name {
-        return Err(format!(
-            "Check error: Name mismatch. Expected name '{}' but received '{}'. The data name must match the expected name.",
-            local_name, name
-        ));
+        return Err(Error::new(
+            ErrorKind::NameMismatch,
+            format!(
+                "Check error: Name mismatch. Expected name '{}' but received '{}'. The data name must match the expected name.",
+                local_name, name
+            ),
+        )
+        .expected(local_name)
+        .received(name));
     }
 
     if local_type_name != type_name {
-        return Err(format!(
-            "Check error: Type mismatch. Expected type '{}' but received '{}'. The data type must match the expected type.",
-            local_type_name, type_name
-        ));
+        return Err(Error::new(
+            ErrorKind::TypeMismatch,
+            format!(
+                "Check error: Type mismatch. Expected type '{}' but received '{}'. The data type must match the expected type.",
+                local_type_name, type_name
+            ),
+        )
+        .expected(local_type_name)
+        .received(type_name));
     }
 
     if let Some(c_type) = custom_types.get(local_type_name) {
-        c_type.check_value(content, custom_types)?;
+        c_type.normalize(&mut content, custom_types, 0)?;
+        c_type.check_value(content.clone(), custom_types)?;
     } else {
+        let content = content.clone();
         match local_type_name {
             "String" => DynamicType::String.deserialize(content, custom_types)?,
             "i64" => DynamicType::i64.deserialize(content, custom_types)?,
             "u64" => DynamicType::u64.deserialize(content, custom_types)?,
             "f64" => DynamicType::f64.deserialize(content, custom_types)?,
             "bool" => DynamicType::bool.deserialize(content, custom_types)?,
-            _ => return Err(format!("Check error: Unknown type name '{}'. The type must be either a built-in type (String, i64, u64, f64, bool) or a custom type defined in the schema.", local_type_name)),
+            "Timestamp" => DynamicType::Timestamp.deserialize(content, custom_types)?,
+            "Date" => DynamicType::Date.deserialize(content, custom_types)?,
+            "Duration" => DynamicType::Duration.deserialize(content, custom_types)?,
+            _ => return Err(Error::new(ErrorKind::UnknownType, format!("Check error: Unknown type name '{}'. The type must be either a built-in type (String, i64, u64, f64, bool, Timestamp, Date, Duration) or a custom type defined in the schema.", local_type_name)).received(local_type_name)),
         };
     };
 
-    Ok(())
+    Ok(content)
+}
+
+fn field_not_found(type_name: &str, field: &str) -> Error {
+    Error::new(
+        ErrorKind::NotFound,
+        format!(
+            "ModifyTypes error: Cannot migrate field '{}'. Type '{}' has no field with this name.",
+            field, type_name
+        ),
+    )
+    .element(type_name)
+    .at_field(field)
+}
+
+/// Who is allowed to emit an event.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    /// Only the owner of the subject.
+    Owner,
+    /// Any issuer allowed by the governance.
+    Anyone,
+}
+
+impl Access {
+    fn allows(&self, is_owner: bool) -> bool {
+        match self {
+            Access::Owner => is_owner,
+            Access::Anyone => true,
+        }
+    }
+}
+
+/// Access policy of the events of a production system. Changes of the
+/// production system are reserved to the owner by default while data can be
+/// registered by anyone.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct Permissions {
+    pub init: Access,
+    pub modify_production_system: Access,
+    pub modify_types: Access,
+    pub modify_unit_process: Access,
+    pub register_data: Access,
+}
+
+impl Default for Permissions {
+    fn default() -> Self {
+        Permissions {
+            init: Access::Owner,
+            modify_production_system: Access::Owner,
+            modify_types: Access::Owner,
+            modify_unit_process: Access::Owner,
+            register_data: Access::Anyone,
+        }
+    }
+}
+
+/// The permissions themselves can only be changed by the owner, otherwise an
+/// event open to anyone could open every other event.
+fn permissions_not_owner() -> Error {
+    Error::new(
+        ErrorKind::Unauthorized,
+        "Permission error: Only the owner of the subject can change the permissions of the production system.",
+    )
+    .expected("Owner")
+}
+
+impl Permissions {
+    fn check(&self, event: &Events, is_owner: bool) -> Result<(), Error> {
+        let (access, event_name) = match event {
+            Events::ChangeProductionSystem(ChangeProductionSystem::Init { .. }) => {
+                (self.init, "Init")
+            }
+            Events::ChangeProductionSystem(ChangeProductionSystem::ModifyProductionSystem {
+                ..
+            }) => (self.modify_production_system, "ModifyProductionSystem"),
+            Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes { .. }) => {
+                (self.modify_types, "ModifyTypes")
+            }
+            Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess { .. }) => {
+                (self.modify_unit_process, "ModifyUnitProcess")
+            }
+            // Each unit process may override who registers its data, the
+            // access is checked for every unit data.
+            Events::RegisterData(..) => return Ok(()),
+        };
+
+        if access.allows(is_owner) {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorKind::Unauthorized,
+                format!(
+                    "Permission error: Only the owner of the subject can emit '{}' events. The permissions of the production system reserve this event to the owner.",
+                    event_name
+                ),
+            )
+            .expected("Owner"))
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -218,1096 +690,7048 @@ struct ProductionSystem {
     pub version: u32,
     pub unit_process: Vec<UnitProcess>,
     pub properties: Vec<Properties>,
+    #[serde(default)]
+    pub validation: ValidationMode,
+    #[serde(default)]
+    pub permissions: Permissions,
+    /// Material flow between the unit processes.
+    #[serde(default)]
+    pub links: Vec<Link>,
+    /// Subject that holds the production system, data cannot target it.
+    /// Targets cannot be registered until it is set.
+    #[serde(default)]
+    pub subject_id: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(untagged)]
-pub enum Fields {
-    Basic(Box<DynamicType>),
-    Object(HashMap<String, DynamicType>),
-}
+impl ProductionSystem {
+    /// JSON Schema (draft 2020-12) document of the production system. The
+    /// custom types are in `$defs` and the content of every element is
+    /// described at `properties/unit_process/properties/<unit process>/
+    /// properties/<inputs|outputs|properties>/properties/<element>` and
+    /// `properties/properties/properties/<property>`.
+    pub fn json_schema(&self) -> Value {
+        let element = |type_name: &str| {
+            if self.custom_types.contains_key(type_name) {
+                return DynamicType::Type(type_name.to_owned()).json_schema();
+            }
 
-impl Fields {
-    fn check_data(&self, custom_types: HashMap<String, Fields>) -> Result<Vec<String>, String> {
-        let mut internal_types: Vec<String> = vec![];
-        match self {
-            Fields::Basic(dynamic_type) => {
-                if let DynamicType::Dummy | DynamicType::Option(..) | DynamicType::Type(..) =
-                    **dynamic_type
-                {
-                    return Err("Check error: Invalid basic type. A 'Basic' field cannot be Dummy, Option, or Type. Use 'Object' for complex types or specify a concrete type.".to_owned());
+            match type_name {
+                "String" => DynamicType::String.json_schema(),
+                "i64" => DynamicType::i64.json_schema(),
+                "u64" => DynamicType::u64.json_schema(),
+                "f64" => DynamicType::f64.json_schema(),
+                "bool" => DynamicType::bool.json_schema(),
+                "Timestamp" => DynamicType::Timestamp.json_schema(),
+                "Date" => DynamicType::Date.json_schema(),
+                "Duration" => DynamicType::Duration.json_schema(),
+                _ => json!({}),
+            }
+        };
+        let object = |properties: serde_json::Map<String, Value>| {
+            json!({
+                "type": "object",
+                "properties": properties,
+                "additionalProperties": false
+            })
+        };
+        let data = |elements: &Vec<Data>| {
+            object(
+                elements
+                    .iter()
+                    .map(|data| {
+                        let mut schema = element(&data.type_name);
+                        if let Some(annotations) = &data.annotations {
+                            annotations.annotate(&mut schema);
+                        }
+                        (data.name.clone(), schema)
+                    })
+                    .collect(),
+            )
+        };
+        let properties = |properties: &Vec<Properties>| {
+            object(
+                properties
+                    .iter()
+                    .map(|property| {
+                        let mut schema = element(&property.type_name);
+                        if let Some(annotations) = &property.annotations {
+                            annotations.annotate(&mut schema);
+                        }
+                        (property.name.clone(), schema)
+                    })
+                    .collect(),
+            )
+        };
+
+        let unit_process = self
+            .unit_process
+            .iter()
+            .map(|unit_process| {
+                let mut schema = object(serde_json::Map::from_iter([
+                    ("inputs".to_owned(), data(&unit_process.inputs)),
+                    ("outputs".to_owned(), data(&unit_process.outputs)),
+                    (
+                        "properties".to_owned(),
+                        properties(&unit_process.properties),
+                    ),
+                ]));
+                if let Some(annotations) = &unit_process.annotations {
+                    annotations.annotate(&mut schema);
                 }
+                (unit_process.name.clone(), schema)
+            })
+            .collect();
+
+        let defs: serde_json::Map<String, Value> = self
+            .custom_types
+            .iter()
+            .map(|(name, fields)| (name.clone(), fields.json_schema()))
+            .collect();
+
+        let mut schema = object(serde_json::Map::from_iter([
+            ("unit_process".to_owned(), object(unit_process)),
+            ("properties".to_owned(), properties(&self.properties)),
+        ]));
+        schema["$schema"] = json!(JSON_SCHEMA_DIALECT);
+        schema["title"] = json!(self.name);
+        schema["$defs"] = Value::Object(defs);
+        schema
+    }
 
-                dynamic_type.check_data(custom_types.clone(), &mut internal_types)?;
-            }
-            Fields::Object(hash_map) => {
-                if hash_map.is_empty() {
-                    return Err("Check error: Object fields cannot be empty. An object type must contain at least one field.".to_owned());
+    /// Custom types, data elements and properties that use the custom type
+    /// `type_name`.
+    fn type_dependants(&self, type_name: &str) -> Vec<String> {
+        let mut dependants: Vec<String> = self
+            .custom_types
+            .iter()
+            .filter(|(name, fields)| *name != type_name && fields.uses(type_name))
+            .map(|(name, _)| format!("type '{}'", name))
+            .collect();
+        dependants.sort();
+
+        for unit_process in self.unit_process.iter() {
+            for (kind, data) in unit_process
+                .inputs
+                .iter()
+                .map(|data| ("input", data))
+                .chain(unit_process.outputs.iter().map(|data| ("output", data)))
+            {
+                if data.type_name == type_name {
+                    dependants.push(format!(
+                        "unit process '{}' {} '{}'",
+                        unit_process.name, kind, data.name
+                    ));
                 }
 
-                for (field, c_type) in hash_map.iter() {
-                    if let DynamicType::Dummy = c_type {
-                        return Err(format!("Check error: Field '{}' has invalid type. Object fields cannot be of type 'Dummy'. Please specify a concrete type.", field));
-                    }
-                    if field.is_empty() {
-                        return Err("Check error: Field name cannot be empty. All object fields must have a non-empty name.".to_owned());
-                    }
+                if let Some(metadata) = &data.metadata
+                    && metadata.type_name == type_name
+                {
+                    dependants.push(format!(
+                        "unit process '{}' {} '{}' metadata",
+                        unit_process.name, kind, data.name
+                    ));
+                }
+            }
 
-                    c_type.check_data(custom_types.clone(), &mut internal_types)?;
+            for property in unit_process.properties.iter() {
+                if property.type_name == type_name {
+                    dependants.push(format!(
+                        "unit process '{}' property '{}'",
+                        unit_process.name, property.name
+                    ));
                 }
             }
         }
 
-        Ok(internal_types)
+        for property in self.properties.iter() {
+            if property.type_name == type_name {
+                dependants.push(format!("property '{}'", property.name));
+            }
+        }
+
+        dependants
     }
 
-    fn check_value(
-        &self,
-        data: Value,
-        custom_types: &HashMap<String, Fields>,
-    ) -> Result<(), String> {
-        match self {
-            Fields::Basic(dynamic_type) => {
-                dynamic_type.deserialize(data, custom_types)?;
-            }
-            Fields::Object(hash_map) => {
-                let Some(mut data_object) = data.as_object().cloned() else {
-                    return Err("Check error: Type mismatch. Expected an object but received a different type. The data must be a JSON object.".to_owned());
-                };
+    /// Makes every element that uses the custom type `from` use `to`.
+    fn replace_type(&mut self, from: &str, to: &str) {
+        for fields in self.custom_types.values_mut() {
+            fields.replace_type(from, to);
+        }
 
-                let (len, options) = count_options(hash_map);
-                if data_object.len() < len - options || data_object.len() > len {
-                    return Err(format!(
-                        "Check error: Field count mismatch. Expected between {} and {} fields but received {}. The data object must match the type definition.",
-                        len - options, len, data_object.len()
-                    ));
+        for unit_process in self.unit_process.iter_mut() {
+            for data in unit_process
+                .inputs
+                .iter_mut()
+                .chain(unit_process.outputs.iter_mut())
+            {
+                if data.type_name == from {
+                    data.type_name = to.to_owned();
                 }
 
-                for (custom_type_name, custom_type_type) in hash_map.clone() {
-                    if let Some(field_type) = data_object.remove(&custom_type_name) {
-                        custom_type_type.deserialize(field_type, custom_types)?;
-                    } else if !custom_type_type.is_option() {
-                        return Err(format!(
-                            "Check error: Missing required field '{}'. This field is required by the type definition and cannot be omitted.",
-                            custom_type_name
-                        ));
-                    };
+                if let Some(metadata) = &mut data.metadata
+                    && metadata.type_name == from
+                {
+                    metadata.type_name = to.to_owned();
                 }
+            }
 
-                if !data_object.is_empty() {
-                    let extra_fields: Vec<String> = data_object.keys().cloned().collect();
-                    return Err(format!(
-                        "Check error: Unexpected fields found: {:?}. These fields are not defined in the type schema and should be removed.",
-                        extra_fields
-                    ));
+            for property in unit_process.properties.iter_mut() {
+                if property.type_name == from {
+                    property.type_name = to.to_owned();
                 }
             }
         }
 
-        Ok(())
+        for property in self.properties.iter_mut() {
+            if property.type_name == from {
+                property.type_name = to.to_owned();
+            }
+        }
     }
-}
 
-#[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub enum DynamicType {
-    String,
-    i64,
-    u64,
-    f64,
-    bool,
-    Vec(Box<DynamicType>),
-    Enum(HashMap<String, DynamicType>),
-    Option(Box<DynamicType>),
-    Type(String),
-    Dummy,
-}
+    /// Replaces the stored content of the element targeted by `migration`.
+    fn migrate(&mut self, migration: Migration) -> Result<(), Error> {
+        match migration {
+            Migration::Data {
+                unit_process,
+                name,
+                content,
+            } => {
+                let data = self.find_data(&unit_process, &name)?;
+                data.content = content;
+            }
+            Migration::Metadata {
+                unit_process,
+                name,
+                content,
+            } => {
+                let data = self.find_data(&unit_process, &name)?;
+                let Some(metadata) = &mut data.metadata else {
+                    return Err(Error::new(
+                        ErrorKind::NotFound,
+                        format!(
+                            "ModifyTypes error: Cannot migrate the metadata of data element '{}'. The element has no metadata.",
+                            name
+                        ),
+                    )
+                    .element(&name)
+                    .unit_process(&unit_process));
+                };
+                metadata.content = content;
+            }
+            Migration::Property {
+                unit_process,
+                name,
+                content,
+            } => {
+                let properties = if let Some(unit_process) = &unit_process {
+                    &mut self.find_unit_process(unit_process)?.properties
+                } else {
+                    &mut self.properties
+                };
 
-impl DynamicType {
-    fn is_option(&self) -> bool {
-        matches!(self, DynamicType::Option(_))
-    }
+                let Some(property) = properties.iter_mut().find(|p| p.name == name) else {
+                    let error = Error::new(
+                        ErrorKind::NotFound,
+                        format!(
+                            "ModifyTypes error: Cannot migrate property '{}'. This property does not exist.",
+                            name
+                        ),
+                    )
+                    .element(&name);
 
-    fn deserialize(
-        &self,
-        value: Value,
-        custom_types: &HashMap<String, Fields>,
-    ) -> Result<(), String> {
-        match self {
-            DynamicType::String => {
-                if value.as_str().is_none() {
-                    return Err(format!("Deserialization error: Expected a String but received '{}'. Please provide a valid string value.", value));
-                }
+                    return Err(match unit_process {
+                        Some(unit_process) => error.unit_process(&unit_process),
+                        None => error,
+                    });
+                };
+                property.content = content;
             }
-            DynamicType::u64 => {
-                if value.as_u64().is_none() {
-                    return Err(format!("Deserialization error: Expected an unsigned 64-bit integer (u64) but received '{}'. Please provide a valid non-negative integer.", value));
+            Migration::RenameField {
+                type_name,
+                from,
+                to,
+            } => {
+                let mut fields = self.object_type(&type_name)?;
+                let Some(field_type) = fields.remove(&from) else {
+                    return Err(field_not_found(&type_name, &from));
+                };
+
+                if fields.contains_key(&to) {
+                    return Err(Error::new(
+                        ErrorKind::Duplicate,
+                        format!(
+                            "ModifyTypes error: Cannot rename field '{}' to '{}'. Type '{}' already has a field named '{}'.",
+                            from, to, type_name, to
+                        ),
+                    )
+                    .element(&type_name)
+                    .at_field(&to));
                 }
+                fields.insert(to.clone(), field_type);
+
+                self.rewrite_values(&type_name, &mut |value| {
+                    if let Some(object) = value.as_object_mut()
+                        && let Some(field_value) = object.remove(&from)
+                    {
+                        object.insert(to.clone(), field_value);
+                    }
+                });
+                self.set_object_type(type_name, fields)?;
             }
-            DynamicType::f64 => {
-                if value.as_f64().is_none() {
-                    return Err(format!("Deserialization error: Expected a 64-bit floating point number (f64) but received '{}'. Please provide a valid decimal number.", value));
+            Migration::AddField {
+                type_name,
+                field,
+                field_type,
+                default,
+            } => {
+                let mut fields = self.object_type(&type_name)?;
+                if fields.contains_key(&field) {
+                    return Err(Error::new(
+                        ErrorKind::Duplicate,
+                        format!(
+                            "ModifyTypes error: Cannot add field '{}'. Type '{}' already has a field with this name.",
+                            field, type_name
+                        ),
+                    )
+                    .element(&type_name)
+                    .at_field(&field));
                 }
+                fields.insert(field.clone(), field_type);
+
+                self.rewrite_values(&type_name, &mut |value| {
+                    if let Some(object) = value.as_object_mut() {
+                        object
+                            .entry(field.clone())
+                            .or_insert_with(|| default.clone());
+                    }
+                });
+                self.set_object_type(type_name, fields)?;
             }
-            DynamicType::i64 => {
-                if value.as_i64().is_none() {
-                    return Err(format!("Deserialization error: Expected a signed 64-bit integer (i64) but received '{}'. Please provide a valid integer.", value));
+            Migration::DropField { type_name, field } => {
+                let mut fields = self.object_type(&type_name)?;
+                if fields.remove(&field).is_none() {
+                    return Err(field_not_found(&type_name, &field));
                 }
+
+                self.rewrite_values(&type_name, &mut |value| {
+                    if let Some(object) = value.as_object_mut() {
+                        object.remove(&field);
+                    }
+                });
+                self.set_object_type(type_name, fields)?;
             }
-            DynamicType::bool => {
-                if value.as_bool().is_none() {
-                    return Err(format!("Deserialization error: Expected a boolean (true/false) but received '{}'. Please provide a valid boolean value.", value));
-                }
-            }
-            DynamicType::Vec(vec_type) => {
-                let Some(vec_dynamic) = value.as_array() else {
-                    return Err(format!("Deserialization error: Expected an array but received '{}'. Please provide a valid JSON array.", value));
+            Migration::WrapOption { type_name, field } => {
+                let mut fields = self.object_type(&type_name)?;
+                let Some(field_type) = fields.remove(&field) else {
+                    return Err(field_not_found(&type_name, &field));
                 };
-                for val in vec_dynamic.clone() {
-                    vec_type.deserialize(val, custom_types)?;
-                }
+                let field_type = match field_type {
+                    DynamicType::Annotated(c_type, annotations) => {
+                        DynamicType::Annotated(Box::new(DynamicType::Option(c_type)), annotations)
+                    }
+                    c_type => DynamicType::Option(Box::new(c_type)),
+                };
+                fields.insert(field, field_type);
+
+                // Every value of the field is a valid value of its Option.
+                self.set_object_type(type_name, fields)?;
             }
-            DynamicType::Enum(enum_type) => {
-                if let Some(obj_dynamic) = value.as_object().cloned() {
-                    if obj_dynamic.len() != 1 {
-                        return Err(format!(
-                            "Deserialization error: Invalid enum object. Expected exactly one field but received {}. Enum values must be represented as a single-field object.",
-                            obj_dynamic.len()
-                        ));
+            Migration::RenameVariant {
+                type_name,
+                field,
+                from,
+                to,
+            } => {
+                let mut fields = self.migration_type(&type_name)?;
+                let enum_type = match (fields.unannotated_mut(), &field) {
+                    (Fields::Basic(dynamic_type), None) => &mut **dynamic_type,
+                    (Fields::Object(hash_map), Some(field)) => hash_map
+                        .get_mut(field)
+                        .ok_or_else(|| field_not_found(&type_name, field))?,
+                    _ => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidType,
+                            format!(
+                                "ModifyTypes error: Cannot rename variant '{}' of type '{}'. 'field' must be given for object types and omitted for basic types.",
+                                from, type_name
+                            ),
+                        )
+                        .element(&type_name));
                     }
+                };
 
-                    for (value_name, value_val) in obj_dynamic {
-                        let Some(type_dyn) = enum_type.get(&value_name) else {
-                            return Err(format!(
-                                "Deserialization error: Unknown enum variant '{}'. Valid variants are: {:?}",
-                                value_name, enum_type.keys().collect::<Vec<_>>()
-                            ));
-                        };
+                let enum_type = match enum_type.unannotated_mut() {
+                    DynamicType::Option(option) => &mut **option,
+                    dynamic_type => dynamic_type,
+                };
 
-                        type_dyn.deserialize(value_val, custom_types)?;
-                    }
-                } else if let Some(obj_dynamic) = value.as_str() {
-                    if let Some(DynamicType::Dummy) = enum_type.get(obj_dynamic) {
-                        // Ok
-                    } else {
-                        return Err(format!(
-                            "Deserialization error: Unknown enum variant '{}'. Valid variants are: {:?}",
-                            obj_dynamic, enum_type.keys().collect::<Vec<_>>()
-                        ));
-                    }
-                } else {
-                    return Err(format!("Deserialization error: Cannot deserialize value '{}' as Enum. Enum values must be either a single-field object or a string (for variants without data).", value));
+                let DynamicType::Enum(variants) = enum_type else {
+                    return Err(Error::new(
+                        ErrorKind::InvalidType,
+                        format!(
+                            "ModifyTypes error: Cannot rename variant '{}' of type '{}'. The renamed element is not an Enum.",
+                            from, type_name
+                        ),
+                    )
+                    .element(&type_name));
                 };
-            }
-            DynamicType::Type(c_type) => {
-                let Some(obj_type) = custom_types.get(c_type) else {
-                    return Err(format!(
-                        "Deserialization error: Custom type '{}' is not defined in the schema. Please ensure the type is defined before using it.",
-                        c_type
-                    ));
+
+                let Some(variant_type) = variants.remove(&from) else {
+                    return Err(Error::new(
+                        ErrorKind::NotFound,
+                        format!(
+                            "ModifyTypes error: Cannot rename variant '{}' of type '{}'. This variant does not exist.",
+                            from, type_name
+                        ),
+                    )
+                    .element(&type_name)
+                    .received(&from));
                 };
 
-                match obj_type {
-                    Fields::Basic(type_dyn) => {
-                        type_dyn.deserialize(value, custom_types)?;
-                    }
-                    Fields::Object(hash_map) => {
-                        let Some(mut obj_dynamic) = value.as_object().cloned() else {
-                            return Err(format!("Deserialization error: Expected an object for custom type '{}' but received '{}'. Please provide a valid JSON object.", c_type, value));
-                        };
+                if variants.contains_key(&to) {
+                    return Err(Error::new(
+                        ErrorKind::Duplicate,
+                        format!(
+                            "ModifyTypes error: Cannot rename variant '{}' to '{}'. Type '{}' already has a variant named '{}'.",
+                            from, to, type_name, to
+                        ),
+                    )
+                    .element(&type_name)
+                    .received(&to));
+                }
+                variants.insert(to.clone(), variant_type);
 
-                        let (len, options) = count_options(hash_map);
-                        if obj_dynamic.len() < len - options || obj_dynamic.len() > len {
-                            return Err(format!(
-                                "Deserialization error: Field count mismatch for custom type '{}'. Expected between {} and {} fields but received {}. Please check the type definition.",
-                                c_type, len - options, len, obj_dynamic.len()
-                            ));
-                        }
+                self.rewrite_values(&type_name, &mut |value| {
+                    let value = match &field {
+                        Some(field) => value.get_mut(field),
+                        None => Some(value),
+                    };
 
-                        for (type_field, type_dyn) in hash_map.clone() {
-                            if let Some(value) = obj_dynamic.remove(&type_field) {
-                                type_dyn.deserialize(value, custom_types)?;
-                            } else if !type_dyn.is_option() {
-                                return Err(format!(
-                                    "Deserialization error: Missing required field '{}' in custom type '{}'. This field is required and cannot be omitted.",
-                                    type_field, c_type
-                                ));
-                            };
+                    match value {
+                        Some(Value::String(variant)) if *variant == from => {
+                            *variant = to.clone();
                         }
-
-                        if !obj_dynamic.is_empty() {
-                            let extra_fields: Vec<String> = obj_dynamic.keys().cloned().collect();
-                            return Err(format!(
-                                "Deserialization error: Unexpected fields {:?} found in custom type '{}'. These fields are not defined in the type schema.",
-                                extra_fields, c_type
-                            ));
+                        Some(Value::Object(object)) => {
+                            if let Some(variant_value) = object.remove(&from) {
+                                object.insert(to.clone(), variant_value);
+                            }
                         }
+                        _ => {}
                     }
-                };
-            }
-            DynamicType::Option(option) => {
-                if value.is_null() {
-                    return Ok(());
-                } else {
-                    return option.deserialize(value, custom_types);
-                }
-            }
-            DynamicType::Dummy => {
-                return Err("Check error: Dummy type encountered during deserialization. Dummy types are placeholders and cannot be used for actual data.".to_owned());
+                });
+                add_types(self, vec![(type_name, fields)])?;
             }
         }
 
         Ok(())
     }
 
-    fn check_data(
-        &self,
-        custom_types: HashMap<String, Fields>,
-        internal_types: &mut Vec<String>,
-    ) -> Result<(), String> {
-        match self {
-            DynamicType::Vec(c_type) | DynamicType::Option(c_type) => {
-                if let DynamicType::Dummy | DynamicType::Option(..) = **c_type {
-                    return Err("Check error: Invalid nested type. Vec and Option types cannot contain Dummy or nested Option types. Please use a concrete type.".to_owned());
-                }
-                c_type.check_data(custom_types, internal_types)?;
-            }
-            DynamicType::Enum(enum_type) => {
-                for (type_field, type_dyn) in enum_type.clone() {
-                    if type_field.is_empty() {
-                        return Err("Check error: Enum variant name cannot be empty. All enum variants must have a non-empty name.".to_owned());
-                    }
+    fn migration_type(&self, type_name: &str) -> Result<Fields, Error> {
+        self.custom_types.get(type_name).cloned().ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!(
+                    "ModifyTypes error: Cannot migrate type '{}'. This type does not exist in the schema.",
+                    type_name
+                ),
+            )
+            .element(type_name)
+        })
+    }
 
-                    if let DynamicType::Option(..) = type_dyn {
-                        return Err(format!("Check error: Enum variant '{}' cannot be of type Option. Use a unit variant (Dummy) for variants without data instead.", type_field));
-                    }
+    fn object_type(&self, type_name: &str) -> Result<HashMap<String, DynamicType>, Error> {
+        match self.migration_type(type_name)?.unannotated() {
+            Fields::Object(hash_map) => Ok(hash_map.clone()),
+            Fields::Basic(..) | Fields::Annotated { .. } => Err(Error::new(
+                ErrorKind::InvalidType,
+                format!(
+                    "ModifyTypes error: Cannot migrate the fields of type '{}'. It is a basic type, field operations need an object type.",
+                    type_name
+                ),
+            )
+            .element(type_name)),
+        }
+    }
 
-                    type_dyn.check_data(custom_types.clone(), internal_types)?;
-                }
-            }
-            DynamicType::Type(c_type) => {
-                if c_type.is_empty() {
-                    return Err("Check error: Custom type name cannot be empty. Please provide a valid type name.".to_string());
-                }
+    /// Redefines `type_name` with new fields, keeping its annotations.
+    fn set_object_type(
+        &mut self,
+        type_name: String,
+        fields: HashMap<String, DynamicType>,
+    ) -> Result<(), Error> {
+        let fields = match self.custom_types.get(&type_name) {
+            Some(Fields::Annotated { annotations, .. }) => Fields::Annotated {
+                fields: Box::new(Fields::Object(fields)),
+                annotations: annotations.clone(),
+            },
+            _ => Fields::Object(fields),
+        };
 
-                if !custom_types.contains_key(c_type) {
-                    return Err(format!("Check error: Custom type '{}' is not defined. Please ensure the type is defined before referencing it.", c_type));
+        add_types(self, vec![(type_name, fields)])
+    }
+
+    /// Calls `f` with every stored value of the custom type `type_name`,
+    /// including the default values of the custom types, the values are found
+    /// with the current custom types.
+    fn rewrite_values(&mut self, type_name: &str, f: &mut dyn FnMut(&mut Value)) {
+        let custom_types = self.custom_types.clone();
+        let mut contents: Vec<(&String, &mut Value)> = vec![];
+
+        for unit_process in self.unit_process.iter_mut() {
+            for data in unit_process
+                .inputs
+                .iter_mut()
+                .chain(unit_process.outputs.iter_mut())
+            {
+                contents.push((&data.type_name, &mut data.content));
+                if let Some(metadata) = &mut data.metadata {
+                    contents.push((&metadata.type_name, &mut metadata.content));
                 }
+            }
 
-                internal_types.push(c_type.clone());
+            for property in unit_process.properties.iter_mut() {
+                contents.push((&property.type_name, &mut property.content));
             }
-            _ => {}
         }
 
-        Ok(())
-    }
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug)]
-struct UnitData {
-    pub name: String,
-    pub outputs: Option<Vec<RegisterData>>,
-    pub inputs: Option<Vec<RegisterData>>,
-    pub properties: Option<Vec<Properties>>,
-}
+        for property in self.properties.iter_mut() {
+            contents.push((&property.type_name, &mut property.content));
+        }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-struct UnitProcess {
-    pub name: String,
-    pub inputs: Vec<Data>,
-    pub outputs: Vec<Data>,
-    pub properties: Vec<Properties>,
-}
+        for (element_type, content) in contents {
+            DynamicType::Type(element_type.clone()).visit(content, &custom_types, type_name, f);
+        }
 
-impl UnitProcess {
-    pub fn check_data(&self, custom_types: &HashMap<String, Fields>) -> Result<(), String> {
-        if self.name.is_empty() {
-            return Err("Check error: Unit process name cannot be empty. Please provide a valid name for the unit process.".to_owned());
+        for fields in self.custom_types.values_mut() {
+            if let Fields::Object(hash_map) = fields.unannotated_mut() {
+                for c_type in hash_map.values_mut() {
+                    if let DynamicType::Default(c_type, default) = c_type.unannotated_mut() {
+                        c_type.visit(default, &custom_types, type_name, f);
+                    }
+                }
+            }
         }
+    }
 
-        let mut names = vec![];
+    fn find_unit_process(&mut self, name: &str) -> Result<&mut UnitProcess, Error> {
+        self.unit_process
+            .iter_mut()
+            .find(|x| x.name == name)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::NotFound,
+                    format!(
+                        "ModifyTypes error: Cannot migrate unit process '{}'. This unit process does not exist in the production system.",
+                        name
+                    ),
+                )
+                .unit_process(name)
+            })
+    }
 
-        for i in self.inputs.iter() {
-            i.check_data(custom_types)?;
-            names.push(i.name.clone());
-        }
+    fn find_data(&mut self, unit_process: &str, name: &str) -> Result<&mut Data, Error> {
+        let unit = self.find_unit_process(unit_process)?;
+        unit.inputs
+            .iter_mut()
+            .chain(unit.outputs.iter_mut())
+            .find(|x| x.name == name)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::NotFound,
+                    format!(
+                        "ModifyTypes error: Cannot migrate data element '{}'. It is not an input or output of unit process '{}'.",
+                        name, unit_process
+                    ),
+                )
+                .element(name)
+                .unit_process(unit_process)
+            })
+    }
 
-        for o in self.outputs.iter() {
-            o.check_data(custom_types)?;
-            names.push(o.name.clone());
+    /// Checks that every link connects an existing output to an existing
+    /// input of the same type, and that no link is repeated.
+    fn check_links(&self) -> Result<(), Error> {
+        let mut errors = Errors::default();
+        let mut seen = HashSet::new();
+
+        for link in self.links.iter() {
+            if !seen.insert(link.ends()) {
+                errors.push(
+                    Error::new(
+                        ErrorKind::Duplicate,
+                        format!(
+                            "Check error: Duplicate link '{}'. Each output can only be linked once to the same input.",
+                            link
+                        ),
+                    )
+                    .element(&link.output)
+                    .unit_process(&link.from),
+                );
+                continue;
+            }
+
+            let output = errors.check(self.link_end(link, &link.from, &link.output, true));
+            let input = errors.check(self.link_end(link, &link.to, &link.input, false));
+            if let (Some(output), Some(input)) = (output, input)
+                && output.type_name != input.type_name
+            {
+                errors.push(
+                    Error::new(
+                        ErrorKind::TypeMismatch,
+                        format!(
+                            "Check error: Invalid link '{}'. The output is of type '{}' but the input is of type '{}', linked elements must have the same type.",
+                            link, output.type_name, input.type_name
+                        ),
+                    )
+                    .expected(&input.type_name)
+                    .received(&output.type_name)
+                    .element(&link.input)
+                    .unit_process(&link.to),
+                );
+            }
         }
 
-        let hash_name: HashSet<String> = HashSet::from_iter(names.iter().cloned());
+        errors.finish()?;
 
-        if hash_name.len() != self.outputs.len() + self.inputs.len() {
-            return Err(format!(
-                "Check error: Duplicate names detected in unit process '{}'. Input and output names must be unique across both lists.",
-                self.name
-            ));
-        }
+        self.check_flow()
+    }
 
-        let mut properties_name = vec![];
-        for p in self.properties.iter() {
-            p.check_data(custom_types)?;
-            properties_name.push(p.name.clone());
+    /// Unit processes fed by each unit process, recirculation links are left
+    /// out.
+    fn flow_graph(&self) -> HashMap<String, Vec<String>> {
+        let mut graph: HashMap<String, Vec<String>> = HashMap::new();
+        for link in self.links.iter().filter(|link| !link.recirculation) {
+            graph
+                .entry(link.from.clone())
+                .or_default()
+                .push(link.to.clone());
         }
 
-        let hash_pro_name: HashSet<String> = HashSet::from_iter(properties_name.iter().cloned());
-        if hash_name.len() != self.outputs.len() + self.inputs.len() {
-            return Err(format!(
-                "Check error: Duplicate names detected in unit process '{}'. Input and output names must be unique across both lists.",
-                self.name
-            ));
-        }
+        graph
+    }
 
-        if hash_pro_name.len() != self.properties.len() {
-            return Err(format!(
-                "Check error: Duplicate property names detected in unit process '{}'. Each property must have a unique name.",
-                self.name
-            ));
+    /// Checks that the material flow has no loops other than the ones closed
+    /// by recirculation links.
+    fn check_flow(&self) -> Result<(), Error> {
+        let graph = self.flow_graph();
+        let names = self.unit_process.iter().map(|x| &x.name);
+        if let Some(unit_process) = find_cycle(names, &graph) {
+            return Err(Error::new(
+                ErrorKind::CircularDependency,
+                format!(
+                    "Check error: Circular material flow detected from unit process '{}'. Unit processes cannot feed themselves, mark the link that closes an intended loop as 'recirculation'.",
+                    unit_process
+                ),
+            )
+            .unit_process(unit_process));
         }
 
         Ok(())
     }
 
-    pub fn register_data(
-        &mut self,
-        unit: UnitData,
-        custom_types: &HashMap<String, Fields>,
-    ) -> Result<(), String> {
-        if unit.inputs.is_none() && unit.outputs.is_none() {
-            return Err(format!(
-                "Check error: Cannot register data for unit '{}'. At least one of 'inputs' or 'outputs' must be provided.",
-                unit.name
-            ));
-        }
-
-        if let Some(inputs) = unit.inputs {
-            if inputs.is_empty() {
-                return Err(format!(
-                    "Check error: Empty inputs array for unit '{}'. If 'inputs' is provided, it must contain at least one input definition.",
-                    unit.name
-                ));
+    /// Unit processes ordered so that each one comes after the unit
+    /// processes that feed it. Unrelated unit processes keep the order in
+    /// which they are defined, so the order is deterministic.
+    pub fn topological_order(&self) -> Vec<String> {
+        let graph = self.flow_graph();
+        let mut pending: HashMap<&str, usize> = self
+            .unit_process
+            .iter()
+            .map(|x| (x.name.as_str(), 0))
+            .collect();
+        for to in graph.values().flatten() {
+            if let Some(count) = pending.get_mut(to.as_str()) {
+                *count += 1;
             }
+        }
 
-            let mut updates: usize = 0;
-
-            for element_state in self.inputs.iter_mut() {
-                for element_unit in inputs.clone() {
-                    if element_state.name == element_unit.name {
-                        let element_unit = Data::from(element_unit);
-                        element_state.register_data(element_unit, custom_types)?;
-                        updates += 1;
-                    }
+        let mut order = vec![];
+        while let Some(unit_process) = self
+            .unit_process
+            .iter()
+            .find(|x| pending.get(x.name.as_str()) == Some(&0))
+        {
+            pending.remove(unit_process.name.as_str());
+            for to in graph.get(&unit_process.name).into_iter().flatten() {
+                if let Some(count) = pending.get_mut(to.as_str()) {
+                    *count -= 1;
                 }
             }
-
-            if updates != inputs.len() {
-                let unmatched = inputs.len() - updates;
-                return Err(format!(
-                    "Check error: Failed to update {} input(s) in unit process '{}'. {} input name(s) do not match any defined inputs in the unit process.",
-                    unmatched, self.name, unmatched
-                ));
-            }
+            order.push(unit_process.name.clone());
         }
 
-        if let Some(outputs) = unit.outputs {
-            if outputs.is_empty() {
-                return Err(format!(
-                    "Check error: Empty outputs array for unit '{}'. If 'outputs' is provided, it must contain at least one output definition.",
-                    unit.name
-                ));
-            }
+        order
+    }
 
-            let mut updates: usize = 0;
+    /// Copies the registered outputs into the inputs they are linked to with
+    /// `propagate`, following the order of the material flow. An input can
+    /// not be registered and propagated in the same event.
+    fn propagate(&mut self, registered: &[(String, String)], is_owner: bool) -> Result<(), Error> {
+        let mut errors = Errors::default();
+        let mut received: Vec<String> = vec![];
+
+        for unit_process in self.topological_order() {
+            let links: Vec<Link> = self
+                .links
+                .iter()
+                .filter(|link| {
+                    link.propagate
+                        && link.from == unit_process
+                        && registered.contains(&(link.from.clone(), link.output.clone()))
+                })
+                .cloned()
+                .collect();
+
+            for link in links {
+                if registered.contains(&(link.to.clone(), link.input.clone())) {
+                    errors.push(
+                        Error::new(
+                            ErrorKind::Duplicate,
+                            format!(
+                                "RegisterData error: Input '{}' is registered in the event and also propagated by link '{}'. Register only the output.",
+                                link.input, link
+                            ),
+                        )
+                        .element(&link.input)
+                        .unit_process(&link.to),
+                    );
+                    continue;
+                }
 
-            for element_state in self.outputs.iter_mut() {
-                for element_unit in outputs.clone() {
-                    if element_state.name == element_unit.name {
-                        let element_unit = Data::from(element_unit);
-                        element_state.register_data(element_unit, custom_types)?;
-                        updates += 1;
+                let Some(content) = errors
+                    .check(self.link_end(&link, &link.from, &link.output, true))
+                    .map(|output| output.content.clone())
+                else {
+                    continue;
+                };
+                let Some(unit) = self.unit_process.iter_mut().find(|x| x.name == link.to) else {
+                    continue;
+                };
+                // The issuer writes into the receiving unit process as if it
+                // registered the input itself.
+                if errors
+                    .check(unit.check_writer(self.permissions.register_data, is_owner))
+                    .is_none()
+                {
+                    continue;
+                }
+                unit.start_batch(std::slice::from_ref(&link.input), &[]);
+                let Some(input) = unit.inputs.iter_mut().find(|x| x.name == link.input) else {
+                    continue;
+                };
+
+                if errors
+                    .check(
+                        check_data(&input.type_name, content.clone(), &self.custom_types)
+                            .map_err(|e| e.element(&link.input).unit_process(&link.to)),
+                    )
+                    .is_some()
+                {
+                    input.content = content;
+                    input.registered = true;
+                    if !received.contains(&link.to) {
+                        received.push(link.to.clone());
                     }
                 }
             }
+        }
 
-            if updates != outputs.len() {
-                let unmatched = outputs.len() - updates;
-                return Err(format!(
-                    "Check error: Failed to update {} output(s) in unit process '{}'. {} output name(s) do not match any defined outputs in the unit process.",
-                    unmatched, self.name, unmatched
-                ));
+        // The propagated inputs are not registered through the unit process.
+        for unit_process in self.unit_process.iter() {
+            if received.contains(&unit_process.name) {
+                errors.check(
+                    unit_process
+                        .check_balance(&self.custom_types)
+                        .map_err(|e| e.unit_process(&unit_process.name)),
+                );
             }
         }
 
-        if let Some(properties) = unit.properties {
-            if properties.is_empty() {
-                return Err(format!(
-                    "Check error: Empty properties array for unit '{}'. If 'properties' is provided, it must contain at least one property definition.",
-                    unit.name
-                ));
-            }
+        errors.finish()
+    }
 
-            let mut updates: usize = 0;
+    /// Output (or input) `name` of `unit_process` at one end of `link`.
+    fn link_end(
+        &self,
+        link: &Link,
+        unit_process: &str,
+        name: &str,
+        output: bool,
+    ) -> Result<&Data, Error> {
+        let Some(unit) = self.unit_process.iter().find(|x| x.name == unit_process) else {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!(
+                    "Check error: Invalid link '{}'. Unit process '{}' does not exist in the production system.",
+                    link, unit_process
+                ),
+            )
+            .unit_process(unit_process));
+        };
 
-            for data in self.properties.iter_mut() {
-                for unit_data in properties.clone() {
-                    if data.name == unit_data.name {
-                        data.register_data(unit_data, custom_types)?;
-                        updates += 1;
-                    }
+        let (elements, kind) = if output {
+            (&unit.outputs, "an output")
+        } else {
+            (&unit.inputs, "an input")
+        };
+        elements.iter().find(|x| x.name == name).ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!(
+                    "Check error: Invalid link '{}'. '{}' is not {} of unit process '{}'.",
+                    link, name, kind, unit_process
+                ),
+            )
+            .element(name)
+            .unit_process(unit_process)
+        })
+    }
+
+    /// Checks the stored content of every data element and property against
+    /// the current custom types.
+    fn check_content(&self) -> Result<(), Error> {
+        let mut errors = Errors::default();
+
+        for unit_process in self.unit_process.iter() {
+            for data in unit_process
+                .inputs
+                .iter()
+                .chain(unit_process.outputs.iter())
+            {
+                if let Some(metadata) = &data.metadata {
+                    errors.check(
+                        check_data(
+                            &metadata.type_name,
+                            metadata.content.clone(),
+                            &self.custom_types,
+                        )
+                        .map_err(|e| e.element(&data.name).unit_process(&unit_process.name)),
+                    );
                 }
+
+                errors.check(
+                    check_data(&data.type_name, data.content.clone(), &self.custom_types)
+                        .map_err(|e| e.element(&data.name).unit_process(&unit_process.name)),
+                );
             }
 
-            if updates != properties.len() {
-                let unmatched = properties.len() - updates;
-                return Err(format!(
-                    "Check error: Failed to update {} propert(y/ies) in unit process '{}'. {} property name(s) do not match any defined properties in the unit process.",
-                    unmatched, self.name, unmatched
-                ));
+            for property in unit_process.properties.iter() {
+                errors.check(
+                    check_data(
+                        &property.type_name,
+                        property.content.clone(),
+                        &self.custom_types,
+                    )
+                    .map_err(|e| e.element(&property.name).unit_process(&unit_process.name)),
+                );
             }
         }
 
-        Ok(())
-    }
-}
+        for property in self.properties.iter() {
+            errors.check(
+                check_data(
+                    &property.type_name,
+                    property.content.clone(),
+                    &self.custom_types,
+                )
+                .map_err(|e| e.element(&property.name)),
+            );
+        }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-struct Target {
-    pub governance_id: String,
-    pub subject_id: String,
-    pub unit_process: String,
+        errors.finish()
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
-struct Properties {
-    pub name: String,
-    pub type_name: String,
-    pub content: Value,
+#[serde(untagged)]
+pub enum Fields {
+    Basic(Box<DynamicType>),
+    Object(HashMap<String, DynamicType>),
+    /// A type with annotations. It must be the last variant, a JSON
+    /// object with a 'fields' and an 'annotations' key is not a valid
+    /// `Object`.
+    Annotated {
+        fields: Box<Fields>,
+        annotations: Annotations,
+    },
 }
 
-impl Properties {
-    fn check_data(&self, custom_types: &HashMap<String, Fields>) -> Result<(), String> {
-        if self.name.is_empty() {
-            return Err("Check error: Property name cannot be empty. Please provide a valid name for the property.".to_owned());
+impl Fields {
+    /// The type without its annotations.
+    fn unannotated(&self) -> &Fields {
+        match self {
+            Fields::Annotated { fields, .. } => fields,
+            fields => fields,
         }
+    }
 
-        check_data(&self.type_name, self.content.clone(), custom_types)
+    fn unannotated_mut(&mut self) -> &mut Fields {
+        match self {
+            Fields::Annotated { fields, .. } => fields,
+            fields => fields,
+        }
     }
 
-    fn register_data(
-        &mut self,
-        data: Self,
-        custom_types: &HashMap<String, Fields>,
-    ) -> Result<(), String> {
-        register_data(
-            &self.name,
-            &self.type_name,
-            custom_types,
-            &data.name,
-            &data.type_name,
-            data.content.clone(),
-        )?;
+    fn check_data(&self, custom_types: HashMap<String, Fields>) -> Result<Vec<String>, Error> {
+        let mut internal_types: Vec<String> = vec![];
+        match self {
+            Fields::Basic(dynamic_type) => {
+                if let DynamicType::Dummy | DynamicType::Option(..) | DynamicType::Type(..) =
+                    **dynamic_type
+                {
+                    return Err(Error::new(ErrorKind::InvalidType, "Check error: Invalid basic type. A 'Basic' field cannot be Dummy, Option, or Type. Use 'Object' for complex types or specify a concrete type."));
+                }
 
-        self.content = data.content;
+                dynamic_type.check_data(custom_types.clone(), &mut internal_types)?;
+            }
+            Fields::Object(hash_map) => {
+                if hash_map.is_empty() {
+                    return Err(Error::new(ErrorKind::InvalidType, "Check error: Object fields cannot be empty. An object type must contain at least one field."));
+                }
 
-        Ok(())
-    }
-}
+                let mut errors = Errors::default();
+                for (field, c_type) in hash_map.iter() {
+                    let c_type = c_type.unannotated();
+                    if let DynamicType::Dummy = c_type {
+                        errors.push(Error::new(ErrorKind::InvalidType, format!("Check error: Field '{}' has invalid type. Object fields cannot be of type 'Dummy'. Please specify a concrete type.", field)).at_field(field));
+                        continue;
+                    }
+                    if field.is_empty() {
+                        errors.push(Error::new(ErrorKind::EmptyName, "Check error: Field name cannot be empty. All object fields must have a non-empty name."));
+                        continue;
+                    }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-struct Data {
-    pub name: String,
-    pub type_name: String,
-    pub metadata: Option<Metadata>,
-    pub content: Value,
-    pub targets: Option<Vec<Target>>,
-}
+                    if let DynamicType::Default(c_type, default) = c_type {
+                        errors.check(
+                            c_type
+                                .check_default(default, custom_types.clone(), &mut internal_types)
+                                .map_err(|e| e.at_field(field)),
+                        );
+                        continue;
+                    }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-struct Metadata {
-    pub type_name: String,
-    pub content: Value,
-}
+                    errors.check(
+                        c_type
+                            .check_data(custom_types.clone(), &mut internal_types)
+                            .map_err(|e| e.at_field(field)),
+                    );
+                }
+                errors.finish()?;
+            }
+            Fields::Annotated { fields, .. } => {
+                if let Fields::Annotated { .. } = **fields {
+                    return Err(Error::new(
+                        ErrorKind::InvalidType,
+                        "Check error: Invalid annotations. A type can only be annotated once.",
+                    ));
+                }
 
-impl From<RegisterData> for Data {
-    fn from(value: RegisterData) -> Self {
-        Data {
-            name: value.name,
-            type_name: value.type_name,
-            metadata: None,
-            content: value.content,
-            targets: value.targets,
+                internal_types = fields.check_data(custom_types)?;
+            }
         }
+
+        Ok(internal_types)
     }
-}
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-struct RegisterData {
-    name: String,
-    type_name: String,
-    content: Value,
-    targets: Option<Vec<Target>>,
-}
+    /// Whether the type uses the custom type `type_name`.
+    fn uses(&self, type_name: &str) -> bool {
+        match self {
+            Fields::Basic(dynamic_type) => dynamic_type.uses(type_name),
+            Fields::Object(hash_map) => hash_map.values().any(|c_type| c_type.uses(type_name)),
+            Fields::Annotated { fields, .. } => fields.uses(type_name),
+        }
+    }
 
-impl Data {
-    fn check_data(&self, custom_types: &HashMap<String, Fields>) -> Result<(), String> {
-        if self.targets.is_some() {
-            return Err(format!(
-                "Check error: Targets must not be set in unit process definition for element '{}'. Targets are only valid when registering data.",
-                self.name
-            ));
+    fn replace_type(&mut self, from: &str, to: &str) {
+        match self {
+            Fields::Basic(dynamic_type) => dynamic_type.replace_type(from, to),
+            Fields::Object(hash_map) => {
+                for c_type in hash_map.values_mut() {
+                    c_type.replace_type(from, to);
+                }
+            }
+            Fields::Annotated { fields, .. } => fields.replace_type(from, to),
         }
+    }
 
-        if self.name.is_empty() {
-            return Err("Check error: Data element name cannot be empty. Please provide a valid name for the data element.".to_owned());
+    fn visit(
+        &self,
+        value: &mut Value,
+        custom_types: &HashMap<String, Fields>,
+        type_name: &str,
+        f: &mut dyn FnMut(&mut Value),
+    ) {
+        match self {
+            Fields::Basic(dynamic_type) => dynamic_type.visit(value, custom_types, type_name, f),
+            Fields::Object(hash_map) => {
+                if let Some(data_object) = value.as_object_mut() {
+                    for (field, c_type) in hash_map.iter() {
+                        if let Some(field_value) = data_object.get_mut(field) {
+                            c_type.visit(field_value, custom_types, type_name, f);
+                        }
+                    }
+                }
+            }
+            Fields::Annotated { fields, .. } => fields.visit(value, custom_types, type_name, f),
         }
+    }
 
-        if let Some(metadata) = self.metadata.clone() {
-            check_data(&metadata.type_name, metadata.content.clone(), custom_types)?;
-        };
+    /// Converts the quantities inside `value` into the units of the type
+    /// and fills in the omitted fields that have a default value.
+    fn normalize(
+        &self,
+        value: &mut Value,
+        custom_types: &HashMap<String, Fields>,
+        depth: usize,
+    ) -> Result<(), Error> {
+        match self {
+            Fields::Basic(dynamic_type) => dynamic_type.normalize(value, custom_types, depth),
+            Fields::Object(hash_map) => {
+                let mut errors = Errors::default();
+                if let Some(data_object) = value.as_object_mut() {
+                    for (field, c_type) in hash_map.iter() {
+                        if let DynamicType::Default(_, default) = c_type.unannotated()
+                            && !data_object.contains_key(field)
+                        {
+                            data_object.insert(field.clone(), default.clone());
+                        }
 
-        check_data(&self.type_name, self.content.clone(), custom_types)
+                        if let Some(field_value) = data_object.get_mut(field) {
+                            errors.check(
+                                c_type
+                                    .normalize(field_value, custom_types, depth)
+                                    .map_err(|e| e.at_field(field)),
+                            );
+                        }
+                    }
+                }
+                errors.finish()
+            }
+            Fields::Annotated { fields, .. } => fields.normalize(value, custom_types, depth),
+        }
     }
 
-    fn register_data(
-        &mut self,
-        data: Self,
+    fn check_value(
+        &self,
+        data: Value,
         custom_types: &HashMap<String, Fields>,
-    ) -> Result<(), String> {
-        register_data(
-            &self.name,
-            &self.type_name,
-            custom_types,
-            &data.name,
-            &data.type_name,
-            data.content.clone(),
-        )?;
+    ) -> Result<(), Error> {
+        self.check_value_at(data, custom_types, 0)
+    }
 
-        if let Some(targets) = self.targets.clone() {
-            for t in targets {
-                if t.governance_id.is_empty()
-                    || t.subject_id.is_empty()
-                    || t.unit_process.is_empty()
-                {
-                    return Err(format!(
-                        "Check error: Invalid target configuration for data element '{}'. All target fields (governance_id, subject_id, unit_process) must be non-empty.",
-                        self.name
-                    ));
+    fn check_value_at(
+        &self,
+        data: Value,
+        custom_types: &HashMap<String, Fields>,
+        depth: usize,
+    ) -> Result<(), Error> {
+        match self {
+            Fields::Basic(dynamic_type) => dynamic_type.deserialize_at(data, custom_types, depth),
+            Fields::Object(hash_map) => {
+                let Some(mut data_object) = data.as_object().cloned() else {
+                    return Err(Error::value(ErrorKind::TypeMismatch, "Check error: Type mismatch. Expected an object but received a different type. The data must be a JSON object.", "object", &data));
+                };
+
+                let mut errors = Errors::default();
+                for (custom_type_name, custom_type_type) in hash_map.clone() {
+                    if let Some(field_type) = data_object.remove(&custom_type_name) {
+                        errors.check(
+                            custom_type_type
+                                .deserialize_at(field_type, custom_types, depth)
+                                .map_err(|e| e.at_field(&custom_type_name)),
+                        );
+                    } else if !custom_type_type.is_optional() {
+                        errors.push(
+                            Error::new(
+                                ErrorKind::MissingField,
+                                format!(
+                                    "Check error: Missing required field '{}'. This field is required by the type definition and cannot be omitted.",
+                                    custom_type_name
+                                ),
+                            )
+                            .at_field(&custom_type_name),
+                        );
+                    };
+                }
+
+                for extra_field in data_object.keys() {
+                    errors.push(
+                        Error::new(
+                            ErrorKind::UnexpectedField,
+                            format!(
+                                "Check error: Unexpected field '{}' found. This field is not defined in the type schema and should be removed.",
+                                extra_field
+                            ),
+                        )
+                        .at_field(extra_field),
+                    );
                 }
+
+                errors.finish()
             }
+            Fields::Annotated { fields, .. } => fields.check_value_at(data, custom_types, depth),
         }
+    }
 
-        self.content = data.content;
-        self.targets = data.targets;
-
-        Ok(())
+    /// JSON Schema (draft 2020-12) of the values of the type, see
+    /// `DynamicType::json_schema`.
+    pub fn json_schema(&self) -> Value {
+        match self {
+            Fields::Basic(dynamic_type) => dynamic_type.json_schema(),
+            Fields::Object(hash_map) => {
+                let mut required: Vec<&String> = hash_map
+                    .iter()
+                    .filter(|(_, c_type)| !c_type.is_optional())
+                    .map(|(field, _)| field)
+                    .collect();
+                required.sort();
+
+                let properties: serde_json::Map<String, Value> = hash_map
+                    .iter()
+                    .map(|(field, c_type)| (field.clone(), c_type.json_schema()))
+                    .collect();
+
+                json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                    "additionalProperties": false
+                })
+            }
+            Fields::Annotated {
+                fields,
+                annotations,
+            } => {
+                let mut schema = fields.json_schema();
+                annotations.annotate(&mut schema);
+                schema
+            }
+        }
     }
-}
 
-#[derive(Serialize, Deserialize, Clone)]
-enum Events {
-    ChangeProductionSystem(ChangeProductionSystem),
-    RegisterData(Vec<UnitData>),
-}
+    /// Type of a JSON Schema. Objects with `properties` become `Object`
+    /// types, their fields that are not required become `Option` or, when
+    /// they have a `default`, `Default`.
+    fn from_json_schema(schema: &Value) -> Result<Fields, Error> {
+        let Some(object) = schema.as_object() else {
+            return Err(unsupported_schema(schema));
+        };
 
-#[derive(Serialize, Deserialize, Clone)]
-enum ChangeProductionSystem {
-    Init {
-        name: String,
-        unit_process: Option<Vec<UnitProcess>>,
-        types: Option<Vec<(String, Fields)>>,
-        properties: Option<Vec<Properties>>,
-    },
-    ModifyProductionSystem {
-        name: Option<String>,
-        delete_properties: Option<Vec<String>>,
-        modify_properties: Option<Vec<(String, Properties)>>,
-        add_properties: Option<Vec<Properties>>,
-    },
-    ModifyTypes {
-        delete: Option<Vec<String>>,
-        add: Option<Vec<(String, Fields)>>,
-    },
-    ModifyUnitProcess {
-        delete: Option<Vec<String>>,
-        modify: Option<Vec<(String, UnitProcess)>>,
-        add: Option<Vec<UnitProcess>>,
-    },
+        let fields = if object.get("type") == Some(&json!("object"))
+            && object.contains_key("properties")
+        {
+            check_keywords(
+                object,
+                &["type", "properties", "required", "additionalProperties"],
+            )?;
+            if object
+                .get("additionalProperties")
+                .is_some_and(|additional| *additional != Value::Bool(false))
+            {
+                return Err(unsupported(
+                    "additionalProperties",
+                    "Objects with 'properties' cannot have other properties, use a map instead.",
+                ));
+            }
+
+            let Some(properties) = object["properties"].as_object() else {
+                return Err(Error::value(
+                    ErrorKind::TypeMismatch,
+                    "Schema error: 'properties' must be an object of schemas.",
+                    "object",
+                    &object["properties"],
+                )
+                .at_field("properties"));
+            };
+            let required: Vec<&str> = object
+                .get("required")
+                .and_then(Value::as_array)
+                .map(|required| required.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+
+            let mut errors = Errors::default();
+            let mut hash_map = HashMap::new();
+            for (field, property) in properties {
+                if let Some(c_type) = errors.check(
+                    DynamicType::from_json_field(property, required.contains(&field.as_str()))
+                        .map_err(|e| e.at_field(field).at_field("properties")),
+                ) {
+                    hash_map.insert(field.clone(), c_type);
+                }
+            }
+            for field in required
+                .iter()
+                .filter(|field| !properties.contains_key(**field))
+            {
+                errors.push(
+                    Error::new(
+                        ErrorKind::NotFound,
+                        format!(
+                            "Schema error: Required field '{}' is not defined in 'properties'.",
+                            field
+                        ),
+                    )
+                    .path("$")
+                    .at_field("required"),
+                );
+            }
+            errors.finish()?;
+
+            Fields::Object(hash_map)
+        } else {
+            Fields::Basic(Box::new(DynamicType::from_json_schema(schema)?))
+        };
+
+        Ok(match schema_annotations(object) {
+            Some(annotations) => Fields::Annotated {
+                fields: Box::new(fields),
+                annotations,
+            },
+            None => fields,
+        })
+    }
 }
 
-#[unsafe(no_mangle)]
-pub unsafe fn main_function(
-    state_ptr: i32,
-    init_state_ptr: i32,
-    event_ptr: i32,
-    is_owner: i32,
-) -> u32 {
-    sdk::execute_contract(
-        state_ptr,
-        init_state_ptr,
-        event_ptr,
-        is_owner,
-        contract_logic,
-    )
+/// Texts for the people who work with the production system, the
+/// contract only stores them and exports them in the JSON Schema.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Annotations {
+    /// Longer help text.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Short name to show instead of the identifier.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// The element should not be used anymore.
+    #[serde(default)]
+    pub deprecated: bool,
 }
 
-#[unsafe(no_mangle)]
-pub unsafe fn init_check_function(state_ptr: i32) -> u32 {
-    sdk::check_init_data(state_ptr, init_logic)
+impl Annotations {
+    /// Adds the annotations to a JSON Schema as `title`, `description` and
+    /// `deprecated`.
+    fn annotate(&self, schema: &mut Value) {
+        if let Some(label) = &self.label {
+            schema["title"] = json!(label);
+        }
+        if let Some(description) = &self.description {
+            schema["description"] = json!(description);
+        }
+        if self.deprecated {
+            schema["deprecated"] = json!(true);
+        }
+    }
 }
 
-fn init_logic(_state: &ProductionSystem, contract_result: &mut sdk::ContractInitCheck) {
-    contract_result.success = true;
+#[allow(non_camel_case_types)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum DynamicType {
+    String,
+    i64,
+    u64,
+    f64,
+    bool,
+    Vec(Box<DynamicType>),
+    Enum(HashMap<String, DynamicType>),
+    Option(Box<DynamicType>),
+    Type(String),
+    Dummy,
+    Number(NumberConstraints),
+    Text(TextConstraints),
+    /// ISO 8601 date and time with a UTC offset, `2024-09-30T14:05:00Z`.
+    Timestamp,
+    /// ISO 8601 calendar date, `2024-09-30`.
+    Date,
+    /// ISO 8601 duration in weeks, days, hours, minutes and seconds,
+    /// `P3DT12H`.
+    Duration,
+    /// Fixed-point number with at most `scale` fraction digits, given as a
+    /// JSON number or string. JSON numbers with a fraction or an exponent
+    /// keep 15 significant digits, longer values must be strings. Registered
+    /// values are stored as strings with exactly `scale` fraction digits.
+    Decimal {
+        scale: u32,
+    },
+    /// JSON object whose keys are values of the first type, given as
+    /// strings, and whose values are values of the second type.
+    Map(Box<DynamicType>, Box<DynamicType>),
+    /// JSON array with one value of each type, in order.
+    Tuple(Vec<DynamicType>),
+    /// JSON array with exactly the given number of values of the type.
+    Array(usize, Box<DynamicType>),
+    /// Object field of the type that can be omitted, the value is then
+    /// stored when registering data.
+    Default(Box<DynamicType>, Value),
+    /// Object field of the type with annotations.
+    Annotated(Box<DynamicType>, Annotations),
 }
 
-fn contract_logic(
-    context: &sdk::Context<Events>,
-    contract_result: &mut sdk::ContractResult<ProductionSystem>,
-) {
-    let state = &mut contract_result.state;
+impl DynamicType {
+    /// Whether an object field of this type can be omitted.
+    fn is_optional(&self) -> bool {
+        matches!(
+            self.unannotated(),
+            DynamicType::Option(_) | DynamicType::Default(..)
+        )
+    }
 
-    if let Events::ChangeProductionSystem(ChangeProductionSystem::Init { .. }) = context.event {
-        if state.version != 0 {
-            contract_result.error = "Contract error: Cannot emit Init event when version is not 0. The Init event can only be used to initialize a new contract (version must be 0).".to_owned();
-            return;
+    /// The type without its annotations.
+    fn unannotated(&self) -> &DynamicType {
+        match self {
+            DynamicType::Annotated(c_type, _) => c_type,
+            c_type => c_type,
         }
-    } else if state.version == 0 {
-        contract_result.error = "Contract error: The first event must be an Init event. Please initialize the contract with an Init event before performing other operations.".to_owned();
-        return;
     }
 
-    if let Events::ChangeProductionSystem(..) = context.event {
-        state.version += 1;
+    fn unannotated_mut(&mut self) -> &mut DynamicType {
+        match self {
+            DynamicType::Annotated(c_type, _) => c_type,
+            c_type => c_type,
+        }
     }
 
-    match context.event.clone() {
-        Events::ChangeProductionSystem(operation) => match operation {
-            ChangeProductionSystem::Init {
-                name,
-                unit_process,
-                properties,
-                types,
-            } => {
-                if name.is_empty() {
-                    contract_result.error = "Init error: Production system name cannot be empty. Please provide a valid name for the production system.".to_owned();
-                    return;
-                }
-
-                state.name = name;
-
-                if let Some(types) = types {
-                    if let Err(e) = add_types(state, types) {
-                        contract_result.error = e;
-                        return;
-                    };
-                }
-
-                if let Some(unit_process) = unit_process {
-                    if let Err(e) = add_unit_process(state, unit_process) {
-                        contract_result.error = e;
-                        return;
-                    }
-                }
+    /// Whether the type uses the custom type `type_name`.
+    fn uses(&self, type_name: &str) -> bool {
+        match self {
+            DynamicType::Vec(c_type)
+            | DynamicType::Option(c_type)
+            | DynamicType::Array(_, c_type)
+            | DynamicType::Default(c_type, _)
+            | DynamicType::Annotated(c_type, _) => c_type.uses(type_name),
+            DynamicType::Tuple(types) => types.iter().any(|type_dyn| type_dyn.uses(type_name)),
+            DynamicType::Map(key_type, value_type) => {
+                key_type.uses(type_name) || value_type.uses(type_name)
+            }
+            DynamicType::Enum(enum_type) => {
+                enum_type.values().any(|type_dyn| type_dyn.uses(type_name))
+            }
+            DynamicType::Type(c_type) => c_type == type_name,
+            _ => false,
+        }
+    }
 
-                if let Some(properties) = properties {
-                    if let Err(e) = add_new_properties(state, properties) {
-                        contract_result.error = e;
-                        return;
-                    }
+    fn replace_type(&mut self, from: &str, to: &str) {
+        match self {
+            DynamicType::Vec(c_type)
+            | DynamicType::Option(c_type)
+            | DynamicType::Array(_, c_type)
+            | DynamicType::Default(c_type, _)
+            | DynamicType::Annotated(c_type, _) => c_type.replace_type(from, to),
+            DynamicType::Tuple(types) => {
+                for type_dyn in types.iter_mut() {
+                    type_dyn.replace_type(from, to);
                 }
             }
-            ChangeProductionSystem::ModifyProductionSystem {
-                name,
-                delete_properties,
-                add_properties,
-                modify_properties,
-            } => {
-                if name.is_none()
-                    && delete_properties.is_none()
-                    && add_properties.is_none()
-                    && modify_properties.is_none()
-                {
-                    contract_result.error = "ModifyProductionSystem error: At least one parameter must be provided. Please specify 'name', 'delete_properties', 'add_properties', or 'modify_properties'.".to_owned();
-                    return;
+            DynamicType::Map(key_type, value_type) => {
+                key_type.replace_type(from, to);
+                value_type.replace_type(from, to);
+            }
+            DynamicType::Enum(enum_type) => {
+                for type_dyn in enum_type.values_mut() {
+                    type_dyn.replace_type(from, to);
                 }
+            }
+            DynamicType::Type(c_type) if c_type == from => *c_type = to.to_owned(),
+            _ => {}
+        }
+    }
 
-                if let Some(name) = name {
-                    if name.is_empty() {
-                        contract_result.error = "ModifyProductionSystem error: New production system name cannot be empty. Please provide a valid name.".to_owned();
-                        return;
+    fn normalize(
+        &self,
+        value: &mut Value,
+        custom_types: &HashMap<String, Fields>,
+        depth: usize,
+    ) -> Result<(), Error> {
+        let mut errors = Errors::default();
+        match self {
+            DynamicType::Vec(c_type) | DynamicType::Array(_, c_type) => {
+                if let Some(vec_dynamic) = value.as_array_mut() {
+                    for (index, val) in vec_dynamic.iter_mut().enumerate() {
+                        errors.check(
+                            c_type
+                                .normalize(val, custom_types, depth)
+                                .map_err(|e| e.at_index(index)),
+                        );
                     }
-
-                    state.name = name;
                 }
-
-                if let Some(delete_properties) = delete_properties {
-                    if delete_properties.is_empty() {
-                        contract_result.error = "ModifyProductionSystem error: The 'delete_properties' list cannot be empty. Please specify at least one property to delete.".to_owned();
-                        return;
+            }
+            DynamicType::Tuple(types) => {
+                if let Some(vec_dynamic) = value.as_array_mut() {
+                    for (index, (val, type_dyn)) in vec_dynamic.iter_mut().zip(types).enumerate() {
+                        errors.check(
+                            type_dyn
+                                .normalize(val, custom_types, depth)
+                                .map_err(|e| e.at_index(index)),
+                        );
                     }
-
-                    for name in delete_properties.iter() {
-                        if let Some(pos) = state.properties.iter().position(|x| x.name == *name) {
-                            state.properties.remove(pos);
-                        } else {
-                            contract_result.error = format!(
-                                "ModifyProductionSystem error: Cannot delete property '{}'. This property does not exist in the production system.",
-                                name
+                }
+            }
+            DynamicType::Option(c_type) if !value.is_null() => {
+                errors.check(c_type.normalize(value, custom_types, depth));
+            }
+            DynamicType::Default(c_type, _) | DynamicType::Annotated(c_type, _) => {
+                errors.check(c_type.normalize(value, custom_types, depth));
+            }
+            DynamicType::Map(_, value_type) => {
+                if let Some(map) = value.as_object_mut() {
+                    for (key, val) in map.iter_mut() {
+                        errors.check(
+                            value_type
+                                .normalize(val, custom_types, depth)
+                                .map_err(|e| e.at_field(key)),
+                        );
+                    }
+                }
+            }
+            DynamicType::Enum(enum_type) => {
+                if let Some(obj_dynamic) = value.as_object_mut() {
+                    for (value_name, value_val) in obj_dynamic.iter_mut() {
+                        if let Some(type_dyn) = enum_type.get(value_name) {
+                            errors.check(
+                                type_dyn
+                                    .normalize(value_val, custom_types, depth)
+                                    .map_err(|e| e.at_field(value_name)),
                             );
-                            return;
                         }
                     }
                 }
-
-                if let Some(modify_properties) = modify_properties {
-                    if modify_properties.is_empty() {
-                        contract_result.error = "ModifyProductionSystem error: The 'modify_properties' list cannot be empty. Please specify at least one property to modify.".to_owned();
-                        return;
+            }
+            DynamicType::Type(c_type) => {
+                // Deeper values are left as they are, `deserialize` rejects them.
+                if depth < MAX_DEPTH
+                    && let Some(fields) = custom_types.get(c_type)
+                {
+                    errors.check(fields.normalize(value, custom_types, depth + 1));
+                }
+            }
+            DynamicType::Number(constraints) => {
+                errors.check(constraints.normalize(value));
+            }
+            DynamicType::Decimal { scale } => {
+                if *scale <= MAX_SCALE
+                    && let Some((negative, integer, fraction)) = parse_decimal(value)
+                    && fraction.len() <= *scale as usize
+                    && !is_inexact_decimal(value, &integer, &fraction)
+                {
+                    let mut decimal = format!("{}{}", if negative { "-" } else { "" }, integer);
+                    if *scale > 0 {
+                        decimal =
+                            format!("{}.{:0<width$}", decimal, fraction, width = *scale as usize);
                     }
+                    *value = Value::String(decimal);
+                }
+            }
+            _ => {}
+        }
+        errors.finish()
+    }
 
-                    for (name, propiertie) in modify_properties.clone() {
-                        if let Err(e) = propiertie.check_data(&state.custom_types) {
-                            contract_result.error = e;
-                            return;
-                        };
-
-                        if let Some(existing) =
-                            state.properties.iter_mut().find(|x| x.name == name)
-                        {
-                            *existing = propiertie;
-                        } else {
-                            contract_result.error = format!(
-                                "ModifyProductionSystem error: Cannot modify property '{}'. This property does not exist in the production system.",
-                                name
-                            );
-                            return;
-                        }
+    /// Calls `f` with every value of the custom type `type_name` inside
+    /// `value`, nested values are visited first.
+    fn visit(
+        &self,
+        value: &mut Value,
+        custom_types: &HashMap<String, Fields>,
+        type_name: &str,
+        f: &mut dyn FnMut(&mut Value),
+    ) {
+        match self {
+            DynamicType::Vec(c_type) | DynamicType::Array(_, c_type) => {
+                if let Some(vec_dynamic) = value.as_array_mut() {
+                    for val in vec_dynamic.iter_mut() {
+                        c_type.visit(val, custom_types, type_name, f);
                     }
                 }
-
-                if let Some(add_properties) = add_properties {
-                    if let Err(e) = add_new_properties(state, add_properties) {
-                        contract_result.error = e;
-                        return;
+            }
+            DynamicType::Tuple(types) => {
+                if let Some(vec_dynamic) = value.as_array_mut() {
+                    for (val, type_dyn) in vec_dynamic.iter_mut().zip(types) {
+                        type_dyn.visit(val, custom_types, type_name, f);
                     }
                 }
             }
-            ChangeProductionSystem::ModifyTypes { delete, add } => {
-                if delete.is_none() && add.is_none() {
-                    contract_result.error = "ModifyTypes error: At least one parameter must be provided. Please specify 'add' or 'delete'.".to_owned();
-                    return;
+            DynamicType::Option(c_type) if !value.is_null() => {
+                c_type.visit(value, custom_types, type_name, f);
+            }
+            DynamicType::Default(c_type, _) | DynamicType::Annotated(c_type, _) => {
+                c_type.visit(value, custom_types, type_name, f);
+            }
+            DynamicType::Map(_, value_type) => {
+                if let Some(map) = value.as_object_mut() {
+                    for val in map.values_mut() {
+                        value_type.visit(val, custom_types, type_name, f);
+                    }
                 }
-
-                if let Some(delete) = delete {
-                    for name in delete {
-                        if state.custom_types.remove(&name).is_none() {
-                            contract_result.error = format!(
-                                "ModifyTypes error: Cannot delete type '{}'. This type does not exist in the schema.",
-                                name
-                            );
-                            return;
+            }
+            DynamicType::Enum(enum_type) => {
+                if let Some(obj_dynamic) = value.as_object_mut() {
+                    for (value_name, value_val) in obj_dynamic.iter_mut() {
+                        if let Some(type_dyn) = enum_type.get(value_name) {
+                            type_dyn.visit(value_val, custom_types, type_name, f);
                         }
                     }
                 }
+            }
+            DynamicType::Type(c_type) => {
+                if let Some(fields) = custom_types.get(c_type) {
+                    fields.visit(value, custom_types, type_name, f);
+                    if c_type == type_name {
+                        f(value);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
 
-                if let Some(add) = add {
-                    if let Err(e) = add_types(state, add) {
-                        contract_result.error = e;
-                        return;
-                    };
+    /// Orders two values of this type: numbers by value, timestamps, dates
+    /// and durations chronologically. `None` for other types or invalid values.
+    pub fn compare(&self, a: &Value, b: &Value) -> Option<Ordering> {
+        match self {
+            DynamicType::i64 | DynamicType::u64 | DynamicType::f64 | DynamicType::Number(..) => {
+                a.as_f64()?.partial_cmp(&b.as_f64()?)
+            }
+            _ => Some(self.temporal_key(a)?.cmp(&self.temporal_key(b)?)),
+        }
+    }
+
+    /// Value of a map key, integer keys are written as numbers.
+    fn map_key(&self, key: &str) -> Value {
+        match self {
+            DynamicType::i64 | DynamicType::u64 => serde_json::from_str::<Value>(key)
+                .ok()
+                .filter(Value::is_number)
+                .unwrap_or_else(|| Value::String(key.to_owned())),
+            _ => Value::String(key.to_owned()),
+        }
+    }
+
+    /// Nanoseconds since the epoch of a timestamp, days since the epoch of a
+    /// date and nanoseconds of a duration.
+    fn temporal_key(&self, value: &Value) -> Option<i128> {
+        let text = value.as_str()?;
+        match self {
+            DynamicType::Timestamp => parse_timestamp(text),
+            DynamicType::Date => parse_date(text).map(i128::from),
+            DynamicType::Duration => parse_duration(text),
+            _ => None,
+        }
+    }
+
+    fn deserialize(
+        &self,
+        value: Value,
+        custom_types: &HashMap<String, Fields>,
+    ) -> Result<(), Error> {
+        self.deserialize_at(value, custom_types, 0)
+    }
+
+    /// `depth` is the number of custom types the value is nested in.
+    fn deserialize_at(
+        &self,
+        value: Value,
+        custom_types: &HashMap<String, Fields>,
+        depth: usize,
+    ) -> Result<(), Error> {
+        match self {
+            DynamicType::String => {
+                if value.as_str().is_none() {
+                    return Err(Error::value(ErrorKind::TypeMismatch, format!("Deserialization error: Expected a String but received '{}'. Please provide a valid string value.", value), "String", &value));
                 }
             }
-            ChangeProductionSystem::ModifyUnitProcess {
-                modify,
-                add,
-                delete,
-            } => {
-                if delete.is_none() && add.is_none() && modify.is_none() {
-                    contract_result.error = "ModifyUnitProcess error: At least one parameter must be provided. Please specify 'add', 'modify', or 'delete'.".to_owned();
-                    return;
+            DynamicType::u64 => {
+                if value.as_u64().is_none() {
+                    return Err(Error::value(ErrorKind::TypeMismatch, format!("Deserialization error: Expected an unsigned 64-bit integer (u64) but received '{}'. Please provide a valid non-negative integer.", value), "u64", &value));
+                }
+            }
+            DynamicType::f64 => {
+                if value.as_f64().is_none() {
+                    return Err(Error::value(ErrorKind::TypeMismatch, format!("Deserialization error: Expected a 64-bit floating point number (f64) but received '{}'. Please provide a valid decimal number.", value), "f64", &value));
+                }
+            }
+            DynamicType::i64 => {
+                if value.as_i64().is_none() {
+                    return Err(Error::value(ErrorKind::TypeMismatch, format!("Deserialization error: Expected a signed 64-bit integer (i64) but received '{}'. Please provide a valid integer.", value), "i64", &value));
+                }
+            }
+            DynamicType::bool => {
+                if value.as_bool().is_none() {
+                    return Err(Error::value(ErrorKind::TypeMismatch, format!("Deserialization error: Expected a boolean (true/false) but received '{}'. Please provide a valid boolean value.", value), "bool", &value));
                 }
+            }
+            DynamicType::Vec(vec_type) => {
+                let Some(vec_dynamic) = value.as_array() else {
+                    return Err(Error::value(ErrorKind::TypeMismatch, format!("Deserialization error: Expected an array but received '{}'. Please provide a valid JSON array.", value), "Vec", &value));
+                };
 
-                if let Some(delete) = delete {
-                    if delete.is_empty() {
-                        contract_result.error = "ModifyUnitProcess error: The 'delete' list cannot be empty. Please specify at least one unit process to delete.".to_owned();
-                        return;
-                    }
+                let mut errors = Errors::default();
+                for (index, val) in vec_dynamic.clone().into_iter().enumerate() {
+                    errors.check(
+                        vec_type
+                            .deserialize_at(val, custom_types, depth)
+                            .map_err(|e| e.at_index(index)),
+                    );
+                }
+                errors.finish()?;
+            }
+            DynamicType::Tuple(types) => {
+                let Some(vec_dynamic) = value.as_array() else {
+                    return Err(Error::value(
+                        ErrorKind::TypeMismatch,
+                        format!(
+                            "Deserialization error: Expected a tuple but received '{}'. Please provide a JSON array with {} values.",
+                            value,
+                            types.len()
+                        ),
+                        "Tuple",
+                        &value,
+                    ));
+                };
+                check_length(vec_dynamic.len(), types.len())?;
 
-                    for name in delete.clone() {
-                        if let Some(pos) = state.unit_process.iter().position(|x| x.name == name) {
-                            state.unit_process.remove(pos);
-                        } else {
-                            contract_result.error = format!(
-                                "ModifyUnitProcess error: Cannot delete unit process '{}'. This unit process does not exist in the production system.",
-                                name
-                            );
-                            return;
-                        }
-                    }
+                let mut errors = Errors::default();
+                for (index, (val, type_dyn)) in
+                    vec_dynamic.clone().into_iter().zip(types).enumerate()
+                {
+                    errors.check(
+                        type_dyn
+                            .deserialize_at(val, custom_types, depth)
+                            .map_err(|e| e.at_index(index)),
+                    );
                 }
+                errors.finish()?;
+            }
+            DynamicType::Array(len, c_type) => {
+                let Some(vec_dynamic) = value.as_array() else {
+                    return Err(Error::value(
+                        ErrorKind::TypeMismatch,
+                        format!(
+                            "Deserialization error: Expected an array but received '{}'. Please provide a JSON array with {} values.",
+                            value, len
+                        ),
+                        "Array",
+                        &value,
+                    ));
+                };
+                check_length(vec_dynamic.len(), *len)?;
 
-                if let Some(modify) = modify {
-                    if modify.is_empty() {
-                        contract_result.error = "ModifyUnitProcess error: The 'modify' list cannot be empty. Please specify at least one unit process to modify.".to_owned();
-                        return;
+                let mut errors = Errors::default();
+                for (index, val) in vec_dynamic.clone().into_iter().enumerate() {
+                    errors.check(
+                        c_type
+                            .deserialize_at(val, custom_types, depth)
+                            .map_err(|e| e.at_index(index)),
+                    );
+                }
+                errors.finish()?;
+            }
+            DynamicType::Map(key_type, value_type) => {
+                let Some(map) = value.as_object() else {
+                    return Err(Error::value(
+                        ErrorKind::TypeMismatch,
+                        format!(
+                            "Deserialization error: Expected a map but received '{}'. Please provide a valid JSON object.",
+                            value
+                        ),
+                        "Map",
+                        &value,
+                    ));
+                };
+
+                let mut errors = Errors::default();
+                for (key, val) in map.clone() {
+                    errors.check(
+                        key_type
+                            .deserialize_at(key_type.map_key(&key), custom_types, depth)
+                            .map_err(|e| {
+                                Error {
+                                    message: format!(
+                                        "Deserialization error: Invalid map key '{}'. {}",
+                                        key, e.message
+                                    ),
+                                    ..e
+                                }
+                                .at_field(&key)
+                            }),
+                    );
+                    errors.check(
+                        value_type
+                            .deserialize_at(val, custom_types, depth)
+                            .map_err(|e| e.at_field(&key)),
+                    );
+                }
+                errors.finish()?;
+            }
+            DynamicType::Enum(enum_type) => {
+                if let Some(obj_dynamic) = value.as_object().cloned() {
+                    if obj_dynamic.len() != 1 {
+                        return Err(Error::new(
+                            ErrorKind::FieldCount,
+                            format!(
+                                "Deserialization error: Invalid enum object. Expected exactly one field but received {}. Enum values must be represented as a single-field object.",
+                                obj_dynamic.len()
+                            ),
+                        )
+                        .path("$")
+                        .expected("1")
+                        .received(&obj_dynamic.len().to_string()));
                     }
 
-                    for (name, process) in modify.clone() {
-                        if let Err(e) = process.check_data(&state.custom_types) {
-                            contract_result.error = e;
-                            return;
+                    for (value_name, value_val) in obj_dynamic {
+                        let Some(type_dyn) = enum_type.get(&value_name) else {
+                            return Err(Error::new(
+                                ErrorKind::UnknownVariant,
+                                format!(
+                                    "Deserialization error: Unknown enum variant '{}'. Valid variants are: {:?}",
+                                    value_name, enum_type.keys().collect::<Vec<_>>()
+                                ),
+                            )
+                            .path("$")
+                            .received(&value_name));
                         };
 
-                        if let Some(existing) =
-                            state.unit_process.iter_mut().find(|x| x.name == name)
-                        {
-                            *existing = process;
-                        } else {
-                            contract_result.error = format!(
-                                "ModifyUnitProcess error: Cannot modify unit process '{}'. This unit process does not exist in the production system.",
-                                name
-                            );
-                            return;
-                        }
+                        type_dyn
+                            .deserialize_at(value_val, custom_types, depth)
+                            .map_err(|e| e.at_field(&value_name))?;
+                    }
+                } else if let Some(obj_dynamic) = value.as_str() {
+                    if let Some(DynamicType::Dummy) = enum_type.get(obj_dynamic) {
+                        // Ok
+                    } else {
+                        return Err(Error::new(
+                            ErrorKind::UnknownVariant,
+                            format!(
+                                "Deserialization error: Unknown enum variant '{}'. Valid variants are: {:?}",
+                                obj_dynamic, enum_type.keys().collect::<Vec<_>>()
+                            ),
+                        )
+                        .path("$")
+                        .received(obj_dynamic));
                     }
+                } else {
+                    return Err(Error::value(ErrorKind::TypeMismatch, format!("Deserialization error: Cannot deserialize value '{}' as Enum. Enum values must be either a single-field object or a string (for variants without data).", value), "Enum", &value));
+                };
+            }
+            DynamicType::Type(c_type) => {
+                let Some(obj_type) = custom_types.get(c_type) else {
+                    return Err(Error::new(
+                        ErrorKind::UnknownType,
+                        format!(
+                            "Deserialization error: Custom type '{}' is not defined in the schema. Please ensure the type is defined before using it.",
+                            c_type
+                        ),
+                    )
+                    .path("$")
+                    .expected(c_type));
+                };
+
+                if depth >= MAX_DEPTH {
+                    return Err(Error::new(
+                        ErrorKind::TooDeep,
+                        format!(
+                            "Deserialization error: Value is nested too deeply in custom type '{}'. Values cannot nest more than {} custom types.",
+                            c_type, MAX_DEPTH
+                        ),
+                    )
+                    .path("$")
+                    .expected(&format!("depth <= {}", MAX_DEPTH)));
                 }
 
-                if let Some(add) = add {
-                    if let Err(e) = add_unit_process(state, add) {
-                        contract_result.error = e;
-                        return;
-                    }
+                obj_type.check_value_at(value, custom_types, depth + 1)?;
+            }
+            DynamicType::Option(option) => {
+                if value.is_null() {
+                    return Ok(());
+                } else {
+                    return option.deserialize_at(value, custom_types, depth);
                 }
             }
-        },
-        Events::RegisterData(data) => {
-            if state.version == 0 {
-                contract_result.error = "RegisterData error: Cannot register data before initialization. The first event must be an Init event.".to_owned();
-                return;
+            DynamicType::Default(c_type, _) | DynamicType::Annotated(c_type, _) => {
+                return c_type.deserialize_at(value, custom_types, depth);
             }
-
-            if data.is_empty() {
-                contract_result.error = "RegisterData error: The data list cannot be empty. Please provide at least one unit data entry to register.".to_owned();
-                return;
+            DynamicType::Dummy => {
+                return Err(Error::new(ErrorKind::InvalidType, "Check error: Dummy type encountered during deserialization. Dummy types are placeholders and cannot be used for actual data.").path("$"));
             }
+            DynamicType::Number(constraints) => {
+                return constraints.check_value(value, custom_types);
+            }
+            DynamicType::Text(constraints) => {
+                return constraints.check_value(value, custom_types);
+            }
+            DynamicType::Timestamp | DynamicType::Date | DynamicType::Duration => {
+                if self.temporal_key(&value).is_none() {
+                    return Err(Error::new(
+                        ErrorKind::TypeMismatch,
+                        format!(
+                            "Deserialization error: Expected a {:?} but received '{}'. Please provide an ISO 8601 {} string.",
+                            self,
+                            value,
+                            match self {
+                                DynamicType::Timestamp => "date and time with a UTC offset",
+                                DynamicType::Date => "calendar date",
+                                _ => "duration without years or months",
+                            }
+                        ),
+                    )
+                    .path("$")
+                    .expected(&format!("{:?}", self))
+                    .received(&value.to_string()));
+                }
+            }
+            DynamicType::Decimal { scale } => {
+                let Some((_, integer, fraction)) = parse_decimal(&value) else {
+                    return Err(Error::value(
+                        ErrorKind::TypeMismatch,
+                        format!(
+                            "Deserialization error: Expected a decimal number but received '{}'. Please provide a JSON number or a string such as '12.50'.",
+                            value
+                        ),
+                        "Decimal",
+                        &value,
+                    ));
+                };
 
-            for d in data {
-                let mut change = false;
-
-                for unit_process in state.unit_process.iter_mut() {
-                    if unit_process.name == d.name {
-                        if let Err(e) = unit_process.register_data(d.clone(), &state.custom_types) {
-                            contract_result.error = e;
-                            return;
-                        };
-                        change = true;
-                        break;
-                    }
+                if is_inexact_decimal(&value, &integer, &fraction) {
+                    return Err(Error::new(
+                        ErrorKind::Constraint,
+                        format!(
+                            "Deserialization error: The decimal {} has more significant digits than a JSON number keeps exactly. Please provide it as a string.",
+                            value
+                        ),
+                    )
+                    .path("$")
+                    .expected(&format!("at most {} significant digits", f64::DIGITS))
+                    .received(&value.to_string()));
                 }
 
-                if !change {
-                    contract_result.error = format!(
-                        "RegisterData error: No unit process found with name '{}'. Please ensure the unit process exists before registering data to it.",
-                        d.name
-                    );
-                    return;
+                if fraction.len() > *scale as usize {
+                    return Err(Error::new(
+                        ErrorKind::Constraint,
+                        format!(
+                            "Deserialization error: The decimal {} has {} fraction digits but its type allows at most {}.",
+                            value,
+                            fraction.len(),
+                            scale
+                        ),
+                    )
+                    .path("$")
+                    .expected(&format!("scale {}", scale))
+                    .received(&value.to_string()));
                 }
             }
         }
+
+        Ok(())
     }
 
-    contract_result.success = true;
-}
+    fn check_data(
+        &self,
+        custom_types: HashMap<String, Fields>,
+        internal_types: &mut Vec<String>,
+    ) -> Result<(), Error> {
+        match self {
+            DynamicType::Vec(c_type) | DynamicType::Option(c_type) => {
+                if let DynamicType::Dummy | DynamicType::Option(..) = **c_type {
+                    return Err(Error::new(ErrorKind::InvalidType, "Check error: Invalid nested type. Vec and Option types cannot contain Dummy or nested Option types. Please use a concrete type."));
+                }
+                // Values can stop at an empty Vec or a None, so the types
+                // reached from here can not make a cycle.
+                c_type.check_data(custom_types, &mut vec![])?;
+            }
+            DynamicType::Tuple(types) => {
+                if types.is_empty() {
+                    return Err(Error::new(
+                        ErrorKind::InvalidType,
+                        "Check error: Tuple types cannot be empty. A tuple must contain at least one type.",
+                    ));
+                }
 
-#[cfg(test)]
-mod tests {
-    use std::{collections::HashMap, vec};
+                let mut errors = Errors::default();
+                for (index, type_dyn) in types.iter().enumerate() {
+                    if let DynamicType::Dummy = type_dyn {
+                        errors.push(Error::new(ErrorKind::InvalidType, "Check error: Invalid tuple type. Tuple values cannot be of type 'Dummy'. Please specify a concrete type.").at_index(index));
+                        continue;
+                    }
 
-    use crate::{
-        contract_logic, ChangeProductionSystem, Data, DynamicType, Events, Fields, Metadata, ProductionSystem, Properties, RegisterData, Target, UnitData, UnitProcess
-    };
-    use kore_contract_sdk as sdk;
-    use serde_json::json;
+                    errors.check(
+                        type_dyn
+                            .check_data(custom_types.clone(), internal_types)
+                            .map_err(|e| e.at_index(index)),
+                    );
+                }
+                errors.finish()?;
+            }
+            DynamicType::Array(len, c_type) => {
+                if *len == 0 {
+                    return Err(Error::new(
+                        ErrorKind::InvalidType,
+                        "Check error: Invalid array length. Fixed-length arrays must have at least one value.",
+                    ));
+                }
 
-    impl PartialEq for DynamicType {
-        fn eq(&self, other: &Self) -> bool {
-            use DynamicType::*;
-            match (self, other) {
-                (String, String) => true,
-                (i64, i64) => true,
-                (u64, u64) => true,
-                (f64, f64) => true,
-                (bool, bool) => true,
-                (Vec(a), Vec(b)) => a == b,
-                (Option(a), Option(b)) => a == b,
-                (Enum(a), Enum(b)) => a == b,
-                (Type(a), Type(b)) => a == b,
-                (Dummy, Dummy) => true,
-                _ => false,
+                if let DynamicType::Dummy | DynamicType::Option(..) = **c_type {
+                    return Err(Error::new(
+                        ErrorKind::InvalidType,
+                        "Check error: Invalid nested type. Array types cannot contain Dummy or Option types. Please use a concrete type.",
+                    ));
+                }
+                c_type.check_data(custom_types, internal_types)?;
+            }
+            DynamicType::Map(key_type, value_type) => {
+                let key = match &**key_type {
+                    DynamicType::String
+                    | DynamicType::Text(..)
+                    | DynamicType::i64
+                    | DynamicType::u64
+                    | DynamicType::Timestamp
+                    | DynamicType::Date
+                    | DynamicType::Duration => true,
+                    DynamicType::Enum(enum_type) => enum_type
+                        .values()
+                        .all(|type_dyn| matches!(type_dyn, DynamicType::Dummy)),
+                    _ => false,
+                };
+                if !key {
+                    return Err(Error::new(
+                        ErrorKind::InvalidType,
+                        "Check error: Invalid map key type. Map keys must be String, Text, i64, u64, Timestamp, Date, Duration or an Enum without data.",
+                    ));
+                }
+
+                if let DynamicType::Dummy | DynamicType::Option(..) = **value_type {
+                    return Err(Error::new(
+                        ErrorKind::InvalidType,
+                        "Check error: Invalid map value type. Map values cannot be Dummy or Option types, omit the key instead.",
+                    ));
+                }
+
+                key_type.check_data(custom_types.clone(), internal_types)?;
+                value_type.check_data(custom_types, &mut vec![])?;
+            }
+            DynamicType::Enum(enum_type) => {
+                let mut errors = Errors::default();
+                for (type_field, type_dyn) in enum_type.clone() {
+                    if type_field.is_empty() {
+                        errors.push(Error::new(ErrorKind::EmptyName, "Check error: Enum variant name cannot be empty. All enum variants must have a non-empty name."));
+                        continue;
+                    }
+
+                    if let DynamicType::Option(..) = type_dyn {
+                        errors.push(Error::new(ErrorKind::InvalidType, format!("Check error: Enum variant '{}' cannot be of type Option. Use a unit variant (Dummy) for variants without data instead.", type_field)).at_field(&type_field));
+                        continue;
+                    }
+
+                    errors.check(
+                        type_dyn
+                            .check_data(custom_types.clone(), internal_types)
+                            .map_err(|e| e.at_field(&type_field)),
+                    );
+                }
+                errors.finish()?;
+            }
+            DynamicType::Type(c_type) => {
+                if c_type.is_empty() {
+                    return Err(Error::new(ErrorKind::EmptyName, "Check error: Custom type name cannot be empty. Please provide a valid type name."));
+                }
+
+                if !custom_types.contains_key(c_type) {
+                    return Err(Error::new(ErrorKind::UnknownType, format!("Check error: Custom type '{}' is not defined. Please ensure the type is defined before referencing it.", c_type)).received(c_type));
+                }
+
+                internal_types.push(c_type.clone());
+            }
+            DynamicType::Number(constraints) => constraints.check_data()?,
+            DynamicType::Text(constraints) => constraints.check_data()?,
+            DynamicType::Decimal { scale } if *scale > MAX_SCALE => {
+                return Err(Error::new(
+                    ErrorKind::InvalidType,
+                    format!(
+                        "Check error: Invalid decimal scale {}. Decimals can have at most {} fraction digits.",
+                        scale, MAX_SCALE
+                    ),
+                )
+                .expected(&format!("scale <= {}", MAX_SCALE))
+                .received(&scale.to_string()));
+            }
+            DynamicType::Default(..) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidType,
+                    "Check error: Invalid default value. Only the fields of an 'Object' type can have a default value.",
+                ));
+            }
+            DynamicType::Annotated(..) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidType,
+                    "Check error: Invalid annotations. Only the fields of an 'Object' type can be annotated, and only once.",
+                ));
             }
+            _ => {}
         }
+
+        Ok(())
     }
 
-    impl Eq for DynamicType {}
+    /// Checks the type of an object field with a default value and the
+    /// value itself.
+    fn check_default(
+        &self,
+        default: &Value,
+        custom_types: HashMap<String, Fields>,
+        internal_types: &mut Vec<String>,
+    ) -> Result<(), Error> {
+        if let DynamicType::Dummy
+        | DynamicType::Option(..)
+        | DynamicType::Default(..)
+        | DynamicType::Annotated(..) = self
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidType,
+                "Check error: Invalid default value. Fields with a default value cannot be Dummy, Option or Default. Please use a concrete type.",
+            ));
+        }
+        self.check_data(custom_types.clone(), internal_types)?;
+
+        let mut default = default.clone();
+        self.normalize(&mut default, &custom_types, 0)?;
+        self.deserialize(default, &custom_types).map_err(|e| Error {
+            message: format!("Check error: Invalid default value. {}", e.message),
+            ..e
+        })
+    }
 
-    impl PartialEq for Fields {
-        fn eq(&self, other: &Self) -> bool {
-            use Fields::*;
-            match (self, other) {
-                (Basic(a), Basic(b)) => a == b,
-                (Object(a), Object(b)) => a == b,
-                _ => false,
+    /// JSON Schema (draft 2020-12) of the values of the type, custom types
+    /// are referenced as `#/$defs/<name>`. Quantities and decimals are
+    /// described in the form they are stored.
+    pub fn json_schema(&self) -> Value {
+        match self {
+            DynamicType::String => json!({"type": "string"}),
+            DynamicType::i64 => json!({"type": "integer"}),
+            DynamicType::u64 => json!({"type": "integer", "minimum": 0}),
+            DynamicType::f64 => json!({"type": "number"}),
+            DynamicType::bool => json!({"type": "boolean"}),
+            DynamicType::Dummy => json!({"type": "null"}),
+            DynamicType::Vec(c_type) => json!({"type": "array", "items": c_type.json_schema()}),
+            DynamicType::Option(c_type) => {
+                json!({"anyOf": [c_type.json_schema(), {"type": "null"}]})
+            }
+            DynamicType::Type(c_type) => json!({"$ref": format!("#/$defs/{}", c_type)}),
+            DynamicType::Enum(enum_type) => {
+                let mut variants: Vec<(&String, &DynamicType)> = enum_type.iter().collect();
+                variants.sort_by_key(|(name, _)| *name);
+
+                let variants: Vec<Value> = variants
+                    .into_iter()
+                    .map(|(name, type_dyn)| match type_dyn {
+                        DynamicType::Dummy => json!({"const": name}),
+                        type_dyn => json!({
+                            "type": "object",
+                            "properties": {name: type_dyn.json_schema()},
+                            "required": [name],
+                            "additionalProperties": false
+                        }),
+                    })
+                    .collect();
+                json!({"oneOf": variants})
+            }
+            DynamicType::Number(constraints) => constraints.json_schema(),
+            DynamicType::Text(constraints) => constraints.json_schema(),
+            DynamicType::Timestamp => json!({"type": "string", "format": "date-time"}),
+            DynamicType::Date => json!({"type": "string", "format": "date"}),
+            DynamicType::Duration => json!({"type": "string", "format": "duration"}),
+            DynamicType::Decimal { scale } => {
+                let pattern = if *scale == 0 {
+                    "^-?[0-9]+$".to_owned()
+                } else {
+                    format!("^-?[0-9]+(\\.[0-9]{{1,{}}})?$", scale)
+                };
+                json!({"type": ["string", "number"], "pattern": pattern})
+            }
+            DynamicType::Map(key_type, value_type) => json!({
+                "type": "object",
+                "propertyNames": key_type.key_schema(),
+                "additionalProperties": value_type.json_schema()
+            }),
+            DynamicType::Tuple(types) => json!({
+                "type": "array",
+                "prefixItems": types.iter().map(DynamicType::json_schema).collect::<Vec<_>>(),
+                "items": false,
+                "minItems": types.len()
+            }),
+            DynamicType::Array(len, c_type) => json!({
+                "type": "array",
+                "items": c_type.json_schema(),
+                "minItems": len,
+                "maxItems": len
+            }),
+            DynamicType::Default(c_type, default) => {
+                let mut schema = c_type.json_schema();
+                schema["default"] = default.clone();
+                schema
+            }
+            DynamicType::Annotated(c_type, annotations) => {
+                let mut schema = c_type.json_schema();
+                annotations.annotate(&mut schema);
+                schema
             }
         }
     }
 
-    impl Eq for Fields {}
-
-    impl PartialEq for Data {
-        fn eq(&self, other: &Self) -> bool {
-            self.name == other.name
-                && self.type_name == other.type_name
-                && self.content == other.content
-                && self.targets == other.targets
+    /// JSON Schema of the keys of a map of this key type, keys are always
+    /// strings.
+    fn key_schema(&self) -> Value {
+        match self {
+            DynamicType::i64 => json!({"pattern": "^-?[0-9]+$"}),
+            DynamicType::u64 => json!({"pattern": "^[0-9]+$"}),
+            DynamicType::Enum(enum_type) => {
+                let mut variants: Vec<&String> = enum_type.keys().collect();
+                variants.sort();
+                json!({"enum": variants})
+            }
+            key_type => key_type.json_schema(),
         }
     }
 
-    impl Eq for Data {}
+    /// Type of an object field, see `Fields::from_json_schema`.
+    fn from_json_field(schema: &Value, required: bool) -> Result<DynamicType, Error> {
+        let mut c_type = DynamicType::from_json_schema(schema)?;
+        if let Some(default) = schema.get("default") {
+            c_type = DynamicType::Default(Box::new(c_type), default.clone());
+        } else if !required && !matches!(c_type, DynamicType::Option(_)) {
+            c_type = DynamicType::Option(Box::new(c_type));
+        }
 
-    impl PartialEq for Metadata {
-        fn eq(&self, other: &Self) -> bool {
-            self.type_name == other.type_name
-                && self.content == other.content
+        if let Some(annotations) = schema.as_object().and_then(schema_annotations) {
+            c_type = DynamicType::Annotated(Box::new(c_type), annotations);
         }
+
+        Ok(c_type)
     }
 
-    impl Eq for Metadata {}
+    /// Type of a JSON Schema, the reverse of `json_schema`. Annotations and
+    /// defaults are dropped, only types and object fields keep them.
+    fn from_json_schema(schema: &Value) -> Result<DynamicType, Error> {
+        let Some(object) = schema.as_object() else {
+            return Err(unsupported_schema(schema));
+        };
 
+        if let Some(reference) = object.get("$ref") {
+            check_keywords(object, &["$ref"])?;
+            return match reference.as_str().and_then(|reference| {
+                reference
+                    .strip_prefix("#/$defs/")
+                    .or_else(|| reference.strip_prefix("#/definitions/"))
+            }) {
+                Some(name) if !name.is_empty() && !name.contains('/') => {
+                    Ok(DynamicType::Type(name.to_owned()))
+                }
+                _ => Err(unsupported(
+                    "$ref",
+                    "Only references to the schemas of '$defs' are supported.",
+                )),
+            };
+        }
 
-    impl PartialEq for Target {
-        fn eq(&self, other: &Self) -> bool {
-            self.governance_id == other.governance_id
-                && self.subject_id == other.subject_id
-                && self.unit_process == other.unit_process
+        if let Some(any_of) = object.get("anyOf") {
+            check_keywords(object, &["anyOf"])?;
+            let null = json!({"type": "null"});
+            return match any_of.as_array().map(Vec::as_slice).unwrap_or_default() {
+                [c_type, other] if *other == null => Ok(DynamicType::Option(Box::new(
+                    DynamicType::from_json_schema(c_type)
+                        .map_err(|e| e.at_index(0).at_field("anyOf"))?,
+                ))),
+                [other, c_type] if *other == null => Ok(DynamicType::Option(Box::new(
+                    DynamicType::from_json_schema(c_type)
+                        .map_err(|e| e.at_index(1).at_field("anyOf"))?,
+                ))),
+                _ => Err(unsupported(
+                    "anyOf",
+                    "Only the union of a schema and {\"type\": \"null\"} is supported.",
+                )),
+            };
         }
-    }
 
-    impl Eq for Target {}
+        if let Some(one_of) = object.get("oneOf") {
+            check_keywords(object, &["oneOf"])?;
+            let Some(branches) = one_of.as_array().filter(|branches| !branches.is_empty()) else {
+                return Err(unsupported(
+                    "oneOf",
+                    "It must be a non-empty array of schemas.",
+                ));
+            };
+
+            let mut errors = Errors::default();
+            let mut enum_type = HashMap::new();
+            for (index, branch) in branches.iter().enumerate() {
+                if let Some((name, type_dyn)) = errors.check(
+                    DynamicType::from_json_variant(branch)
+                        .map_err(|e| e.at_index(index).at_field("oneOf")),
+                ) {
+                    enum_type.insert(name, type_dyn);
+                }
+            }
+            errors.finish()?;
 
-    
+            return Ok(DynamicType::Enum(enum_type));
+        }
 
-    impl PartialEq for Properties {
-        fn eq(&self, other: &Self) -> bool {
-            self.name == other.name
-                && self.type_name == other.type_name
-                && self.content == other.content
+        if let Some(variants) = object.get("enum") {
+            check_keywords(object, &["enum", "type"])?;
+            let variants: Option<Vec<&str>> = variants
+                .as_array()
+                .filter(|variants| !variants.is_empty())
+                .and_then(|variants| variants.iter().map(Value::as_str).collect());
+
+            return match variants {
+                Some(variants) => Ok(DynamicType::Enum(
+                    variants
+                        .into_iter()
+                        .map(|variant| (variant.to_owned(), DynamicType::Dummy))
+                        .collect(),
+                )),
+                None => Err(unsupported(
+                    "enum",
+                    "Only non-empty enums of strings are supported.",
+                )),
+            };
         }
-    }
-    
-    impl Eq for Properties {}
 
-    impl PartialEq for UnitProcess {
-        fn eq(&self, other: &Self) -> bool {
-            self.name == other.name
-                && self.inputs == other.inputs
-                && self.outputs == other.outputs
-                && self.properties == other.properties
+        let types: Vec<&str> = match object.get("type") {
+            Some(Value::String(c_type)) => vec![c_type.as_str()],
+            Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+
+        match types.as_slice() {
+            [c_type, "null"] | ["null", c_type] => {
+                let mut schema = object.clone();
+                schema.insert("type".to_owned(), json!(c_type));
+                Ok(DynamicType::Option(Box::new(
+                    DynamicType::from_json_schema(&Value::Object(schema))?,
+                )))
+            }
+            ["string", "number"] | ["number", "string"] => {
+                check_keywords(object, &["type", "pattern"])?;
+                let scale = match object.get("pattern").and_then(Value::as_str) {
+                    Some("^-?[0-9]+$") => Some(0),
+                    Some(pattern) => pattern
+                        .strip_prefix("^-?[0-9]+(\\.[0-9]{1,")
+                        .and_then(|scale| scale.strip_suffix("})?$"))
+                        .and_then(|scale| scale.parse().ok()),
+                    None => None,
+                };
+
+                scale.map(|scale| DynamicType::Decimal { scale }).ok_or_else(|| {
+                    unsupported(
+                        "type",
+                        "Strings or numbers are only supported with the pattern of a decimal, such as '^-?[0-9]+(\\.[0-9]{1,2})?$'.",
+                    )
+                })
+            }
+            ["string"] => DynamicType::from_json_string(object),
+            ["integer"] => DynamicType::from_json_number(object, NumberKind::i64),
+            ["number"] => DynamicType::from_json_number(object, NumberKind::f64),
+            ["boolean"] => {
+                check_keywords(object, &["type"])?;
+                Ok(DynamicType::bool)
+            }
+            ["array"] => DynamicType::from_json_array(object),
+            ["object"] => {
+                if object.contains_key("properties") {
+                    return Err(unsupported(
+                        "properties",
+                        "Nested objects are not supported, define them in '$defs' and use '$ref'.",
+                    ));
+                }
+                check_keywords(object, &["type", "additionalProperties", "propertyNames"])?;
+
+                let Some(values) = object
+                    .get("additionalProperties")
+                    .filter(|values| values.is_object())
+                else {
+                    return Err(unsupported(
+                        "additionalProperties",
+                        "Maps must give the schema of their values in 'additionalProperties'.",
+                    ));
+                };
+                let value_type = DynamicType::from_json_schema(values)
+                    .map_err(|e| e.at_field("additionalProperties"))?;
+                let key_type = match object.get("propertyNames") {
+                    Some(keys) => {
+                        DynamicType::from_json_key(keys).map_err(|e| e.at_field("propertyNames"))?
+                    }
+                    None => DynamicType::String,
+                };
+
+                Ok(DynamicType::Map(Box::new(key_type), Box::new(value_type)))
+            }
+            [] => Err(unsupported(
+                "type",
+                "Schemas without a 'type' accept any value.",
+            )),
+            _ => Err(unsupported(
+                "type",
+                format!("The type {} has no equivalent.", object["type"]),
+            )),
         }
     }
 
-    impl Eq for UnitProcess {}
+    /// Enum variant of a `oneOf` branch: `{"const": <variant>}` or an object
+    /// with the variant as its only, required, property.
+    fn from_json_variant(schema: &Value) -> Result<(String, DynamicType), Error> {
+        if let Some(Value::String(name)) = schema.get("const") {
+            return Ok((name.clone(), DynamicType::Dummy));
+        }
 
-    #[test]
-    fn register_types_type_cycle() {
-        let mut custom_type = HashMap::new();
-        custom_type.insert("name".to_owned(), DynamicType::String);
-        custom_type.insert(
-            "value".to_owned(),
-            DynamicType::Type("Another User".to_owned()),
-        );
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let required = schema.get("required").and_then(Value::as_array);
+        if let (Some(properties), Some([Value::String(required)])) =
+            (properties, required.map(Vec::as_slice))
+            && properties.len() == 1
+            && let Some(variant) = properties.get(required)
+        {
+            let type_dyn = DynamicType::from_json_schema(variant)
+                .map_err(|e| e.at_field(required).at_field("properties"))?;
+            return Ok((required.clone(), type_dyn));
+        }
 
-        let custom_type = Fields::Object(custom_type);
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "Schema error: Unsupported 'oneOf' branch. Branches must be {\"const\": <variant>} or an object with a single required property.",
+        )
+        .path("$"))
+    }
 
-        let mut types = HashMap::new();
-        types.insert("User".to_owned(), custom_type);
+    fn from_json_string(object: &serde_json::Map<String, Value>) -> Result<DynamicType, Error> {
+        check_keywords(
+            object,
+            &["type", "minLength", "maxLength", "pattern", "format"],
+        )?;
 
-        let init_state = ProductionSystem {
-            name: "wine process".to_owned(),
-            version: 1,
-            unit_process: vec![],
-            custom_types: types,
-            properties: vec![],
+        let constrained = ["minLength", "maxLength", "pattern"]
+            .iter()
+            .any(|keyword| object.contains_key(*keyword));
+        let format = match object.get("format").map(|format| format.as_str()) {
+            None => None,
+            Some(Some("date-time")) if !constrained => return Ok(DynamicType::Timestamp),
+            Some(Some("date")) if !constrained => return Ok(DynamicType::Date),
+            Some(Some("duration")) if !constrained => return Ok(DynamicType::Duration),
+            Some(Some("date")) => Some(TextFormat::Date),
+            Some(Some("time")) => Some(TextFormat::Time),
+            Some(Some("date-time")) => Some(TextFormat::DateTime),
+            Some(Some("uuid")) => Some(TextFormat::Uuid),
+            Some(Some("email")) => Some(TextFormat::Email),
+            Some(Some("gtin")) => Some(TextFormat::Gtin),
+            Some(Some("lot")) => Some(TextFormat::Lot),
+            Some(_) => {
+                return Err(unsupported(
+                    "format",
+                    format!("The format {} has no equivalent.", object["format"]),
+                ));
+            }
         };
 
-        let mut custom_type = HashMap::new();
-        custom_type.insert(
-            "user_data".to_owned(),
-            DynamicType::Type("Fake User".to_owned()),
-        );
-        custom_type.insert("value".to_owned(), DynamicType::String);
-        let custom_type = Fields::Object(custom_type);
+        let length = |keyword: &str| match object.get(keyword) {
+            None => Ok(None),
+            Some(length) => length
+                .as_u64()
+                .map(|length| Some(length as usize))
+                .ok_or_else(|| unsupported(keyword, "Lengths must be non-negative integers.")),
+        };
+        let pattern = match object.get("pattern") {
+            None => None,
+            Some(pattern) => {
+                let Some(pattern) = pattern.as_str() else {
+                    return Err(unsupported("pattern", "Patterns must be strings."));
+                };
+                Some(
+                    match pattern
+                        .strip_prefix("^(?:")
+                        .and_then(|p| p.strip_suffix(")$"))
+                    {
+                        // Pattern written by `json_schema`.
+                        Some(inner) if Regex::new(inner).is_ok() => inner.to_owned(),
+                        // JSON Schema patterns match anywhere in the text.
+                        _ => format!("(?s:.*)(?:{})(?s:.*)", pattern),
+                    },
+                )
+            }
+        };
 
-        let mut custom_type_2 = HashMap::new();
-        custom_type_2.insert("user_data".to_owned(), DynamicType::Type("User".to_owned()));
-        custom_type_2.insert("value".to_owned(), DynamicType::String);
+        let constraints = TextConstraints {
+            min_length: length("minLength")?,
+            max_length: length("maxLength")?,
+            pattern,
+            format,
+        };
+        if constraints.min_length.is_none()
+            && constraints.max_length.is_none()
+            && constraints.pattern.is_none()
+            && constraints.format.is_none()
+        {
+            return Ok(DynamicType::String);
+        }
 
-        let custom_type_2 = Fields::Object(custom_type_2);
+        Ok(DynamicType::Text(constraints))
+    }
 
-        let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
-                delete: None,
-                add: Some(vec![
-                    ("Another User".to_owned(), custom_type),
-                    ("Fake User".to_owned(), custom_type_2),
-                ]),
-            }),
-            is_owner: false,
+    /// Integers with a non-negative minimum are `u64`.
+    fn from_json_number(
+        object: &serde_json::Map<String, Value>,
+        kind: NumberKind,
+    ) -> Result<DynamicType, Error> {
+        check_keywords(
+            object,
+            &[
+                "type",
+                "minimum",
+                "maximum",
+                "exclusiveMinimum",
+                "exclusiveMaximum",
+                "multipleOf",
+                "x-unit",
+            ],
+        )?;
+
+        let bound = |keyword: &str| match object.get(keyword) {
+            None => Ok(None),
+            Some(bound) => bound
+                .as_f64()
+                .map(Some)
+                .ok_or_else(|| unsupported(keyword, "Bounds must be numbers.")),
+        };
+        let unit = match object.get("x-unit") {
+            None => None,
+            Some(unit) => Some(
+                unit.as_str()
+                    .ok_or_else(|| unsupported("x-unit", "Units must be strings."))?
+                    .to_owned(),
+            ),
         };
 
-        let mut result = sdk::ContractResult::new(init_state.clone());
+        let mut constraints = NumberConstraints {
+            kind,
+            min: bound("minimum")?,
+            max: bound("maximum")?,
+            exclusive_min: bound("exclusiveMinimum")?,
+            exclusive_max: bound("exclusiveMaximum")?,
+            multiple_of: bound("multipleOf")?,
+            unit,
+        };
+        if let NumberKind::i64 = kind
+            && constraints.min.is_some_and(|min| min >= 0.0)
+        {
+            constraints.kind = NumberKind::u64;
+            if constraints.min == Some(0.0) {
+                constraints.min = None;
+            }
+        }
 
-        contract_logic(&context, &mut result);
+        if constraints.min.is_none()
+            && constraints.max.is_none()
+            && constraints.exclusive_min.is_none()
+            && constraints.exclusive_max.is_none()
+            && constraints.multiple_of.is_none()
+            && constraints.unit.is_none()
+        {
+            return Ok(match constraints.kind {
+                NumberKind::i64 => DynamicType::i64,
+                NumberKind::u64 => DynamicType::u64,
+                NumberKind::f64 => DynamicType::f64,
+            });
+        }
 
-        assert!(!result.error.is_empty());
-        assert!(!result.success);
+        Ok(DynamicType::Number(constraints))
     }
 
-        #[test]
-    fn test_metadata_field() {
-        ////////////////////////////////////////////////////////////////
-        // Type definition
-        ////////////////////////////////////////////////////////////////
-        let init_state = ProductionSystem {
-            name: "example".to_owned(),
-            version: 1,
-            unit_process: vec![],
-            custom_types: HashMap::new(),
-            properties: vec![],
-        };
+    /// Arrays of any length are `Vec`, of a fixed length `Array` and with
+    /// `prefixItems` `Tuple`.
+    fn from_json_array(object: &serde_json::Map<String, Value>) -> Result<DynamicType, Error> {
+        check_keywords(
+            object,
+            &["type", "items", "prefixItems", "minItems", "maxItems"],
+        )?;
 
-        let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
-                delete: None,
-                add: Some(vec![
-                    (
-                        "UserObject".to_owned(),
-                        Fields::Object(HashMap::from([("name".to_owned(), DynamicType::String)])),
-                    ),
-                    (
-                        "UserBasic".to_owned(),
-                        Fields::Basic(Box::new(DynamicType::String)),
-                    ),
-                ]),
-            }),
-            is_owner: false,
+        let count = |keyword: &str| match object.get(keyword) {
+            None => Ok(None),
+            Some(count) => count
+                .as_u64()
+                .map(|count| Some(count as usize))
+                .ok_or_else(|| unsupported(keyword, "Counts must be non-negative integers.")),
         };
+        let (min_items, max_items) = (count("minItems")?, count("maxItems")?);
 
-        let mut result = sdk::ContractResult::new(init_state.clone());
-
-        contract_logic(&context, &mut result);
+        if let Some(prefix_items) = object.get("prefixItems") {
+            let Some(prefix_items) = prefix_items.as_array() else {
+                return Err(unsupported(
+                    "prefixItems",
+                    "It must be an array of schemas.",
+                ));
+            };
+            if object.get("items") != Some(&Value::Bool(false)) {
+                return Err(unsupported(
+                    "items",
+                    "Tuples cannot have more items than 'prefixItems', set 'items' to false.",
+                ));
+            }
+            if min_items.is_some_and(|min| min != prefix_items.len())
+                || max_items.is_some_and(|max| max != prefix_items.len())
+            {
+                return Err(unsupported(
+                    "minItems",
+                    "Tuples must have exactly the items of 'prefixItems'.",
+                ));
+            }
 
+            let mut errors = Errors::default();
+            let mut types = vec![];
+            for (index, item) in prefix_items.iter().enumerate() {
+                if let Some(type_dyn) = errors.check(
+                    DynamicType::from_json_schema(item)
+                        .map_err(|e| e.at_index(index).at_field("prefixItems")),
+                ) {
+                    types.push(type_dyn);
+                }
+            }
+            errors.finish()?;
+
+            return Ok(DynamicType::Tuple(types));
+        }
+
+        let Some(items) = object.get("items") else {
+            return Err(unsupported(
+                "items",
+                "Arrays must give the schema of their items in 'items'.",
+            ));
+        };
+        let c_type =
+            Box::new(DynamicType::from_json_schema(items).map_err(|e| e.at_field("items"))?);
+
+        match (min_items, max_items) {
+            (None | Some(0), None) => Ok(DynamicType::Vec(c_type)),
+            (Some(min), Some(max)) if min == max => Ok(DynamicType::Array(min, c_type)),
+            _ => Err(unsupported(
+                if max_items.is_some() {
+                    "maxItems"
+                } else {
+                    "minItems"
+                },
+                "Only arrays of any length or of a fixed length are supported.",
+            )),
+        }
+    }
+
+    /// Type of the `propertyNames` of a map, integer keys are recognized by
+    /// the patterns written by `key_schema`.
+    fn from_json_key(schema: &Value) -> Result<DynamicType, Error> {
+        let Some(object) = schema.as_object() else {
+            return Err(unsupported_schema(schema));
+        };
+
+        match object.get("pattern").and_then(Value::as_str) {
+            Some("^[0-9]+$") if object.len() == 1 => return Ok(DynamicType::u64),
+            Some("^-?[0-9]+$") if object.len() == 1 => return Ok(DynamicType::i64),
+            _ => {}
+        }
+        if object.contains_key("enum") || object.contains_key("$ref") {
+            return DynamicType::from_json_schema(schema);
+        }
+
+        let mut object = object.clone();
+        object.insert("type".to_owned(), json!("string"));
+        DynamicType::from_json_schema(&Value::Object(object))
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NumberKind {
+    i64,
+    u64,
+    f64,
+}
+
+/// Number whose values must satisfy every given constraint. Bounds are
+/// compared as f64. JSON numbers are always finite, so NaN and infinity need
+/// no constraint.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NumberConstraints {
+    pub kind: NumberKind,
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+    #[serde(default)]
+    pub exclusive_min: Option<f64>,
+    #[serde(default)]
+    pub exclusive_max: Option<f64>,
+    #[serde(default)]
+    pub multiple_of: Option<f64>,
+    /// Unit of measure of the values. Quantities registered as
+    /// `{"value": 1.2, "unit": "t"}` in a unit of the same dimension are
+    /// converted to it.
+    #[serde(default)]
+    pub unit: Option<String>,
+}
+
+impl NumberConstraints {
+    fn check_data(&self) -> Result<(), Error> {
+        let bounds = [
+            ("min", self.min),
+            ("max", self.max),
+            ("exclusive_min", self.exclusive_min),
+            ("exclusive_max", self.exclusive_max),
+            ("multiple_of", self.multiple_of),
+        ];
+        for (name, bound) in bounds {
+            if let Some(bound) = bound
+                && !bound.is_finite()
+            {
+                return Err(Error::new(ErrorKind::InvalidType, format!("Check error: Invalid number constraint '{}'. Constraints must be finite numbers.", name)).at_field(name));
+            }
+        }
+
+        if let Some(multiple_of) = self.multiple_of
+            && multiple_of <= 0.0
+        {
+            return Err(Error::new(ErrorKind::InvalidType, format!("Check error: Invalid number constraint 'multiple_of'. Expected a positive number but received {}.", multiple_of)).at_field("multiple_of"));
+        }
+
+        if let Some(unit) = &self.unit
+            && find_unit(unit).is_none()
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidType,
+                format!(
+                    "Check error: Unknown unit '{}'. Supported units are: {}.",
+                    unit,
+                    UNITS
+                        .iter()
+                        .map(|unit| unit.symbol)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            )
+            .at_field("unit"));
+        }
+
+        let lower = [self.min, self.exclusive_min]
+            .into_iter()
+            .flatten()
+            .reduce(f64::max);
+        let upper = [self.max, self.exclusive_max]
+            .into_iter()
+            .flatten()
+            .reduce(f64::min);
+        if let (Some(lower), Some(upper)) = (lower, upper) {
+            let exclusive = self.exclusive_min == Some(lower) || self.exclusive_max == Some(upper);
+            if lower > upper || (exclusive && lower == upper) {
+                return Err(Error::new(
+                    ErrorKind::InvalidType,
+                    format!(
+                        "Check error: Invalid number constraints. No value can be greater than {} and lower than {}.",
+                        lower, upper
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Converts a quantity given as `{"value", "unit"}` into the unit of the
+    /// type.
+    fn normalize(&self, value: &mut Value) -> Result<(), Error> {
+        let Some(unit) = &self.unit else {
+            return Ok(());
+        };
+        let Some(object) = value.as_object() else {
+            return Ok(());
+        };
+
+        let (Some(amount), Some(from), 2) = (
+            object.get("value").and_then(Value::as_f64),
+            object.get("unit").and_then(Value::as_str),
+            object.len(),
+        ) else {
+            return Err(Error::value(
+                ErrorKind::TypeMismatch,
+                format!(
+                    "Deserialization error: Invalid quantity '{}'. Quantities must be a number in '{}' or an object with a numeric 'value' and a 'unit'.",
+                    value, unit
+                ),
+                "quantity",
+                value,
+            ));
+        };
+
+        let mismatch = |message: String| {
+            Err(Error::new(ErrorKind::UnitMismatch, message)
+                .path("$")
+                .expected(unit)
+                .received(from))
+        };
+        let Some(from_unit) = find_unit(from) else {
+            return mismatch(format!(
+                "Deserialization error: Unknown unit '{}'. The value must be given in '{}' or in a unit of the same dimension.",
+                from, unit
+            ));
+        };
+        let Some(to_unit) = find_unit(unit) else {
+            return mismatch(format!(
+                "Deserialization error: Unknown unit '{}' in the type definition.",
+                unit
+            ));
+        };
+        if from_unit.dimension != to_unit.dimension {
+            return mismatch(format!(
+                "Deserialization error: Cannot convert '{}' ({}) into '{}' ({}). Units must measure the same dimension.",
+                from, from_unit.dimension, unit, to_unit.dimension
+            ));
+        }
+
+        let converted =
+            (amount * from_unit.factor + from_unit.offset - to_unit.offset) / to_unit.factor;
+        let rounded = converted.round();
+        let integral = (converted - rounded).abs() <= 1e-9 * rounded.abs().max(1.0);
+
+        *value = match self.kind {
+            NumberKind::i64
+                if integral && rounded >= i64::MIN as f64 && rounded <= i64::MAX as f64 =>
+            {
+                Value::from(rounded as i64)
+            }
+            NumberKind::u64 if integral && rounded >= 0.0 && rounded <= u64::MAX as f64 => {
+                Value::from(rounded as u64)
+            }
+            _ => Value::from(converted),
+        };
+
+        Ok(())
+    }
+
+    fn check_value(
+        &self,
+        value: Value,
+        custom_types: &HashMap<String, Fields>,
+    ) -> Result<(), Error> {
+        let base_type = match self.kind {
+            NumberKind::i64 => DynamicType::i64,
+            NumberKind::u64 => DynamicType::u64,
+            NumberKind::f64 => DynamicType::f64,
+        };
+        base_type.deserialize(value.clone(), custom_types)?;
+
+        let Some(number) = value.as_f64() else {
+            return Err(Error::value(
+                ErrorKind::TypeMismatch,
+                format!(
+                    "Deserialization error: Expected a number but received '{}'.",
+                    value
+                ),
+                "number",
+                &value,
+            ));
+        };
+
+        let violation = |expected: String| {
+            Err(Error::new(
+                ErrorKind::Constraint,
+                format!(
+                    "Deserialization error: The number {} does not satisfy the constraint '{}' of its type.",
+                    value, expected
+                ),
+            )
+            .path("$")
+            .expected(&expected)
+            .received(&value.to_string()))
+        };
+
+        if let Some(min) = self.min
+            && number < min
+        {
+            return violation(format!(">= {}", min));
+        }
+        if let Some(max) = self.max
+            && number > max
+        {
+            return violation(format!("<= {}", max));
+        }
+        if let Some(exclusive_min) = self.exclusive_min
+            && number <= exclusive_min
+        {
+            return violation(format!("> {}", exclusive_min));
+        }
+        if let Some(exclusive_max) = self.exclusive_max
+            && number >= exclusive_max
+        {
+            return violation(format!("< {}", exclusive_max));
+        }
+        if let Some(multiple_of) = self.multiple_of
+            && !is_multiple(&value, number, multiple_of)
+        {
+            return violation(format!("multiple of {}", multiple_of));
+        }
+
+        Ok(())
+    }
+
+    /// The unit is kept in the non-standard `x-unit` keyword.
+    fn json_schema(&self) -> Value {
+        let mut schema = match self.kind {
+            NumberKind::i64 => DynamicType::i64.json_schema(),
+            NumberKind::u64 => DynamicType::u64.json_schema(),
+            NumberKind::f64 => DynamicType::f64.json_schema(),
+        };
+
+        for (keyword, bound) in [
+            ("minimum", self.min),
+            ("maximum", self.max),
+            ("exclusiveMinimum", self.exclusive_min),
+            ("exclusiveMaximum", self.exclusive_max),
+            ("multipleOf", self.multiple_of),
+        ] {
+            if let Some(bound) = bound {
+                schema[keyword] = json!(bound);
+            }
+        }
+        if let NumberKind::u64 = self.kind
+            && self.min.is_some_and(|min| min < 0.0)
+        {
+            schema["minimum"] = json!(0);
+        }
+        if let Some(unit) = &self.unit {
+            schema["x-unit"] = json!(unit);
+        }
+
+        schema
+    }
+}
+
+/// Integers are checked exactly, floats allow for the rounding error of the
+/// division.
+fn is_multiple(value: &Value, number: f64, multiple_of: f64) -> bool {
+    if multiple_of.fract() == 0.0 && multiple_of <= i64::MAX as f64 {
+        if let Some(integer) = value.as_i64() {
+            return integer as i128 % multiple_of as i128 == 0;
+        }
+        if let Some(integer) = value.as_u64() {
+            return integer as i128 % multiple_of as i128 == 0;
+        }
+    }
+
+    let quotient = number / multiple_of;
+    (quotient - quotient.round()).abs() <= 1e-9 * quotient.abs().max(1.0)
+}
+
+fn check_length(len: usize, expected: usize) -> Result<(), Error> {
+    if len == expected {
+        return Ok(());
+    }
+
+    Err(Error::new(
+        ErrorKind::Constraint,
+        format!(
+            "Deserialization error: Expected an array with {} values but received {}.",
+            expected, len
+        ),
+    )
+    .path("$")
+    .expected(&format!("length {}", expected))
+    .received(&len.to_string()))
+}
+
+static DECIMAL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(-)?(\d+)(?:\.(\d+))?(?:[eE]([+-]?\d{1,3}))?$").expect("valid decimal regex")
+});
+
+/// Sign, integer digits and fraction digits of a decimal given as a JSON
+/// number or string, without leading or trailing zeros.
+fn parse_decimal(value: &Value) -> Option<(bool, String, String)> {
+    let text = match value {
+        Value::Number(number) => number.to_string(),
+        Value::String(text) => text.clone(),
+        _ => return None,
+    };
+
+    let captures = DECIMAL_REGEX.captures(&text)?;
+
+    let mut digits = format!(
+        "{}{}",
+        &captures[2],
+        captures.get(3).map_or("", |m| m.as_str())
+    );
+    let exponent: i64 = captures
+        .get(4)
+        .map_or(Some(0), |m| m.as_str().parse().ok())?;
+
+    // Moves the decimal point by the exponent.
+    let mut point = captures[2].len() as i64 + exponent;
+    if point < 0 {
+        digits = format!("{}{}", "0".repeat(-point as usize), digits);
+        point = 0;
+    }
+    if point as usize > digits.len() {
+        digits.push_str(&"0".repeat(point as usize - digits.len()));
+    }
+
+    let (integer, fraction) = digits.split_at(point as usize);
+    let integer = match integer.trim_start_matches('0') {
+        "" => "0",
+        integer => integer,
+    };
+    let fraction = fraction.trim_end_matches('0');
+    let negative = captures.get(1).is_some() && (integer != "0" || !fraction.is_empty());
+
+    Some((negative, integer.to_owned(), fraction.to_owned()))
+}
+
+/// Whether `value` is a JSON number with more significant digits than it can
+/// keep: numbers with a fraction or an exponent reach the contract as f64.
+fn is_inexact_decimal(value: &Value, integer: &str, fraction: &str) -> bool {
+    let digits = format!("{}{}", integer, fraction);
+    let digits = digits.trim_start_matches('0').trim_end_matches('0');
+
+    value.as_number().is_some_and(serde_json::Number::is_f64) && digits.len() > f64::DIGITS as usize
+}
+
+/// Unit of measure, a value in the unit is `value * factor + offset` in the
+/// base unit of its dimension.
+struct Unit {
+    symbol: &'static str,
+    dimension: &'static str,
+    factor: f64,
+    offset: f64,
+}
+
+const fn unit(symbol: &'static str, dimension: &'static str, factor: f64, offset: f64) -> Unit {
+    Unit {
+        symbol,
+        dimension,
+        factor,
+        offset,
+    }
+}
+
+const UNITS: &[Unit] = &[
+    unit("kg", "mass", 1.0, 0.0),
+    unit("g", "mass", 1e-3, 0.0),
+    unit("mg", "mass", 1e-6, 0.0),
+    unit("t", "mass", 1e3, 0.0),
+    unit("lb", "mass", 0.453_592_37, 0.0),
+    unit("L", "volume", 1.0, 0.0),
+    unit("mL", "volume", 1e-3, 0.0),
+    unit("hL", "volume", 1e2, 0.0),
+    unit("m3", "volume", 1e3, 0.0),
+    unit("gal", "volume", 3.785_411_784, 0.0),
+    unit("K", "temperature", 1.0, 0.0),
+    unit("°C", "temperature", 1.0, 273.15),
+    unit("°F", "temperature", 5.0 / 9.0, 273.15 - 32.0 * 5.0 / 9.0),
+    unit("Pa", "pressure", 1.0, 0.0),
+    unit("kPa", "pressure", 1e3, 0.0),
+    unit("bar", "pressure", 1e5, 0.0),
+    unit("psi", "pressure", 6_894.757_293_168, 0.0),
+    unit("g/L", "concentration", 1.0, 0.0),
+    unit("mg/L", "concentration", 1e-3, 0.0),
+    unit("%ABV", "alcohol content", 1.0, 0.0),
+];
+
+fn find_unit(symbol: &str) -> Option<&'static Unit> {
+    UNITS.iter().find(|unit| unit.symbol == symbol)
+}
+
+fn base_unit(dimension: &str) -> Option<&'static Unit> {
+    UNITS
+        .iter()
+        .find(|unit| unit.dimension == dimension && unit.factor == 1.0 && unit.offset == 0.0)
+}
+
+/// Well-known string formats.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextFormat {
+    /// ISO 8601 calendar date, `2024-09-30`.
+    Date,
+    /// ISO 8601 time of day with an optional UTC offset, `14:05:00`.
+    Time,
+    /// ISO 8601 date and time with a UTC offset, `2024-09-30T14:05:00Z`.
+    DateTime,
+    /// Hyphenated UUID, `123e4567-e89b-12d3-a456-426614174000`.
+    Uuid,
+    Email,
+    /// GTIN-8, GTIN-12, GTIN-13 (EAN) or GTIN-14 with a valid check digit.
+    Gtin,
+    /// GS1 batch or lot number, up to 20 characters of the GS1 character set.
+    Lot,
+}
+
+impl fmt::Display for TextFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// String whose values must satisfy every given constraint. Lengths are
+/// counted in characters and `pattern` must match the whole value.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TextConstraints {
+    #[serde(default)]
+    pub min_length: Option<usize>,
+    #[serde(default)]
+    pub max_length: Option<usize>,
+    #[serde(default)]
+    pub pattern: Option<String>,
+    #[serde(default)]
+    pub format: Option<TextFormat>,
+}
+
+/// Compiled text patterns, each pattern is compiled once however many
+/// values it checks.
+static PATTERNS: LazyLock<Mutex<HashMap<String, Regex>>> = LazyLock::new(Default::default);
+
+impl TextConstraints {
+    fn regex(&self) -> Result<Option<Regex>, Error> {
+        let Some(pattern) = &self.pattern else {
+            return Ok(None);
+        };
+
+        let mut patterns = PATTERNS.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(regex) = patterns.get(pattern) {
+            return Ok(Some(regex.clone()));
+        }
+
+        let regex = Regex::new(&format!("^(?:{})$", pattern)).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidType,
+                format!("Check error: Invalid text pattern '{}'. {}", pattern, e),
+            )
+            .at_field("pattern")
+        })?;
+        patterns.insert(pattern.clone(), regex.clone());
+
+        Ok(Some(regex))
+    }
+
+    fn check_data(&self) -> Result<(), Error> {
+        if let (Some(min_length), Some(max_length)) = (self.min_length, self.max_length)
+            && min_length > max_length
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidType,
+                format!(
+                    "Check error: Invalid text constraints. 'min_length' {} is greater than 'max_length' {}.",
+                    min_length, max_length
+                ),
+            ));
+        }
+
+        self.regex()?;
+
+        Ok(())
+    }
+
+    fn check_value(
+        &self,
+        value: Value,
+        custom_types: &HashMap<String, Fields>,
+    ) -> Result<(), Error> {
+        DynamicType::String.deserialize(value.clone(), custom_types)?;
+        let text = value.as_str().unwrap_or_default();
+
+        let violation = |expected: String| {
+            Err(Error::new(
+                ErrorKind::Constraint,
+                format!(
+                    "Deserialization error: The text {} does not satisfy the constraint '{}' of its type.",
+                    value, expected
+                ),
+            )
+            .path("$")
+            .expected(&expected)
+            .received(text))
+        };
+
+        let length = text.chars().count();
+        if let Some(min_length) = self.min_length
+            && length < min_length
+        {
+            return violation(format!("length >= {}", min_length));
+        }
+        if let Some(max_length) = self.max_length
+            && length > max_length
+        {
+            return violation(format!("length <= {}", max_length));
+        }
+        if let Some(regex) = self.regex()?
+            && !regex.is_match(text)
+        {
+            return violation(format!("pattern {}", regex.as_str()));
+        }
+        if let Some(format) = self.format
+            && !is_format(format, text)
+        {
+            return violation(format!("format {}", format));
+        }
+
+        Ok(())
+    }
+
+    /// GTIN and lot formats have no JSON Schema equivalent, they are kept
+    /// as the `gtin` and `lot` formats that validators ignore.
+    fn json_schema(&self) -> Value {
+        let mut schema = DynamicType::String.json_schema();
+        if let Some(min_length) = self.min_length {
+            schema["minLength"] = json!(min_length);
+        }
+        if let Some(max_length) = self.max_length {
+            schema["maxLength"] = json!(max_length);
+        }
+        if let Some(pattern) = &self.pattern {
+            schema["pattern"] = json!(format!("^(?:{})$", pattern));
+        }
+        if let Some(format) = self.format {
+            schema["format"] = json!(match format {
+                TextFormat::Date => "date",
+                TextFormat::Time => "time",
+                TextFormat::DateTime => "date-time",
+                TextFormat::Uuid => "uuid",
+                TextFormat::Email => "email",
+                TextFormat::Gtin => "gtin",
+                TextFormat::Lot => "lot",
+            });
+        }
+
+        schema
+    }
+}
+
+static UUID_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$")
+        .expect("valid UUID regex")
+});
+
+static EMAIL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[A-Za-z0-9.!#$%&'*+/=?^_`{|}~-]+@[A-Za-z0-9](?:[A-Za-z0-9-]*[A-Za-z0-9])?(?:\.[A-Za-z0-9](?:[A-Za-z0-9-]*[A-Za-z0-9])?)+$")
+        .expect("valid email regex")
+});
+
+static LOT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^[!"%&'()*+,\-./0-9:;<=>?A-Z_a-z]{1,20}$"#).expect("valid lot regex")
+});
+
+fn is_format(format: TextFormat, text: &str) -> bool {
+    match format {
+        TextFormat::Date => parse_date(text).is_some(),
+        TextFormat::Time => parse_time(text).is_some(),
+        TextFormat::DateTime => parse_timestamp(text).is_some(),
+        TextFormat::Uuid => UUID_REGEX.is_match(text),
+        TextFormat::Email => EMAIL_REGEX.is_match(text),
+        TextFormat::Gtin => {
+            if !matches!(text.len(), 8 | 12 | 13 | 14) || !text.bytes().all(|b| b.is_ascii_digit()) {
+                return false;
+            }
+
+            // Weights 3 and 1 alternate from the rightmost digit before the
+            // check digit.
+            let digits: Vec<u32> = text.bytes().map(|b| (b - b'0') as u32).collect();
+            let (check, body) = digits.split_last().unwrap_or((&0, &[]));
+            let sum: u32 = body
+                .iter()
+                .rev()
+                .enumerate()
+                .map(|(i, d)| if i % 2 == 0 { d * 3 } else { *d })
+                .sum();
+            (10 - sum % 10) % 10 == *check
+        }
+        TextFormat::Lot => LOT_REGEX.is_match(text),
+    }
+}
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+const SECONDS_PER_DAY: i128 = 86_400;
+
+static DATE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d{4})-(\d{2})-(\d{2})$").expect("valid date regex"));
+
+static TIME_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{2}):(\d{2}):(\d{2})(?:\.(\d+))?(?:([Zz])|([+-])(\d{2}):(\d{2}))?$")
+        .expect("valid time regex")
+});
+
+static DURATION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^P(?:(\d+)W|(?:(\d+)D)?(?:T(?:(\d+)H)?(?:(\d+)M)?(?:(\d+)(?:\.(\d+))?S)?)?)$")
+        .expect("valid duration regex")
+});
+
+/// Days since 1970-01-01 of an ISO 8601 calendar date, `YYYY-MM-DD`.
+fn parse_date(text: &str) -> Option<i64> {
+    let captures = DATE_REGEX.captures(text)?;
+
+    let year: i64 = captures[1].parse().ok()?;
+    let month: i64 = captures[2].parse().ok()?;
+    let day: i64 = captures[3].parse().ok()?;
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return None,
+    };
+
+    if !(1..=days).contains(&day) {
+        return None;
+    }
+
+    // Days from civil, the year starts in March so leap days come last.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    Some(era * 146_097 + day_of_era - 719_468)
+}
+
+/// Nanoseconds since midnight and UTC offset in seconds of an ISO 8601 time,
+/// `hh:mm:ss[.f][Z|±hh:mm]`.
+fn parse_time(text: &str) -> Option<(i128, Option<i128>)> {
+    let captures = TIME_REGEX.captures(text)?;
+
+    let number = |i: usize| -> i128 {
+        captures
+            .get(i)
+            .map_or(0, |m| m.as_str().parse().unwrap_or_default())
+    };
+
+    // Seconds allow for leap seconds.
+    if number(1) >= 24 || number(2) >= 60 || number(3) > 60 || number(7) >= 24 || number(8) >= 60 {
+        return None;
+    }
+
+    let seconds = number(1) * 3600 + number(2) * 60 + number(3);
+    let nanos = captures.get(4).map_or(0, |m| parse_fraction(m.as_str()));
+    let offset = if captures.get(5).is_some() {
+        Some(0)
+    } else {
+        captures.get(6).map(|sign| {
+            let offset = number(7) * 3600 + number(8) * 60;
+            if sign.as_str() == "-" {
+                -offset
+            } else {
+                offset
+            }
+        })
+    };
+
+    Some((seconds * NANOS_PER_SECOND + nanos, offset))
+}
+
+/// Nanoseconds since 1970-01-01T00:00:00Z of an ISO 8601 date and time with
+/// a UTC offset, `YYYY-MM-DDThh:mm:ss[.f](Z|±hh:mm)`.
+fn parse_timestamp(text: &str) -> Option<i128> {
+    let (date, time) = text.split_once(['T', 't'])?;
+    let days = parse_date(date)? as i128;
+    let (nanos, offset) = parse_time(time)?;
+
+    Some((days * SECONDS_PER_DAY - offset?) * NANOS_PER_SECOND + nanos)
+}
+
+/// Nanoseconds of an ISO 8601 duration, `PnW` or `PnDTnHnMn[.f]S`. Years and
+/// months are not accepted because their length depends on the calendar.
+fn parse_duration(text: &str) -> Option<i128> {
+    let captures = DURATION_REGEX.captures(text)?;
+
+    // At least one component, and one after `T`.
+    if text == "P" || text.ends_with('T') {
+        return None;
+    }
+
+    let number =
+        |i: usize| -> Option<i128> { captures.get(i).map_or(Some(0), |m| m.as_str().parse().ok()) };
+
+    // Durations that do not fit in the nanoseconds are not valid.
+    let seconds = [
+        (1, 7 * SECONDS_PER_DAY),
+        (2, SECONDS_PER_DAY),
+        (3, 3600),
+        (4, 60),
+        (5, 1),
+    ]
+    .into_iter()
+    .try_fold(0i128, |seconds, (i, factor)| {
+        seconds.checked_add(number(i)?.checked_mul(factor)?)
+    })?;
+    let nanos = captures.get(6).map_or(0, |m| parse_fraction(m.as_str()));
+
+    seconds.checked_mul(NANOS_PER_SECOND)?.checked_add(nanos)
+}
+
+/// Nanoseconds of the decimal digits of a second, extra digits are ignored.
+fn parse_fraction(digits: &str) -> i128 {
+    let digits: String = digits
+        .chars()
+        .chain(std::iter::repeat('0'))
+        .take(9)
+        .collect();
+    digits.parse().unwrap_or_default()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct UnitData {
+    pub name: String,
+    pub outputs: Option<Vec<RegisterData>>,
+    pub inputs: Option<Vec<RegisterData>>,
+    pub properties: Option<Vec<Properties>>,
+    /// How many errors the event reports, instead of the validation mode of
+    /// the production system. The first unit data that sets it applies to
+    /// the whole event, so any issuer can ask for every error of a batch.
+    #[serde(default)]
+    pub validation: Option<ValidationMode>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct UnitProcess {
+    pub name: String,
+    pub inputs: Vec<Data>,
+    pub outputs: Vec<Data>,
+    pub properties: Vec<Properties>,
+    /// Who can register data in this unit process, when it is not set the
+    /// `register_data` permission of the production system applies.
+    ///
+    /// This is not an allow-list of writers or roles: the contract is only
+    /// told whether the issuer is the owner of the subject, so it can restrict
+    /// a unit process to the owner but cannot tell other issuers apart. Which
+    /// issuers may emit events at all is decided by the governance.
+    #[serde(default)]
+    pub writers: Option<Access>,
+    #[serde(default)]
+    pub annotations: Option<Annotations>,
+    #[serde(default)]
+    pub balance: Option<BalanceRule>,
+}
+
+impl UnitProcess {
+    /// Checks that the issuer can register data in the unit process,
+    /// `register_data` is the permission of the production system.
+    fn check_writer(&self, register_data: Access, is_owner: bool) -> Result<(), Error> {
+        if self.writers.unwrap_or(register_data).allows(is_owner) {
+            return Ok(());
+        }
+
+        Err(Error::new(
+            ErrorKind::Unauthorized,
+            format!(
+                "Permission error: Only the owner of the subject can register data in unit process '{}'. The writers of the unit process are restricted to the owner.",
+                self.name
+            ),
+        )
+        .expected("Owner")
+        .unit_process(&self.name))
+    }
+
+    pub fn check_data(&self, custom_types: &HashMap<String, Fields>) -> Result<(), Error> {
+        self.check_definition(custom_types)
+            .map_err(|e| e.unit_process(&self.name))
+    }
+
+    fn check_definition(&self, custom_types: &HashMap<String, Fields>) -> Result<(), Error> {
+        if self.name.is_empty() {
+            return Err(Error::new(ErrorKind::EmptyName, "Check error: Unit process name cannot be empty. Please provide a valid name for the unit process."));
+        }
+
+        let mut names = vec![];
+        let mut errors = Errors::default();
+
+        for i in self.inputs.iter() {
+            errors.check(i.check_data(custom_types));
+            names.push(i.name.clone());
+        }
+
+        for o in self.outputs.iter() {
+            errors.check(o.check_data(custom_types));
+            names.push(o.name.clone());
+        }
+
+        let hash_name: HashSet<String> = HashSet::from_iter(names.iter().cloned());
+
+        if hash_name.len() != self.outputs.len() + self.inputs.len() {
+            errors.push(Error::new(
+                ErrorKind::Duplicate,
+                format!(
+                    "Check error: Duplicate names detected in unit process '{}'. Input and output names must be unique across both lists.",
+                    self.name
+                ),
+            ));
+        }
+
+        let mut properties_name = vec![];
+        for p in self.properties.iter() {
+            errors.check(p.check_data(custom_types));
+            properties_name.push(p.name.clone());
+        }
+
+        let hash_pro_name: HashSet<String> = HashSet::from_iter(properties_name.iter().cloned());
+        if hash_pro_name.len() != self.properties.len() {
+            errors.push(Error::new(
+                ErrorKind::Duplicate,
+                format!(
+                    "Check error: Duplicate property names detected in unit process '{}'. Each property must have a unique name.",
+                    self.name
+                ),
+            ));
+        }
+
+        if let Some(balance) = &self.balance {
+            errors.check(balance.check_data(self, custom_types));
+        }
+
+        errors.finish()
+    }
+
+    /// Starts a new batch of the balance rule, if any, when only one of its
+    /// sides is registered after a complete batch: the elements of the other
+    /// side belong to the previous batch and are no longer registered.
+    /// `inputs` and `outputs` are the elements registered in the event.
+    fn start_batch(&mut self, inputs: &[String], outputs: &[String]) {
+        let Some(balance) = &self.balance else {
+            return;
+        };
+        let registered = |elements: &[Data], names: &[String]| {
+            elements
+                .iter()
+                .filter(|x| names.contains(&x.name))
+                .all(|x| x.registered)
+        };
+        if !registered(&self.inputs, &balance.inputs)
+            || !registered(&self.outputs, &balance.outputs)
+        {
+            return;
+        }
+
+        let (names, elements) = match (
+            balance.inputs.iter().any(|x| inputs.contains(x)),
+            balance.outputs.iter().any(|x| outputs.contains(x)),
+        ) {
+            (true, false) => (&balance.outputs, &mut self.outputs),
+            (false, true) => (&balance.inputs, &mut self.inputs),
+            _ => return,
+        };
+        for element in elements.iter_mut().filter(|x| names.contains(&x.name)) {
+            element.registered = false;
+        }
+    }
+
+    /// Checks the balance rule, if any, once every element of both of its
+    /// sides has been registered.
+    fn check_balance(&self, custom_types: &HashMap<String, Fields>) -> Result<(), Error> {
+        let Some(balance) = &self.balance else {
+            return Ok(());
+        };
+        let (Some(inputs), Some(outputs)) = (
+            balance.total(&self.inputs, &balance.inputs, custom_types),
+            balance.total(&self.outputs, &balance.outputs, custom_types),
+        ) else {
+            return Ok(());
+        };
+
+        let expected = inputs * (1.0 - balance.losses);
+        let margin = inputs.abs() * balance.tolerance;
+        // Unit conversions leave rounding errors in the sums.
+        if (outputs - expected).abs() <= margin + 1e-9 * inputs.abs().max(1.0) {
+            return Ok(());
+        }
+
+        let unit = self
+            .inputs
+            .iter()
+            .find(|x| balance.inputs.contains(&x.name))
+            .and_then(|x| balance_unit(x, custom_types).ok().flatten())
+            .and_then(|x| base_unit(x.dimension))
+            .map(|x| format!(" {}", x.symbol))
+            .unwrap_or_default();
+        Err(Error::new(
+            ErrorKind::Unbalanced,
+            format!(
+                "Check error: Unbalanced unit process '{}'. The inputs add up to {}{}, with losses of {}% the outputs should add up to {}{} ± {}{} but they add up to {}{}.",
+                self.name,
+                inputs,
+                unit,
+                balance.losses * 100.0,
+                expected,
+                unit,
+                margin,
+                unit,
+                outputs,
+                unit
+            ),
+        )
+        .expected(&format!("{} ± {}", expected, margin))
+        .received(&outputs.to_string()))
+    }
+
+    pub fn register_data(
+        &mut self,
+        unit: UnitData,
+        custom_types: &HashMap<String, Fields>,
+        subject_id: Option<&str>,
+    ) -> Result<(), Error> {
+        let name = self.name.clone();
+        self.register_unit_data(unit, custom_types, subject_id)
+            .map_err(|e| e.unit_process(&name))
+    }
+
+    fn register_unit_data(
+        &mut self,
+        unit: UnitData,
+        custom_types: &HashMap<String, Fields>,
+        subject_id: Option<&str>,
+    ) -> Result<(), Error> {
+        if unit.inputs.is_none() && unit.outputs.is_none() {
+            return Err(Error::new(
+                ErrorKind::MissingParameter,
+                format!(
+                    "Check error: Cannot register data for unit '{}'. At least one of 'inputs' or 'outputs' must be provided.",
+                    unit.name
+                ),
+            ));
+        }
+
+        let names = |data: &Option<Vec<RegisterData>>| -> Vec<String> {
+            data.iter().flatten().map(|x| x.name.clone()).collect()
+        };
+        self.start_batch(&names(&unit.inputs), &names(&unit.outputs));
+
+        let mut errors = Errors::default();
+
+        if let Some(inputs) = unit.inputs {
+            if inputs.is_empty() {
+                errors.push(Error::new(
+                    ErrorKind::MissingParameter,
+                    format!(
+                        "Check error: Empty inputs array for unit '{}'. If 'inputs' is provided, it must contain at least one input definition.",
+                        unit.name
+                    ),
+                ));
+            }
+
+            let mut updates: usize = 0;
+
+            for element_state in self.inputs.iter_mut() {
+                for element_unit in inputs.clone() {
+                    if element_state.name == element_unit.name {
+                        let element_unit = Data::from(element_unit);
+                        errors.check(element_state.register_data(
+                            element_unit,
+                            custom_types,
+                            subject_id,
+                        ));
+                        updates += 1;
+                    }
+                }
+            }
+
+            if updates != inputs.len() {
+                let unmatched = inputs.len() - updates;
+                errors.push(Error::new(
+                    ErrorKind::NotFound,
+                    format!(
+                        "Check error: Failed to update {} input(s) in unit process '{}'. {} input name(s) do not match any defined inputs in the unit process.",
+                        unmatched, self.name, unmatched
+                    ),
+                ));
+            }
+        }
+
+        if let Some(outputs) = unit.outputs {
+            if outputs.is_empty() {
+                errors.push(Error::new(
+                    ErrorKind::MissingParameter,
+                    format!(
+                        "Check error: Empty outputs array for unit '{}'. If 'outputs' is provided, it must contain at least one output definition.",
+                        unit.name
+                    ),
+                ));
+            }
+
+            let mut updates: usize = 0;
+
+            for element_state in self.outputs.iter_mut() {
+                for element_unit in outputs.clone() {
+                    if element_state.name == element_unit.name {
+                        let element_unit = Data::from(element_unit);
+                        errors.check(element_state.register_data(
+                            element_unit,
+                            custom_types,
+                            subject_id,
+                        ));
+                        updates += 1;
+                    }
+                }
+            }
+
+            if updates != outputs.len() {
+                let unmatched = outputs.len() - updates;
+                errors.push(Error::new(
+                    ErrorKind::NotFound,
+                    format!(
+                        "Check error: Failed to update {} output(s) in unit process '{}'. {} output name(s) do not match any defined outputs in the unit process.",
+                        unmatched, self.name, unmatched
+                    ),
+                ));
+            }
+        }
+
+        if let Some(properties) = unit.properties {
+            if properties.is_empty() {
+                errors.push(Error::new(
+                    ErrorKind::MissingParameter,
+                    format!(
+                        "Check error: Empty properties array for unit '{}'. If 'properties' is provided, it must contain at least one property definition.",
+                        unit.name
+                    ),
+                ));
+            }
+
+            let mut updates: usize = 0;
+
+            for data in self.properties.iter_mut() {
+                for unit_data in properties.clone() {
+                    if data.name == unit_data.name {
+                        errors.check(data.register_data(unit_data, custom_types));
+                        updates += 1;
+                    }
+                }
+            }
+
+            if updates != properties.len() {
+                let unmatched = properties.len() - updates;
+                errors.push(Error::new(
+                    ErrorKind::NotFound,
+                    format!(
+                        "Check error: Failed to update {} propert(y/ies) in unit process '{}'. {} property name(s) do not match any defined properties in the unit process.",
+                        unmatched, self.name, unmatched
+                    ),
+                ));
+            }
+        }
+
+        errors.finish()?;
+
+        self.check_balance(custom_types)
+    }
+}
+
+/// Mass or volume balance of a unit process: what enters through `inputs`,
+/// less the declared `losses`, must leave through `outputs`. Quantities are
+/// compared in the base unit of their dimension.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct BalanceRule {
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+    /// Expected losses, as a fraction of the inputs.
+    #[serde(default)]
+    pub losses: f64,
+    /// Allowed difference between the outputs and the expected outputs, as a
+    /// fraction of the inputs.
+    #[serde(default)]
+    pub tolerance: f64,
+}
+
+impl BalanceRule {
+    fn check_data(
+        &self,
+        unit_process: &UnitProcess,
+        custom_types: &HashMap<String, Fields>,
+    ) -> Result<(), Error> {
+        if self.inputs.is_empty() || self.outputs.is_empty() {
+            return Err(Error::new(
+                ErrorKind::MissingParameter,
+                "Check error: Invalid balance. The balance must include at least one input and one output.",
+            ));
+        }
+
+        let mut errors = Errors::default();
+        if !(0.0..1.0).contains(&self.losses) {
+            errors.push(
+                Error::new(
+                    ErrorKind::Constraint,
+                    format!(
+                        "Check error: Invalid balance losses {}. Losses are a fraction of the inputs, from 0 up to 1.",
+                        self.losses
+                    ),
+                )
+                .expected("0 <= losses < 1")
+                .received(&self.losses.to_string()),
+            );
+        }
+        if !self.tolerance.is_finite() || self.tolerance < 0.0 {
+            errors.push(
+                Error::new(
+                    ErrorKind::Constraint,
+                    format!(
+                        "Check error: Invalid balance tolerance {}. The tolerance is a non-negative fraction of the inputs.",
+                        self.tolerance
+                    ),
+                )
+                .expected("tolerance >= 0")
+                .received(&self.tolerance.to_string()),
+            );
+        }
+
+        let mut dimensions: Vec<(&str, Option<&str>)> = vec![];
+        for (names, elements, kind) in [
+            (&self.inputs, &unit_process.inputs, "an input"),
+            (&self.outputs, &unit_process.outputs, "an output"),
+        ] {
+            for name in names {
+                if dimensions.iter().any(|(x, _)| x == name) {
+                    errors.push(
+                        Error::new(
+                            ErrorKind::Duplicate,
+                            format!(
+                                "Check error: Element '{}' is repeated in the balance. Each element can only be counted once.",
+                                name
+                            ),
+                        )
+                        .element(name),
+                    );
+                    continue;
+                }
+
+                let Some(data) = elements.iter().find(|x| x.name == *name) else {
+                    errors.push(
+                        Error::new(
+                            ErrorKind::NotFound,
+                            format!(
+                                "Check error: The balance includes '{}', which is not {} of the unit process.",
+                                name, kind
+                            ),
+                        )
+                        .element(name),
+                    );
+                    continue;
+                };
+
+                if let Some(unit) = errors.check(balance_unit(data, custom_types)) {
+                    dimensions.push((name, unit.map(|x| x.dimension)));
+                }
+            }
+        }
+        errors.finish()?;
+
+        let (first, dimension) = dimensions[0];
+        if let Some((name, other)) = dimensions.iter().find(|(_, x)| *x != dimension) {
+            return Err(Error::new(
+                ErrorKind::UnitMismatch,
+                format!(
+                    "Check error: The balance mixes '{}' ({}) and '{}' ({}). All the elements of a balance must be quantities of the same dimension.",
+                    first,
+                    dimension.unwrap_or("no unit"),
+                    name,
+                    other.unwrap_or("no unit")
+                ),
+            )
+            .expected(dimension.unwrap_or("no unit"))
+            .received(other.unwrap_or("no unit"))
+            .element(name));
+        }
+
+        Ok(())
+    }
+
+    /// Sum of the `names` elements in the base unit of their dimension, `None`
+    /// while any of them has not been registered.
+    fn total(
+        &self,
+        elements: &[Data],
+        names: &[String],
+        custom_types: &HashMap<String, Fields>,
+    ) -> Option<f64> {
+        let elements: Vec<&Data> = elements
+            .iter()
+            .filter(|x| names.contains(&x.name))
+            .collect();
+        if elements.iter().any(|x| !x.registered) {
+            return None;
+        }
+
+        Some(
+            elements
+                .iter()
+                .filter_map(|x| {
+                    let factor = balance_unit(x, custom_types)
+                        .ok()
+                        .flatten()
+                        .map_or(1.0, |unit| unit.factor);
+                    x.content.as_f64().map(|value| value * factor)
+                })
+                .sum(),
+        )
+    }
+}
+
+/// Dimensions of the quantities that add up, and so can be balanced. Their
+/// units have no offset.
+const BALANCE_DIMENSIONS: &[&str] = &["mass", "volume"];
+
+/// Unit of an element of a balance, only numbers without a unit or with a
+/// unit of mass or volume can be balanced.
+fn balance_unit(
+    data: &Data,
+    custom_types: &HashMap<String, Fields>,
+) -> Result<Option<&'static Unit>, Error> {
+    let unit = match custom_types.get(&data.type_name).map(Fields::unannotated) {
+        None => matches!(data.type_name.as_str(), "i64" | "u64" | "f64").then_some(None),
+        Some(Fields::Basic(c_type)) => match c_type.unannotated() {
+            DynamicType::i64 | DynamicType::u64 | DynamicType::f64 => Some(None),
+            DynamicType::Number(constraints) => {
+                Some(constraints.unit.as_deref().and_then(find_unit))
+            }
+            _ => None,
+        },
+        Some(_) => None,
+    };
+
+    unit.ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidType,
+            format!(
+                "Check error: Element '{}' cannot be part of a balance. Only numbers (i64, u64, f64 or Number) can be balanced.",
+                data.name
+            ),
+        )
+        .received(&data.type_name)
+        .element(&data.name)
+    })
+    .and_then(|unit| match unit {
+        Some(unit) if !BALANCE_DIMENSIONS.contains(&unit.dimension) => Err(Error::new(
+            ErrorKind::UnitMismatch,
+            format!(
+                "Check error: Element '{}' cannot be part of a balance. Its unit '{}' is a {}, only mass and volume quantities add up.",
+                data.name, unit.symbol, unit.dimension
+            ),
+        )
+        .expected("mass or volume")
+        .received(unit.dimension)
+        .element(&data.name)),
+        unit => Ok(unit),
+    })
+}
+
+/// Connects an output of a unit process to an input of another one, the
+/// material that leaves `from` through `output` enters `to` through `input`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+struct Link {
+    pub from: String,
+    pub output: String,
+    pub to: String,
+    pub input: String,
+    /// The link closes an intended loop of the material flow, it is left out
+    /// of the flow when checking for loops and ordering the unit processes.
+    #[serde(default)]
+    pub recirculation: bool,
+    /// The content registered in the output is copied into the input in the
+    /// same event.
+    #[serde(default)]
+    pub propagate: bool,
+}
+
+impl Link {
+    fn ends(&self) -> (&str, &str, &str, &str) {
+        (&self.from, &self.output, &self.to, &self.input)
+    }
+}
+
+impl fmt::Display for Link {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{} -> {}.{}",
+            self.from, self.output, self.to, self.input
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Target {
+    pub governance_id: String,
+    pub subject_id: String,
+    pub unit_process: String,
+    /// Type the receiving element expects. When it is not given the type of
+    /// the registered element is recorded, so the receiving subject can
+    /// verify that both types are compatible.
+    #[serde(default)]
+    pub type_name: Option<String>,
+}
+
+impl Target {
+    fn check_data(&self, type_name: &str, subject_id: &str) -> Result<(), Error> {
+        if self.governance_id.is_empty()
+            || self.subject_id.is_empty()
+            || self.unit_process.is_empty()
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidTarget,
+                "Check error: Invalid target configuration. All target fields (governance_id, subject_id, unit_process) must be non-empty.",
+            ));
+        }
+
+        for (field, id) in [
+            ("governance_id", &self.governance_id),
+            ("subject_id", &self.subject_id),
+        ] {
+            if !is_identifier(id) {
+                return Err(Error::new(
+                    ErrorKind::InvalidTarget,
+                    format!(
+                        "Check error: Invalid target {} '{}'. Identifiers are digests in base64url prefixed by the code of their algorithm.",
+                        field, id
+                    ),
+                )
+                .expected("digest identifier")
+                .received(id));
+            }
+        }
+
+        if subject_id == self.subject_id {
+            return Err(Error::new(
+                ErrorKind::InvalidTarget,
+                format!(
+                    "Check error: Invalid target subject '{}'. Data cannot target the subject that holds it, link the unit processes instead.",
+                    self.subject_id
+                ),
+            )
+            .received(&self.subject_id));
+        }
+
+        if let Some(expected) = &self.type_name
+            && expected != type_name
+        {
+            return Err(Error::new(
+                ErrorKind::TypeMismatch,
+                format!(
+                    "Check error: Target unit process '{}' expects type '{}' but the element is of type '{}'.",
+                    self.unit_process, expected, type_name
+                ),
+            )
+            .expected(expected)
+            .received(type_name));
+        }
+
+        Ok(())
+    }
+
+    fn same_receiver(&self, other: &Target) -> bool {
+        self.governance_id == other.governance_id
+            && self.subject_id == other.subject_id
+            && self.unit_process == other.unit_process
+    }
+}
+
+/// Checks the `subject_id` set by `event_name`.
+fn check_subject_id(event_name: &str, subject_id: &str) -> Result<(), Error> {
+    if is_identifier(subject_id) {
+        return Ok(());
+    }
+
+    Err(Error::new(
+        ErrorKind::Constraint,
+        format!(
+            "{} error: Invalid subject_id '{}'. Identifiers are digests in base64url prefixed by the code of their algorithm.",
+            event_name, subject_id
+        ),
+    )
+    .expected("digest identifier")
+    .received(subject_id))
+}
+
+/// Whether `id` is a digest identifier: the code of the digest algorithm
+/// (`J` Blake3, `L` SHA-2 or `M` SHA-3, preceded by `0` for 512-bit digests)
+/// followed by the digest in base64url without padding.
+fn is_identifier(id: &str) -> bool {
+    let digest = match id.len() {
+        44 => id.strip_prefix(['J', 'L', 'M']),
+        88 => id
+            .strip_prefix("0J")
+            .or_else(|| id.strip_prefix("0L"))
+            .or_else(|| id.strip_prefix("0M")),
+        _ => None,
+    };
+
+    digest.is_some_and(|digest| {
+        digest
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+    })
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Properties {
+    pub name: String,
+    pub type_name: String,
+    pub content: Value,
+    #[serde(default)]
+    pub annotations: Option<Annotations>,
+}
+
+impl Properties {
+    fn check_data(&self, custom_types: &HashMap<String, Fields>) -> Result<(), Error> {
+        if self.name.is_empty() {
+            return Err(Error::new(ErrorKind::EmptyName, "Check error: Property name cannot be empty. Please provide a valid name for the property."));
+        }
+
+        check_data(&self.type_name, self.content.clone(), custom_types)
+            .map_err(|e| e.element(&self.name))
+    }
+
+    fn register_data(
+        &mut self,
+        data: Self,
+        custom_types: &HashMap<String, Fields>,
+    ) -> Result<(), Error> {
+        let content = register_data(
+            &self.name,
+            &self.type_name,
+            custom_types,
+            &data.name,
+            &data.type_name,
+            data.content,
+        )
+        .map_err(|e| e.element(&self.name))?;
+
+        self.content = content;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Data {
+    pub name: String,
+    pub type_name: String,
+    pub metadata: Option<Metadata>,
+    pub content: Value,
+    pub targets: Option<Vec<Target>>,
+    #[serde(default)]
+    pub annotations: Option<Annotations>,
+    /// Whether the content has been registered, the content of the
+    /// definition is only an initial value.
+    #[serde(default)]
+    pub registered: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Metadata {
+    pub type_name: String,
+    pub content: Value,
+}
+
+impl From<RegisterData> for Data {
+    fn from(value: RegisterData) -> Self {
+        Data {
+            name: value.name,
+            type_name: value.type_name,
+            metadata: None,
+            content: value.content,
+            targets: value.targets,
+            annotations: None,
+            registered: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct RegisterData {
+    name: String,
+    type_name: String,
+    content: Value,
+    targets: Option<Vec<Target>>,
+}
+
+impl Data {
+    fn check_data(&self, custom_types: &HashMap<String, Fields>) -> Result<(), Error> {
+        if self.targets.is_some() {
+            return Err(Error::new(
+                ErrorKind::InvalidTarget,
+                format!(
+                    "Check error: Targets must not be set in unit process definition for element '{}'. Targets are only valid when registering data.",
+                    self.name
+                ),
+            )
+            .element(&self.name));
+        }
+
+        if self.registered {
+            return Err(Error::new(
+                ErrorKind::InvalidEvent,
+                format!(
+                    "Check error: 'registered' must not be set in unit process definition for element '{}'. The contract sets it when data is registered.",
+                    self.name
+                ),
+            )
+            .element(&self.name));
+        }
+
+        if self.name.is_empty() {
+            return Err(Error::new(ErrorKind::EmptyName, "Check error: Data element name cannot be empty. Please provide a valid name for the data element."));
+        }
+
+        let mut errors = Errors::default();
+
+        if let Some(metadata) = self.metadata.clone() {
+            errors.check(
+                check_data(&metadata.type_name, metadata.content.clone(), custom_types)
+                    .map_err(|e| e.element(&self.name)),
+            );
+        };
+
+        errors.check(
+            check_data(&self.type_name, self.content.clone(), custom_types)
+                .map_err(|e| e.element(&self.name)),
+        );
+
+        errors.finish()
+    }
+
+    fn register_data(
+        &mut self,
+        data: Self,
+        custom_types: &HashMap<String, Fields>,
+        subject_id: Option<&str>,
+    ) -> Result<(), Error> {
+        let content = register_data(
+            &self.name,
+            &self.type_name,
+            custom_types,
+            &data.name,
+            &data.type_name,
+            data.content,
+        )
+        .map_err(|e| e.element(&self.name))?;
+
+        let mut targets = data.targets;
+        if let Some(targets) = &mut targets
+            && !targets.is_empty()
+        {
+            // Without it data could target the subject that holds it.
+            let Some(subject_id) = subject_id else {
+                return Err(Error::new(
+                    ErrorKind::InvalidTarget,
+                    "Check error: Cannot register targets while the subject_id of the production system is not set. Please set it with the Init or ModifyProductionSystem event.",
+                )
+                .expected("subject_id")
+                .element(&self.name));
+            };
+
+            let mut errors = Errors::default();
+            for (index, target) in targets.iter().enumerate() {
+                if targets[..index].iter().any(|x| x.same_receiver(target)) {
+                    errors.push(
+                        Error::new(
+                            ErrorKind::Duplicate,
+                            format!(
+                                "Check error: Duplicate target unit process '{}' of subject '{}'. Each receiver can only be targeted once.",
+                                target.unit_process, target.subject_id
+                            ),
+                        )
+                        .at_index(index)
+                        .at_field("targets"),
+                    );
+                    continue;
+                }
+
+                errors.check(
+                    target
+                        .check_data(&self.type_name, subject_id)
+                        .map_err(|e| e.at_index(index).at_field("targets")),
+                );
+            }
+            errors.finish().map_err(|e| e.element(&self.name))?;
+
+            for target in targets.iter_mut() {
+                target
+                    .type_name
+                    .get_or_insert_with(|| self.type_name.clone());
+            }
+        }
+
+        self.content = content;
+        self.targets = targets;
+        self.registered = true;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+enum Events {
+    ChangeProductionSystem(ChangeProductionSystem),
+    RegisterData(Vec<UnitData>),
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+enum ChangeProductionSystem {
+    Init {
+        name: String,
+        unit_process: Option<Vec<UnitProcess>>,
+        types: Option<Vec<(String, Fields)>>,
+        properties: Option<Vec<Properties>>,
+        validation: Option<ValidationMode>,
+        permissions: Option<Permissions>,
+        links: Option<Vec<Link>>,
+        subject_id: Option<String>,
+    },
+    ModifyProductionSystem {
+        name: Option<String>,
+        delete_properties: Option<Vec<String>>,
+        modify_properties: Option<Vec<(String, Properties)>>,
+        add_properties: Option<Vec<Properties>>,
+        validation: Option<ValidationMode>,
+        permissions: Option<Permissions>,
+        subject_id: Option<String>,
+    },
+    ModifyTypes {
+        delete: Option<Vec<String>>,
+        add: Option<Vec<(String, Fields)>>,
+        /// Deleted types and the types that take their place in the elements
+        /// that use them.
+        replace: Option<Vec<(String, String)>>,
+        /// New content for the elements whose stored values do not fit the
+        /// modified types.
+        migrate: Option<Vec<Migration>>,
+    },
+    ModifyUnitProcess {
+        delete: Option<Vec<String>>,
+        modify: Option<Vec<(String, UnitProcess)>>,
+        add: Option<Vec<UnitProcess>>,
+        /// Links removed before the unit processes change.
+        delete_links: Option<Vec<Link>>,
+        /// Links added after the unit processes change.
+        add_links: Option<Vec<Link>>,
+    },
+}
+
+/// Content that replaces the stored value of an element when its type changes.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum Migration {
+    /// Content of an input or output of a unit process.
+    Data {
+        unit_process: String,
+        name: String,
+        content: Value,
+    },
+    /// Metadata content of an input or output of a unit process.
+    Metadata {
+        unit_process: String,
+        name: String,
+        content: Value,
+    },
+    /// Property of a unit process, or of the production system when
+    /// `unit_process` is not given.
+    Property {
+        unit_process: Option<String>,
+        name: String,
+        content: Value,
+    },
+    /// Renames a field of an object type and of its stored values.
+    RenameField {
+        type_name: String,
+        from: String,
+        to: String,
+    },
+    /// Adds a field to an object type, stored values get `default`.
+    AddField {
+        type_name: String,
+        field: String,
+        field_type: DynamicType,
+        default: Value,
+    },
+    /// Removes a field from an object type and from its stored values.
+    DropField { type_name: String, field: String },
+    /// Makes a field of an object type optional.
+    WrapOption { type_name: String, field: String },
+    /// Renames a variant of an Enum, `field` names the object field that holds
+    /// the Enum and is omitted for basic types.
+    RenameVariant {
+        type_name: String,
+        field: Option<String>,
+        from: String,
+        to: String,
+    },
+}
+
+/// # Safety
+///
+/// Entry point called by the Kore runtime, the pointers must reference the
+/// state, init state and event written by the host.
+#[unsafe(no_mangle)]
+pub unsafe fn main_function(
+    state_ptr: i32,
+    init_state_ptr: i32,
+    event_ptr: i32,
+    is_owner: i32,
+) -> u32 {
+    sdk::execute_contract(
+        state_ptr,
+        init_state_ptr,
+        event_ptr,
+        is_owner,
+        contract_logic,
+    )
+}
+
+/// JSON Schema (draft 2020-12) document of a production system state, see
+/// `ProductionSystem::json_schema`.
+pub fn json_schema(state: Value) -> Result<Value, Error> {
+    Ok(production_system(state)?.json_schema())
+}
+
+/// Unit processes of a production system state in the order of the material
+/// flow, see `ProductionSystem::topological_order`.
+pub fn topological_order(state: Value) -> Result<Vec<String>, Error> {
+    Ok(production_system(state)?.topological_order())
+}
+
+fn production_system(state: Value) -> Result<ProductionSystem, Error> {
+    serde_json::from_value(state.clone()).map_err(|e| {
+        Error::value(
+            ErrorKind::TypeMismatch,
+            format!(
+                "State error: The state is not a valid production system. {}",
+                e
+            ),
+            "ProductionSystem",
+            &state,
+        )
+    })
+}
+
+/// Custom types of the `$defs` (or `definitions`) of a JSON Schema document,
+/// ready for `ModifyTypes { add }`. Every construct without an equivalent
+/// custom type is reported as an `Unsupported` error with its path in the
+/// document.
+pub fn import_json_schema(schema: &Value) -> Result<Vec<(String, Fields)>, Error> {
+    let mut errors = Errors::default();
+    let mut types = vec![];
+
+    for keyword in ["$defs", "definitions"] {
+        let Some(defs) = schema.get(keyword) else {
+            continue;
+        };
+        let Some(defs) = defs.as_object() else {
+            errors.push(
+                Error::value(
+                    ErrorKind::TypeMismatch,
+                    format!("Schema error: '{}' must be an object of schemas.", keyword),
+                    "object",
+                    defs,
+                )
+                .at_field(keyword),
+            );
+            continue;
+        };
+
+        for (name, def) in defs {
+            if let Some(fields) = errors.check(
+                Fields::from_json_schema(def)
+                    .map_err(|e| e.at_field(name).at_field(keyword).element(name)),
+            ) {
+                types.push((name.clone(), fields));
+            }
+        }
+    }
+
+    if types.is_empty() && errors.is_empty() {
+        return Err(Error::new(
+            ErrorKind::MissingParameter,
+            "Schema error: The document has no types. Define them in '$defs'.",
+        ));
+    }
+    errors.finish()?;
+
+    Ok(types)
+}
+
+/// Keywords that only annotate a schema, they are ignored where the custom
+/// types have no place for them.
+const SCHEMA_ANNOTATIONS: &[&str] = &[
+    "$schema",
+    "$id",
+    "$comment",
+    "title",
+    "description",
+    "deprecated",
+    "default",
+    "examples",
+    "readOnly",
+    "writeOnly",
+];
+
+fn unsupported(keyword: &str, message: impl fmt::Display) -> Error {
+    Error::new(
+        ErrorKind::Unsupported,
+        format!(
+            "Schema error: Unsupported JSON Schema keyword '{}'. {}",
+            keyword, message
+        ),
+    )
+    .path("$")
+    .at_field(keyword)
+}
+
+fn unsupported_schema(schema: &Value) -> Error {
+    Error::value(
+        ErrorKind::Unsupported,
+        format!(
+            "Schema error: Unsupported schema {}. Only object schemas are supported.",
+            schema
+        ),
+        "object",
+        schema,
+    )
+}
+
+/// Reports every keyword of the schema that is not in `keywords` nor an
+/// annotation.
+fn check_keywords(schema: &serde_json::Map<String, Value>, keywords: &[&str]) -> Result<(), Error> {
+    let mut errors = Errors::default();
+    for keyword in schema.keys() {
+        if !keywords.contains(&keyword.as_str()) && !SCHEMA_ANNOTATIONS.contains(&keyword.as_str())
+        {
+            errors.push(unsupported(
+                keyword,
+                "It has no equivalent in the custom types.",
+            ));
+        }
+    }
+    errors.finish()
+}
+
+/// `title`, `description` and `deprecated` of a schema, if any.
+fn schema_annotations(schema: &serde_json::Map<String, Value>) -> Option<Annotations> {
+    let text = |keyword: &str| {
+        schema
+            .get(keyword)
+            .and_then(Value::as_str)
+            .map(str::to_owned)
+    };
+    let annotations = Annotations {
+        description: text("description"),
+        label: text("title"),
+        deprecated: schema
+            .get("deprecated")
+            .and_then(Value::as_bool)
+            .unwrap_or_default(),
+    };
+
+    (annotations != Annotations::default()).then_some(annotations)
+}
+
+/// # Safety
+///
+/// Entry point called by the Kore runtime, the pointer must reference the
+/// init state written by the host.
+#[unsafe(no_mangle)]
+pub unsafe fn init_check_function(state_ptr: i32) -> u32 {
+    sdk::check_init_data(state_ptr, init_logic)
+}
+
+fn init_logic(_state: &ProductionSystem, contract_result: &mut sdk::ContractInitCheck) {
+    contract_result.success = true;
+}
+
+fn contract_logic(
+    context: &sdk::Context<Events>,
+    contract_result: &mut sdk::ContractResult<ProductionSystem>,
+) {
+    // The event is applied to a working copy, the state is only replaced when
+    // every operation of the event succeeds.
+    let mut state = contract_result.state.clone();
+
+    match apply_event(&mut state, context.event.clone(), context.is_owner) {
+        Ok(()) => {
+            contract_result.state = state;
+            contract_result.success = true;
+        }
+        Err(e) => {
+            let validation = match &context.event {
+                Events::RegisterData(units) => units.iter().find_map(|x| x.validation),
+                Events::ChangeProductionSystem(..) => None,
+            };
+            let e = match validation.unwrap_or(state.validation) {
+                ValidationMode::FirstError => e.first(),
+                ValidationMode::AllErrors => e,
+            };
+            contract_result.error = serde_json::to_string(&e).unwrap_or_else(|_| e.to_string())
+        }
+    }
+}
+
+fn apply_event(state: &mut ProductionSystem, event: Events, is_owner: bool) -> Result<(), Error> {
+    state.permissions.check(&event, is_owner)?;
+
+    if let Events::ChangeProductionSystem(ChangeProductionSystem::Init { .. }) = event {
+        if state.version != 0 {
+            return Err(Error::new(ErrorKind::InvalidEvent, "Contract error: Cannot emit Init event when version is not 0. The Init event can only be used to initialize a new contract (version must be 0)."));
+        }
+    } else if state.version == 0 {
+        return Err(Error::new(ErrorKind::InvalidEvent, "Contract error: The first event must be an Init event. Please initialize the contract with an Init event before performing other operations."));
+    }
+
+    if let Events::ChangeProductionSystem(..) = event {
+        state.version += 1;
+    }
+
+    let mut errors = Errors::default();
+
+    match event {
+        Events::ChangeProductionSystem(operation) => match operation {
+            ChangeProductionSystem::Init {
+                name,
+                unit_process,
+                properties,
+                types,
+                validation,
+                permissions,
+                links,
+                subject_id,
+            } => {
+                if name.is_empty() {
+                    errors.push(Error::new(ErrorKind::EmptyName, "Init error: Production system name cannot be empty. Please provide a valid name for the production system."));
+                }
+
+                if let Some(subject_id) = &subject_id {
+                    errors.check(check_subject_id("Init", subject_id));
+                }
+
+                state.subject_id = subject_id;
+
+                state.name = name;
+
+                if let Some(validation) = validation {
+                    state.validation = validation;
+                }
+
+                if let Some(permissions) = permissions {
+                    if is_owner {
+                        state.permissions = permissions;
+                    } else {
+                        errors.push(permissions_not_owner());
+                    }
+                }
+
+                // Unit processes and properties are checked against the custom
+                // types, they are not checked when the types are not valid.
+                if let Some(types) = types
+                    && errors.check(add_types(state, types)).is_none()
+                {
+                    return errors.finish();
+                }
+
+                if let Some(unit_process) = unit_process {
+                    errors.check(add_unit_process(state, unit_process));
+                }
+
+                if let Some(properties) = properties {
+                    errors.check(add_new_properties(state, properties));
+                }
+
+                if let Some(links) = links {
+                    state.links = links;
+                    errors.check(state.check_links());
+                }
+            }
+            ChangeProductionSystem::ModifyProductionSystem {
+                name,
+                delete_properties,
+                add_properties,
+                modify_properties,
+                validation,
+                permissions,
+                subject_id,
+            } => {
+                if name.is_none()
+                    && delete_properties.is_none()
+                    && add_properties.is_none()
+                    && modify_properties.is_none()
+                    && validation.is_none()
+                    && permissions.is_none()
+                    && subject_id.is_none()
+                {
+                    return Err(Error::new(
+                        ErrorKind::MissingParameter,
+                        "ModifyProductionSystem error: At least one parameter must be provided. Please specify 'name', 'delete_properties', 'add_properties', 'modify_properties', 'validation', 'permissions' or 'subject_id'.",
+                    ));
+                }
+
+                if let Some(subject_id) = subject_id {
+                    errors.check(check_subject_id("ModifyProductionSystem", &subject_id));
+                    state.subject_id = Some(subject_id);
+                }
+
+                if let Some(validation) = validation {
+                    state.validation = validation;
+                }
+
+                if let Some(permissions) = permissions {
+                    if is_owner {
+                        state.permissions = permissions;
+                    } else {
+                        errors.push(permissions_not_owner());
+                    }
+                }
+
+                if let Some(name) = name {
+                    if name.is_empty() {
+                        errors.push(Error::new(ErrorKind::EmptyName, "ModifyProductionSystem error: New production system name cannot be empty. Please provide a valid name."));
+                    }
+
+                    state.name = name;
+                }
+
+                if let Some(delete_properties) = delete_properties {
+                    if delete_properties.is_empty() {
+                        errors.push(Error::new(ErrorKind::MissingParameter, "ModifyProductionSystem error: The 'delete_properties' list cannot be empty. Please specify at least one property to delete."));
+                    }
+
+                    for name in delete_properties.iter() {
+                        if let Some(pos) = state.properties.iter().position(|x| x.name == *name) {
+                            state.properties.remove(pos);
+                        } else {
+                            errors.push(Error::new(
+                                ErrorKind::NotFound,
+                                format!(
+                                    "ModifyProductionSystem error: Cannot delete property '{}'. This property does not exist in the production system.",
+                                    name
+                                ),
+                            )
+                            .element(name));
+                        }
+                    }
+                }
+
+                if let Some(modify_properties) = modify_properties {
+                    if modify_properties.is_empty() {
+                        errors.push(Error::new(ErrorKind::MissingParameter, "ModifyProductionSystem error: The 'modify_properties' list cannot be empty. Please specify at least one property to modify."));
+                    }
+
+                    for (name, propiertie) in modify_properties {
+                        if errors.check(propiertie.check_data(&state.custom_types)).is_none() {
+                            continue;
+                        }
+
+                        if let Some(existing) =
+                            state.properties.iter_mut().find(|x| x.name == name)
+                        {
+                            *existing = propiertie;
+                        } else {
+                            errors.push(Error::new(
+                                ErrorKind::NotFound,
+                                format!(
+                                    "ModifyProductionSystem error: Cannot modify property '{}'. This property does not exist in the production system.",
+                                    name
+                                ),
+                            )
+                            .element(&name));
+                        }
+                    }
+                }
+
+                if let Some(add_properties) = add_properties {
+                    errors.check(add_new_properties(state, add_properties));
+                }
+            }
+            ChangeProductionSystem::ModifyTypes {
+                delete,
+                add,
+                replace,
+                migrate,
+            } => {
+                if delete.is_none() && add.is_none() && migrate.is_none() {
+                    return Err(Error::new(ErrorKind::MissingParameter, "ModifyTypes error: At least one parameter must be provided. Please specify 'add', 'delete' or 'migrate'."));
+                }
+
+                let delete = delete.unwrap_or_default();
+                for name in delete.iter() {
+                    if state.custom_types.remove(name).is_none() {
+                        errors.push(Error::new(
+                            ErrorKind::NotFound,
+                            format!(
+                                "ModifyTypes error: Cannot delete type '{}'. This type does not exist in the schema.",
+                                name
+                            ),
+                        )
+                        .element(name));
+                    }
+                }
+
+                // Types added by the event take the place of the deleted ones,
+                // their previous definitions are not dependants.
+                let added: Vec<String> =
+                    add.iter().flatten().map(|(name, _)| name.clone()).collect();
+
+                let replace = replace.unwrap_or_default();
+                for (from, to) in replace.iter() {
+                    if !delete.contains(from) {
+                        errors.push(Error::new(
+                            ErrorKind::NotFound,
+                            format!(
+                                "ModifyTypes error: Cannot replace type '{}'. Only deleted types can be replaced, please add it to 'delete'.",
+                                from
+                            ),
+                        )
+                        .element(from));
+                        continue;
+                    }
+
+                    // Elements can also take a built-in type, references
+                    // in the custom types are checked below.
+                    if !state.custom_types.contains_key(to)
+                        && !added.contains(to)
+                        && !BUILT_IN_TYPES.contains(&to.as_str())
+                    {
+                        errors.push(
+                            Error::new(
+                                ErrorKind::UnknownType,
+                                format!(
+                                    "ModifyTypes error: Cannot replace type '{}' with '{}'. The type taking its place must be a built-in type, a custom type that is not deleted or a type added by the event.",
+                                    from, to
+                                ),
+                            )
+                            .received(to)
+                            .element(from),
+                        );
+                        continue;
+                    }
+
+                    state.replace_type(from, to);
+                }
+                let mut remaining = state.clone();
+                remaining
+                    .custom_types
+                    .retain(|name, _| !added.contains(name));
+
+                for name in delete.iter().filter(|name| !added.contains(name)) {
+                    let dependants = remaining.type_dependants(name);
+                    if !dependants.is_empty() {
+                        errors.push(Error::new(
+                            ErrorKind::InUse,
+                            format!(
+                                "ModifyTypes error: Cannot delete type '{}'. It is still used by: {}. Change those elements first or give a type to take its place in 'replace'.",
+                                name,
+                                dependants.join(", ")
+                            ),
+                        )
+                        .element(name));
+                    }
+                }
+
+                if !errors.is_empty() {
+                    return errors.finish();
+                }
+
+                // Added types are checked with the rest, replaced references
+                // may now close a cycle.
+                if let Some(add) = add {
+                    errors.check(add_types(state, add));
+                } else if !replace.is_empty() {
+                    errors.check(check_types(&state.custom_types));
+                }
+
+                for migration in migrate.unwrap_or_default() {
+                    errors.check(state.migrate(migration));
+                }
+
+                // Redefined and replaced types may no longer accept the stored
+                // content.
+                if errors.is_empty() {
+                    errors.check(state.check_content());
+                }
+            }
+            ChangeProductionSystem::ModifyUnitProcess {
+                modify,
+                add,
+                delete,
+                delete_links,
+                add_links,
+            } => {
+                if delete.is_none()
+                    && add.is_none()
+                    && modify.is_none()
+                    && delete_links.is_none()
+                    && add_links.is_none()
+                {
+                    return Err(Error::new(
+                        ErrorKind::MissingParameter,
+                        "ModifyUnitProcess error: At least one parameter must be provided. Please specify 'add', 'modify', 'delete', 'add_links' or 'delete_links'.",
+                    ));
+                }
+
+                if let Some(delete_links) = delete_links {
+                    if delete_links.is_empty() {
+                        errors.push(Error::new(ErrorKind::MissingParameter, "ModifyUnitProcess error: The 'delete_links' list cannot be empty. Please specify at least one link to delete."));
+                    }
+
+                    for link in delete_links {
+                        if let Some(pos) = state.links.iter().position(|x| x.ends() == link.ends())
+                        {
+                            state.links.remove(pos);
+                        } else {
+                            errors.push(Error::new(
+                                ErrorKind::NotFound,
+                                format!(
+                                    "ModifyUnitProcess error: Cannot delete link '{}'. This link does not exist in the production system.",
+                                    link
+                                ),
+                            )
+                            .unit_process(&link.from));
+                        }
+                    }
+                }
+
+                if let Some(delete) = delete {
+                    if delete.is_empty() {
+                        errors.push(Error::new(ErrorKind::MissingParameter, "ModifyUnitProcess error: The 'delete' list cannot be empty. Please specify at least one unit process to delete."));
+                    }
+
+                    for name in delete {
+                        if let Some(pos) = state.unit_process.iter().position(|x| x.name == name) {
+                            state.unit_process.remove(pos);
+                        } else {
+                            errors.push(Error::new(
+                                ErrorKind::NotFound,
+                                format!(
+                                    "ModifyUnitProcess error: Cannot delete unit process '{}'. This unit process does not exist in the production system.",
+                                    name
+                                ),
+                            )
+                            .unit_process(&name));
+                        }
+                    }
+                }
+
+                if let Some(modify) = modify {
+                    if modify.is_empty() {
+                        errors.push(Error::new(ErrorKind::MissingParameter, "ModifyUnitProcess error: The 'modify' list cannot be empty. Please specify at least one unit process to modify."));
+                    }
+
+                    for (name, process) in modify {
+                        if errors.check(process.check_data(&state.custom_types)).is_none() {
+                            continue;
+                        }
+
+                        if let Some(existing) =
+                            state.unit_process.iter_mut().find(|x| x.name == name)
+                        {
+                            *existing = process;
+                        } else {
+                            errors.push(Error::new(
+                                ErrorKind::NotFound,
+                                format!(
+                                    "ModifyUnitProcess error: Cannot modify unit process '{}'. This unit process does not exist in the production system.",
+                                    name
+                                ),
+                            )
+                            .unit_process(&name));
+                        }
+                    }
+                }
+
+                if let Some(add) = add {
+                    errors.check(add_unit_process(state, add));
+                }
+
+                if let Some(add_links) = add_links {
+                    if add_links.is_empty() {
+                        errors.push(Error::new(ErrorKind::MissingParameter, "ModifyUnitProcess error: The 'add_links' list cannot be empty. Please specify at least one link to add."));
+                    }
+
+                    state.links.extend(add_links);
+                }
+
+                // Deleted and modified unit processes may leave links without
+                // one of their ends.
+                if errors.is_empty() {
+                    errors.check(state.check_links());
+                }
+            }
+        },
+        Events::RegisterData(data) => {
+            if data.is_empty() {
+                return Err(Error::new(ErrorKind::MissingParameter, "RegisterData error: The data list cannot be empty. Please provide at least one unit data entry to register."));
+            }
+
+            // Inputs and outputs registered by the event, as unit process and
+            // element names.
+            let mut registered: Vec<(String, String)> = vec![];
+            for d in data {
+                let Some(unit_process) = state.unit_process.iter_mut().find(|x| x.name == d.name)
+                else {
+                    errors.push(Error::new(
+                        ErrorKind::NotFound,
+                        format!(
+                            "RegisterData error: No unit process found with name '{}'. Please ensure the unit process exists before registering data to it.",
+                            d.name
+                        ),
+                    )
+                    .unit_process(&d.name));
+                    continue;
+                };
+
+                if errors
+                    .check(unit_process.check_writer(state.permissions.register_data, is_owner))
+                    .is_none()
+                {
+                    continue;
+                }
+
+                let unit_name = d.name.clone();
+                let elements: Vec<(String, String)> = d
+                    .inputs
+                    .iter()
+                    .chain(d.outputs.iter())
+                    .flatten()
+                    .map(|x| (unit_name.clone(), x.name.clone()))
+                    .collect();
+                if errors
+                    .check(unit_process.register_data(
+                        d,
+                        &state.custom_types,
+                        state.subject_id.as_deref(),
+                    ))
+                    .is_some()
+                {
+                    registered.extend(elements);
+                }
+            }
+
+            if errors.is_empty() {
+                errors.check(state.propagate(&registered, is_owner));
+            }
+        }
+    }
+
+    errors.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, vec};
+
+    use crate::{
+        Access, Annotations, BalanceRule, ChangeProductionSystem, Data, DynamicType, Error,
+        ErrorKind, Events, Fields, Link, MAX_DEPTH, Metadata, Migration, NumberConstraints,
+        NumberKind, Permissions, ProductionSystem, Properties, RegisterData, Target,
+        TextConstraints, TextFormat, UnitData, UnitProcess, ValidationMode, contract_logic,
+        import_json_schema, json_schema, topological_order,
+    };
+    use kore_contract_sdk as sdk;
+    use serde_json::{Value, json};
+
+    impl PartialEq for DynamicType {
+        fn eq(&self, other: &Self) -> bool {
+            use DynamicType::*;
+            match (self, other) {
+                (String, String) => true,
+                (i64, i64) => true,
+                (u64, u64) => true,
+                (f64, f64) => true,
+                (bool, bool) => true,
+                (Vec(a), Vec(b)) => a == b,
+                (Option(a), Option(b)) => a == b,
+                (Enum(a), Enum(b)) => a == b,
+                (Type(a), Type(b)) => a == b,
+                (Dummy, Dummy) => true,
+                (Number(a), Number(b)) => a == b,
+                (Text(a), Text(b)) => a == b,
+                (Timestamp, Timestamp) => true,
+                (Date, Date) => true,
+                (Duration, Duration) => true,
+                (Decimal { scale: a }, Decimal { scale: b }) => a == b,
+                (Map(a, b), Map(c, d)) => a == c && b == d,
+                (Tuple(a), Tuple(b)) => a == b,
+                (Array(a, b), Array(c, d)) => a == c && b == d,
+                (Default(a, b), Default(c, d)) => a == c && b == d,
+                (Annotated(a, b), Annotated(c, d)) => a == c && b == d,
+                _ => false,
+            }
+        }
+    }
+
+    impl Eq for DynamicType {}
+
+    impl PartialEq for NumberConstraints {
+        fn eq(&self, other: &Self) -> bool {
+            self.kind == other.kind
+                && self.min == other.min
+                && self.max == other.max
+                && self.exclusive_min == other.exclusive_min
+                && self.exclusive_max == other.exclusive_max
+                && self.multiple_of == other.multiple_of
+                && self.unit == other.unit
+        }
+    }
+
+    impl PartialEq for TextConstraints {
+        fn eq(&self, other: &Self) -> bool {
+            self.min_length == other.min_length
+                && self.max_length == other.max_length
+                && self.pattern == other.pattern
+                && self.format == other.format
+        }
+    }
+
+    impl PartialEq for Fields {
+        fn eq(&self, other: &Self) -> bool {
+            use Fields::*;
+            match (self, other) {
+                (Basic(a), Basic(b)) => a == b,
+                (Object(a), Object(b)) => a == b,
+                (
+                    Annotated {
+                        fields: a,
+                        annotations: b,
+                    },
+                    Annotated {
+                        fields: c,
+                        annotations: d,
+                    },
+                ) => a == c && b == d,
+                _ => false,
+            }
+        }
+    }
+
+    impl Eq for Fields {}
+
+    impl PartialEq for Data {
+        fn eq(&self, other: &Self) -> bool {
+            self.name == other.name
+                && self.type_name == other.type_name
+                && self.content == other.content
+                && self.targets == other.targets
+                && self.annotations == other.annotations
+                && self.registered == other.registered
+        }
+    }
+
+    impl Eq for Data {}
+
+    impl PartialEq for Metadata {
+        fn eq(&self, other: &Self) -> bool {
+            self.type_name == other.type_name
+                && self.content == other.content
+        }
+    }
+
+    impl Eq for Metadata {}
+
+
+    impl PartialEq for Target {
+        fn eq(&self, other: &Self) -> bool {
+            self.governance_id == other.governance_id
+                && self.subject_id == other.subject_id
+                && self.unit_process == other.unit_process
+                && self.type_name == other.type_name
+        }
+    }
+
+    impl Eq for Target {}
+
+    
+
+    impl PartialEq for Properties {
+        fn eq(&self, other: &Self) -> bool {
+            self.name == other.name
+                && self.type_name == other.type_name
+                && self.content == other.content
+                && self.annotations == other.annotations
+        }
+    }
+    
+    impl Eq for Properties {}
+
+    impl PartialEq for UnitProcess {
+        fn eq(&self, other: &Self) -> bool {
+            self.name == other.name
+                && self.inputs == other.inputs
+                && self.outputs == other.outputs
+                && self.properties == other.properties
+                && self.writers == other.writers
+                && self.annotations == other.annotations
+                && self.balance == other.balance
+        }
+    }
+
+    impl Eq for UnitProcess {}
+
+    #[test]
+    fn register_types_type_cycle() {
+        let mut custom_type = HashMap::new();
+        custom_type.insert("name".to_owned(), DynamicType::String);
+        custom_type.insert(
+            "value".to_owned(),
+            DynamicType::Type("Another User".to_owned()),
+        );
+
+        let custom_type = Fields::Object(custom_type);
+
+        let mut types = HashMap::new();
+        types.insert("User".to_owned(), custom_type);
+
+        let init_state = ProductionSystem {
+            name: "wine process".to_owned(),
+            version: 1,
+            unit_process: vec![],
+            custom_types: types,
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        let mut custom_type = HashMap::new();
+        custom_type.insert(
+            "user_data".to_owned(),
+            DynamicType::Type("Fake User".to_owned()),
+        );
+        custom_type.insert("value".to_owned(), DynamicType::String);
+        let custom_type = Fields::Object(custom_type);
+
+        let mut custom_type_2 = HashMap::new();
+        custom_type_2.insert("user_data".to_owned(), DynamicType::Type("User".to_owned()));
+        custom_type_2.insert("value".to_owned(), DynamicType::String);
+
+        let custom_type_2 = Fields::Object(custom_type_2);
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![
+                    ("Another User".to_owned(), custom_type),
+                    ("Fake User".to_owned(), custom_type_2),
+                ]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(!result.error.is_empty());
+        assert!(!result.success);
+    }
+
+        #[test]
+    fn test_metadata_field() {
+        ////////////////////////////////////////////////////////////////
+        // Type definition
+        ////////////////////////////////////////////////////////////////
+        let init_state = ProductionSystem {
+            name: "example".to_owned(),
+            version: 1,
+            unit_process: vec![],
+            custom_types: HashMap::new(),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![
+                    (
+                        "UserObject".to_owned(),
+                        Fields::Object(HashMap::from([("name".to_owned(), DynamicType::String)])),
+                    ),
+                    (
+                        "UserBasic".to_owned(),
+                        Fields::Basic(Box::new(DynamicType::String)),
+                    ),
+                ]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(result.error.is_empty());
+        assert_eq!(
+            result.state.custom_types.get("UserObject").unwrap().clone(),
+            Fields::Object(HashMap::from([("name".to_owned(), DynamicType::String)]))
+        );
+        assert_eq!(
+            result.state.custom_types.get("UserBasic").unwrap().clone(),
+            Fields::Basic(Box::new(DynamicType::String))
+        );
+        assert_eq!(result.state.custom_types.len(), 2);
+
+        ////////////////////////////////////////////////////////////////
+        // Unit process
+        ////////////////////////////////////////////////////////////////
+        let unit_process = UnitProcess {
+            name: "Unit example".to_owned(),
+            outputs: vec![
+                Data {
+                    name: "Example Object".to_owned(),
+                    type_name: "UserObject".to_owned(),
+                    content: json!({"name": "ExampleName"}),
+                    targets: None,
+                    metadata: None,
+                    annotations: None,
+                    registered: false,
+                },
+                Data {
+                    name: "Example String".to_owned(),
+                    type_name: "String".to_owned(),
+                    content: json!("ExampleString"),
+                    targets: None,
+                    metadata: None,
+                    annotations: None,
+                    registered: false,
+                },
+            ],
+            inputs: vec![Data {
+                name: "Example Basic".to_owned(),
+                type_name: "UserBasic".to_owned(),
+                content: json!("ExampleBasic"),
+                targets: None,
+                metadata: Some(Metadata { type_name: "UserObject".to_owned(), content: json!({"name": "Metadata"}) }),
+                annotations: None,
+                registered: false,
+            }],
+            properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                delete: None,
+                modify: None,
+                add: Some(vec![unit_process]),
+                delete_links: None,
+                add_links: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(result.state);
+        contract_logic(&context, &mut result);
+
+        println!("{}", result.error);
+        assert!(result.error.is_empty());
+
+        assert_eq!(result.state.unit_process.len(), 1);
+        assert_eq!(result.state.unit_process[0].name, "Unit example");
+        assert_eq!(result.state.unit_process[0].outputs.len(), 2);
+
+        let data = result.state.unit_process[0].outputs[0].clone();
+        assert_eq!(data.name, "Example Object");
+        assert_eq!(data.type_name, "UserObject");
+        assert_eq!(data.content, json!({"name": "ExampleName"}));
+        let data = result.state.unit_process[0].outputs[1].clone();
+        assert_eq!(data.name, "Example String");
+        assert_eq!(data.type_name, "String");
+        assert_eq!(data.content, json!("ExampleString"));
+
+        let data = result.state.unit_process[0].inputs[0].clone();
+        assert_eq!(data.name, "Example Basic");
+        assert_eq!(data.type_name, "UserBasic");
+        assert_eq!(data.content, json!("ExampleBasic"));
+        assert_eq!(data.metadata.unwrap(), Metadata { type_name: "UserObject".to_owned(), content: json!({"name": "Metadata"}) });
+
+        assert!(result.success);
+    }
+
+    #[test]
+    fn test_string() {
+        ////////////////////////////////////////////////////////////////
+        // Type definition
+        ////////////////////////////////////////////////////////////////
+        let init_state = ProductionSystem {
+            name: "example".to_owned(),
+            version: 1,
+            unit_process: vec![],
+            custom_types: HashMap::new(),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![
+                    (
+                        "UserObject".to_owned(),
+                        Fields::Object(HashMap::from([("name".to_owned(), DynamicType::String)])),
+                    ),
+                    (
+                        "UserBasic".to_owned(),
+                        Fields::Basic(Box::new(DynamicType::String)),
+                    ),
+                ]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(result.error.is_empty());
+        assert_eq!(
+            result.state.custom_types.get("UserObject").unwrap().clone(),
+            Fields::Object(HashMap::from([("name".to_owned(), DynamicType::String)]))
+        );
+        assert_eq!(
+            result.state.custom_types.get("UserBasic").unwrap().clone(),
+            Fields::Basic(Box::new(DynamicType::String))
+        );
+        assert_eq!(result.state.custom_types.len(), 2);
+
+        ////////////////////////////////////////////////////////////////
+        // Unit process
+        ////////////////////////////////////////////////////////////////
+        let unit_process = UnitProcess {
+            name: "Unit example".to_owned(),
+            outputs: vec![
+                Data {
+                    name: "Example Object".to_owned(),
+                    type_name: "UserObject".to_owned(),
+                    content: json!({"name": "ExampleName"}),
+                    targets: None,
+                    metadata: None,
+                    annotations: None,
+                    registered: false,
+                },
+                Data {
+                    name: "Example String".to_owned(),
+                    type_name: "String".to_owned(),
+                    content: json!("ExampleString"),
+                    targets: None,
+                    metadata: None,
+                    annotations: None,
+                    registered: false,
+                },
+            ],
+            inputs: vec![Data {
+                name: "Example Basic".to_owned(),
+                type_name: "UserBasic".to_owned(),
+                content: json!("ExampleBasic"),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                delete: None,
+                modify: None,
+                add: Some(vec![unit_process]),
+                delete_links: None,
+                add_links: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(result.state);
+        contract_logic(&context, &mut result);
+
+        println!("{}", result.error);
+        assert!(result.error.is_empty());
+
+        assert_eq!(result.state.unit_process.len(), 1);
+        assert_eq!(result.state.unit_process[0].name, "Unit example");
+        assert_eq!(result.state.unit_process[0].outputs.len(), 2);
+
+        let data = result.state.unit_process[0].outputs[0].clone();
+        assert_eq!(data.name, "Example Object");
+        assert_eq!(data.type_name, "UserObject");
+        assert_eq!(data.content, json!({"name": "ExampleName"}));
+        let data = result.state.unit_process[0].outputs[1].clone();
+        assert_eq!(data.name, "Example String");
+        assert_eq!(data.type_name, "String");
+        assert_eq!(data.content, json!("ExampleString"));
+
+        let data = result.state.unit_process[0].inputs[0].clone();
+        assert_eq!(data.name, "Example Basic");
+        assert_eq!(data.type_name, "UserBasic");
+        assert_eq!(data.content, json!("ExampleBasic"));
+
+        assert!(result.success);
+    }
+
+    #[test]
+    fn test_i64() {
+        ////////////////////////////////////////////////////////////////
+        // Type definition
+        ////////////////////////////////////////////////////////////////
+        let init_state = ProductionSystem {
+            name: "example".to_owned(),
+            version: 1,
+            unit_process: vec![],
+            custom_types: HashMap::new(),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![
+                    (
+                        "UserObject".to_owned(),
+                        Fields::Object(HashMap::from([("value".to_owned(), DynamicType::i64)])),
+                    ),
+                    (
+                        "UserBasic".to_owned(),
+                        Fields::Basic(Box::new(DynamicType::i64)),
+                    ),
+                ]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(result.error.is_empty());
+        assert_eq!(
+            result.state.custom_types.get("UserObject").unwrap().clone(),
+            Fields::Object(HashMap::from([("value".to_owned(), DynamicType::i64)]))
+        );
+        assert_eq!(
+            result.state.custom_types.get("UserBasic").unwrap().clone(),
+            Fields::Basic(Box::new(DynamicType::i64))
+        );
+        assert_eq!(result.state.custom_types.len(), 2);
+
+        ////////////////////////////////////////////////////////////////
+        // Unit process
+        ////////////////////////////////////////////////////////////////
+        let unit_process = UnitProcess {
+            name: "Unit example".to_owned(),
+            outputs: vec![
+                Data {
+                    name: "Example Object".to_owned(),
+                    type_name: "UserObject".to_owned(),
+                    content: json!({"value": -5}),
+                    targets: None,
+                    metadata: None,
+                    annotations: None,
+                    registered: false,
+                },
+                Data {
+                    name: "Example i64".to_owned(),
+                    type_name: "i64".to_owned(),
+                    content: json!(21412),
+                    targets: None,
+                    metadata: None,
+                    annotations: None,
+                    registered: false,
+                },
+            ],
+            inputs: vec![Data {
+                name: "Example Basic".to_owned(),
+                type_name: "UserBasic".to_owned(),
+                content: json!(-132),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                delete: None,
+                modify: None,
+                add: Some(vec![unit_process]),
+                delete_links: None,
+                add_links: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(result.state);
+        contract_logic(&context, &mut result);
+
+        println!("{}", result.error);
+        assert!(result.error.is_empty());
+
+        assert_eq!(result.state.unit_process.len(), 1);
+        assert_eq!(result.state.unit_process[0].name, "Unit example");
+        assert_eq!(result.state.unit_process[0].outputs.len(), 2);
+
+        let data = result.state.unit_process[0].outputs[0].clone();
+        assert_eq!(data.name, "Example Object");
+        assert_eq!(data.type_name, "UserObject");
+        assert_eq!(data.content, json!({"value": -5}));
+        let data = result.state.unit_process[0].outputs[1].clone();
+        assert_eq!(data.name, "Example i64");
+        assert_eq!(data.type_name, "i64");
+        assert_eq!(data.content, json!(21412),);
+
+        let data = result.state.unit_process[0].inputs[0].clone();
+        assert_eq!(data.name, "Example Basic");
+        assert_eq!(data.type_name, "UserBasic");
+        assert_eq!(data.content, json!(-132),);
+
+        assert!(result.success);
+    }
+
+    #[test]
+    fn test_u64() {
+        ////////////////////////////////////////////////////////////////
+        // Type definition
+        ////////////////////////////////////////////////////////////////
+        let init_state = ProductionSystem {
+            name: "example".to_owned(),
+            version: 1,
+            unit_process: vec![],
+            custom_types: HashMap::new(),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![
+                    (
+                        "UserObject".to_owned(),
+                        Fields::Object(HashMap::from([("value".to_owned(), DynamicType::u64)])),
+                    ),
+                    (
+                        "UserBasic".to_owned(),
+                        Fields::Basic(Box::new(DynamicType::u64)),
+                    ),
+                ]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(result.error.is_empty());
+        assert_eq!(
+            result.state.custom_types.get("UserObject").unwrap().clone(),
+            Fields::Object(HashMap::from([("value".to_owned(), DynamicType::u64)]))
+        );
+        assert_eq!(
+            result.state.custom_types.get("UserBasic").unwrap().clone(),
+            Fields::Basic(Box::new(DynamicType::u64))
+        );
+        assert_eq!(result.state.custom_types.len(), 2);
+
+        ////////////////////////////////////////////////////////////////
+        // Unit process
+        ////////////////////////////////////////////////////////////////
+        let unit_process = UnitProcess {
+            name: "Unit example".to_owned(),
+            outputs: vec![
+                Data {
+                    name: "Example Object".to_owned(),
+                    type_name: "UserObject".to_owned(),
+                    content: json!({"value": 0}),
+                    targets: None,
+                    metadata: None,
+                    annotations: None,
+                    registered: false,
+                },
+                Data {
+                    name: "Example u64".to_owned(),
+                    type_name: "u64".to_owned(),
+                    content: json!(21412),
+                    targets: None,
+                    metadata: None,
+                    annotations: None,
+                    registered: false,
+                },
+            ],
+            inputs: vec![Data {
+                name: "Example Basic".to_owned(),
+                type_name: "UserBasic".to_owned(),
+                content: json!(132),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                delete: None,
+                modify: None,
+                add: Some(vec![unit_process]),
+                delete_links: None,
+                add_links: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(result.state);
+        contract_logic(&context, &mut result);
+
+        println!("{}", result.error);
+        assert!(result.error.is_empty());
+
+        assert_eq!(result.state.unit_process.len(), 1);
+        assert_eq!(result.state.unit_process[0].name, "Unit example");
+        assert_eq!(result.state.unit_process[0].outputs.len(), 2);
+
+        let data = result.state.unit_process[0].outputs[0].clone();
+        assert_eq!(data.name, "Example Object");
+        assert_eq!(data.type_name, "UserObject");
+        assert_eq!(data.content, json!({"value": 0}));
+        let data = result.state.unit_process[0].outputs[1].clone();
+        assert_eq!(data.name, "Example u64");
+        assert_eq!(data.type_name, "u64");
+        assert_eq!(data.content, json!(21412),);
+
+        let data = result.state.unit_process[0].inputs[0].clone();
+        assert_eq!(data.name, "Example Basic");
+        assert_eq!(data.type_name, "UserBasic");
+        assert_eq!(data.content, json!(132),);
+
+        assert!(result.success);
+    }
+
+    #[test]
+    fn test_f64() {
+        ////////////////////////////////////////////////////////////////
+        // Type definition
+        ////////////////////////////////////////////////////////////////
+        let init_state = ProductionSystem {
+            name: "example".to_owned(),
+            version: 1,
+            unit_process: vec![],
+            custom_types: HashMap::new(),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![
+                    (
+                        "UserObject".to_owned(),
+                        Fields::Object(HashMap::from([("value".to_owned(), DynamicType::f64)])),
+                    ),
+                    (
+                        "UserBasic".to_owned(),
+                        Fields::Basic(Box::new(DynamicType::f64)),
+                    ),
+                ]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(result.error.is_empty());
+        assert_eq!(
+            result.state.custom_types.get("UserObject").unwrap().clone(),
+            Fields::Object(HashMap::from([("value".to_owned(), DynamicType::f64)]))
+        );
+        assert_eq!(
+            result.state.custom_types.get("UserBasic").unwrap().clone(),
+            Fields::Basic(Box::new(DynamicType::f64))
+        );
+        assert_eq!(result.state.custom_types.len(), 2);
+
+        ////////////////////////////////////////////////////////////////
+        // Unit process
+        ////////////////////////////////////////////////////////////////
+        let unit_process = UnitProcess {
+            name: "Unit example".to_owned(),
+            outputs: vec![
+                Data {
+                    name: "Example Object".to_owned(),
+                    type_name: "UserObject".to_owned(),
+                    content: json!({"value": 0}),
+                    targets: None,
+                    metadata: None,
+                    annotations: None,
+                    registered: false,
+                },
+                Data {
+                    name: "Example f64".to_owned(),
+                    type_name: "f64".to_owned(),
+                    content: json!(21412.0),
+                    targets: None,
+                    metadata: None,
+                    annotations: None,
+                    registered: false,
+                },
+            ],
+            inputs: vec![Data {
+                name: "Example Basic".to_owned(),
+                type_name: "UserBasic".to_owned(),
+                content: json!(-132.55),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                delete: None,
+                modify: None,
+                add: Some(vec![unit_process]),
+                delete_links: None,
+                add_links: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(result.state);
+        contract_logic(&context, &mut result);
+
+        println!("{}", result.error);
+        assert!(result.error.is_empty());
+
+        assert_eq!(result.state.unit_process.len(), 1);
+        assert_eq!(result.state.unit_process[0].name, "Unit example");
+        assert_eq!(result.state.unit_process[0].outputs.len(), 2);
+
+        let data = result.state.unit_process[0].outputs[0].clone();
+        assert_eq!(data.name, "Example Object");
+        assert_eq!(data.type_name, "UserObject");
+        assert_eq!(data.content, json!({"value": 0}));
+        let data = result.state.unit_process[0].outputs[1].clone();
+        assert_eq!(data.name, "Example f64");
+        assert_eq!(data.type_name, "f64");
+        assert_eq!(data.content, json!(21412.0),);
+
+        let data = result.state.unit_process[0].inputs[0].clone();
+        assert_eq!(data.name, "Example Basic");
+        assert_eq!(data.type_name, "UserBasic");
+        assert_eq!(data.content, json!(-132.55),);
+
+        assert!(result.success);
+    }
+
+    #[test]
+    fn test_bool() {
+        ////////////////////////////////////////////////////////////////
+        // Type definition
+        ////////////////////////////////////////////////////////////////
+        let init_state = ProductionSystem {
+            name: "example".to_owned(),
+            version: 1,
+            unit_process: vec![],
+            custom_types: HashMap::new(),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![
+                    (
+                        "UserObject".to_owned(),
+                        Fields::Object(HashMap::from([("value".to_owned(), DynamicType::bool)])),
+                    ),
+                    (
+                        "UserBasic".to_owned(),
+                        Fields::Basic(Box::new(DynamicType::bool)),
+                    ),
+                ]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(result.error.is_empty());
+        assert_eq!(
+            result.state.custom_types.get("UserObject").unwrap().clone(),
+            Fields::Object(HashMap::from([("value".to_owned(), DynamicType::bool)]))
+        );
+        assert_eq!(
+            result.state.custom_types.get("UserBasic").unwrap().clone(),
+            Fields::Basic(Box::new(DynamicType::bool))
+        );
+        assert_eq!(result.state.custom_types.len(), 2);
+
+        ////////////////////////////////////////////////////////////////
+        // Unit process
+        ////////////////////////////////////////////////////////////////
+        let unit_process = UnitProcess {
+            name: "Unit example".to_owned(),
+            outputs: vec![
+                Data {
+                    name: "Example Object".to_owned(),
+                    type_name: "UserObject".to_owned(),
+                    content: json!({"value": false}),
+                    targets: None,
+                    metadata: None,
+                    annotations: None,
+                    registered: false,
+                },
+                Data {
+                    name: "Example bool".to_owned(),
+                    type_name: "bool".to_owned(),
+                    content: json!(true),
+                    targets: None,
+                    metadata: None,
+                    annotations: None,
+                    registered: false,
+                },
+            ],
+            inputs: vec![Data {
+                name: "Example Basic".to_owned(),
+                type_name: "UserBasic".to_owned(),
+                content: json!(false),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                delete: None,
+                modify: None,
+                add: Some(vec![unit_process]),
+                delete_links: None,
+                add_links: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(result.state);
+        contract_logic(&context, &mut result);
+
+        println!("{}", result.error);
+        assert!(result.error.is_empty());
+
+        assert_eq!(result.state.unit_process.len(), 1);
+        assert_eq!(result.state.unit_process[0].name, "Unit example");
+        assert_eq!(result.state.unit_process[0].outputs.len(), 2);
+
+        let data = result.state.unit_process[0].outputs[0].clone();
+        assert_eq!(data.name, "Example Object");
+        assert_eq!(data.type_name, "UserObject");
+        assert_eq!(data.content, json!({"value": false}));
+        let data = result.state.unit_process[0].outputs[1].clone();
+        assert_eq!(data.name, "Example bool");
+        assert_eq!(data.type_name, "bool");
+        assert_eq!(data.content, json!(true),);
+
+        let data = result.state.unit_process[0].inputs[0].clone();
+        assert_eq!(data.name, "Example Basic");
+        assert_eq!(data.type_name, "UserBasic");
+        assert_eq!(data.content, json!(false),);
+
+        assert!(result.success);
+    }
+
+    #[test]
+    fn test_dummy_fail() {
+        ////////////////////////////////////////////////////////////////
+        // Type definition
+        ////////////////////////////////////////////////////////////////
+        let init_state = ProductionSystem {
+            name: "example".to_owned(),
+            version: 1,
+            unit_process: vec![],
+            custom_types: HashMap::new(),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![(
+                    "UserObject".to_owned(),
+                    Fields::Object(HashMap::from([("value".to_owned(), DynamicType::Dummy)])),
+                )]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(!result.error.is_empty());
+        assert!(!result.success);
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![(
+                    "UserBasic".to_owned(),
+                    Fields::Basic(Box::new(DynamicType::Dummy)),
+                )]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(!result.error.is_empty());
+        assert!(!result.success);
+
+        ////////////////////////////////////////////////////////////////
+        // Unit process
+        ////////////////////////////////////////////////////////////////
+        let unit_process = UnitProcess {
+            name: "Unit example".to_owned(),
+            outputs: vec![Data {
+                name: "Example Dummy".to_owned(),
+                type_name: "Dummy".to_owned(),
+                content: json!({}),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            inputs: vec![],
+            properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                delete: None,
+                modify: None,
+                add: Some(vec![unit_process]),
+                delete_links: None,
+                add_links: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+        contract_logic(&context, &mut result);
+
+        assert!(!result.error.is_empty());
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_option_fail() {
+        ////////////////////////////////////////////////////////////////
+        // Type definition
+        ////////////////////////////////////////////////////////////////
+        let init_state = ProductionSystem {
+            name: "example".to_owned(),
+            version: 1,
+            unit_process: vec![],
+            custom_types: HashMap::new(),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![(
+                    "UserObject".to_owned(),
+                    Fields::Object(HashMap::from([(
+                        "value".to_owned(),
+                        DynamicType::Option(Box::new(DynamicType::Dummy)),
+                    )])),
+                )]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(!result.error.is_empty());
+        assert!(!result.success);
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![(
+                    "UserBasic".to_owned(),
+                    Fields::Basic(Box::new(DynamicType::Option(Box::new(DynamicType::String)))),
+                )]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(!result.error.is_empty());
+        assert!(!result.success);
+
+        ////////////////////////////////////////////////////////////////
+        // Unit process
+        ////////////////////////////////////////////////////////////////
+        let unit_process = UnitProcess {
+            name: "Unit example".to_owned(),
+            outputs: vec![Data {
+                name: "Example Dummy".to_owned(),
+                type_name: "Option".to_owned(),
+                content: json!({}),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            inputs: vec![],
+            properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                delete: None,
+                modify: None,
+                add: Some(vec![unit_process]),
+                delete_links: None,
+                add_links: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+        contract_logic(&context, &mut result);
+
+        assert!(!result.error.is_empty());
+        assert!(!result.success);
+    }
+
+    // TODO Poner más ejemplos como Enum(Vec)...
+    #[test]
+    fn test_enum() {
+        ////////////////////////////////////////////////////////////////
+        // Type definition
+        ////////////////////////////////////////////////////////////////
+        let init_state = ProductionSystem {
+            name: "example".to_owned(),
+            version: 1,
+            unit_process: vec![],
+            custom_types: HashMap::new(),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![
+                    (
+                        "UserObject".to_owned(),
+                        Fields::Object(HashMap::from([(
+                            "value".to_owned(),
+                            DynamicType::Enum(HashMap::from([
+                                ("Name".to_owned(), DynamicType::Dummy),
+                                ("Data".to_owned(), DynamicType::String),
+                            ])),
+                        )])),
+                    ),
+                    (
+                        "UserBasic".to_owned(),
+                        Fields::Basic(Box::new(DynamicType::Enum(HashMap::from([
+                            ("Name".to_owned(), DynamicType::Dummy),
+                            ("Data".to_owned(), DynamicType::String),
+                        ])))),
+                    ),
+                ]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(result.error.is_empty());
+        assert_eq!(
+            result.state.custom_types.get("UserObject").unwrap().clone(),
+            Fields::Object(HashMap::from([(
+                "value".to_owned(),
+                DynamicType::Enum(HashMap::from([
+                    ("Name".to_owned(), DynamicType::Dummy),
+                    ("Data".to_owned(), DynamicType::String)
+                ]))
+            )]))
+        );
+        assert_eq!(
+            result.state.custom_types.get("UserBasic").unwrap().clone(),
+            Fields::Basic(Box::new(DynamicType::Enum(HashMap::from([
+                ("Name".to_owned(), DynamicType::Dummy),
+                ("Data".to_owned(), DynamicType::String)
+            ]))))
+        );
+        assert_eq!(result.state.custom_types.len(), 2);
+
+        ////////////////////////////////////////////////////////////////
+        // Unit process
+        ////////////////////////////////////////////////////////////////
+        let unit_process = UnitProcess {
+            name: "Unit example".to_owned(),
+            outputs: vec![Data {
+                name: "Example Object".to_owned(),
+                type_name: "UserObject".to_owned(),
+                content: json!({"value": {"Data": "info"}}),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            inputs: vec![Data {
+                name: "Example Basic".to_owned(),
+                type_name: "UserBasic".to_owned(),
+                content: json!("Name"),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                delete: None,
+                modify: None,
+                add: Some(vec![unit_process]),
+                delete_links: None,
+                add_links: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(result.state);
+        contract_logic(&context, &mut result);
+
+        println!("{}", result.error);
+        assert!(result.error.is_empty());
+
+        assert_eq!(result.state.unit_process.len(), 1);
+        assert_eq!(result.state.unit_process[0].name, "Unit example");
+        assert_eq!(result.state.unit_process[0].outputs.len(), 1);
+
+        let data = result.state.unit_process[0].outputs[0].clone();
+        assert_eq!(data.name, "Example Object");
+        assert_eq!(data.type_name, "UserObject");
+        assert_eq!(data.content, json!({"value": {"Data": "info"}}));
+
+        let data = result.state.unit_process[0].inputs[0].clone();
+        assert_eq!(data.name, "Example Basic");
+        assert_eq!(data.type_name, "UserBasic");
+        assert_eq!(data.content, json!("Name"));
+
+        assert!(result.success);
+
+        ////////////////////////////////////////////////////////////////
+        // Unit process register data
+        ////////////////////////////////////////////////////////////////
+        let context = sdk::Context {
+            event: Events::RegisterData(vec![UnitData {
+                name: "Unit example".to_owned(),
+                outputs: Some(vec![RegisterData {
+                    name: "Example Object".to_owned(),
+                    type_name: "UserObject".to_owned(),
+                    content: json!({"value": "Name"}),
+                    targets: None,
+                }]),
+                inputs: Some(vec![RegisterData {
+                    name: "Example Basic".to_owned(),
+                    type_name: "UserBasic".to_owned(),
+                    content: json!({"Data": "info"}),
+                    targets: None,
+                }]),
+                properties: None,
+                validation: None,
+            }]),
+            is_owner: false,
+        };
+
+        let mut result = sdk::ContractResult::new(result.state);
+        contract_logic(&context, &mut result);
+
+        println!("{}", result.error);
+        assert!(result.error.is_empty());
+
+        assert_eq!(result.state.unit_process.len(), 1);
+        assert_eq!(result.state.unit_process[0].name, "Unit example");
+        assert_eq!(result.state.unit_process[0].outputs.len(), 1);
+
+        let data = result.state.unit_process[0].outputs[0].clone();
+        assert_eq!(data.name, "Example Object");
+        assert_eq!(data.type_name, "UserObject");
+        assert_eq!(data.content, json!({"value": "Name"}));
+
+        let data = result.state.unit_process[0].inputs[0].clone();
+        assert_eq!(data.name, "Example Basic");
+        assert_eq!(data.type_name, "UserBasic");
+        assert_eq!(data.content, json!({"Data": "info"}));
+
+        assert!(result.success);
+    }
+
+    #[test]
+    fn test_enum_fail() {
+        ////////////////////////////////////////////////////////////////
+        // Type definition
+        ////////////////////////////////////////////////////////////////
+        let init_state = ProductionSystem {
+            name: "example".to_owned(),
+            version: 1,
+            unit_process: vec![],
+            custom_types: HashMap::new(),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![(
+                    "UserObject".to_owned(),
+                    Fields::Object(HashMap::from([(
+                        "value".to_owned(),
+                        DynamicType::Enum(HashMap::from([
+                            ("Name".to_owned(), DynamicType::Dummy),
+                            (
+                                "Data".to_owned(),
+                                DynamicType::Option(Box::new(DynamicType::String)),
+                            ),
+                        ])),
+                    )])),
+                )]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(!result.error.is_empty());
+        assert!(!result.success);
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![(
+                    "UserBasic".to_owned(),
+                    Fields::Basic(Box::new(DynamicType::Enum(HashMap::from([
+                        ("Name".to_owned(), DynamicType::Dummy),
+                        (
+                            "Data".to_owned(),
+                            DynamicType::Option(Box::new(DynamicType::String)),
+                        ),
+                    ])))),
+                )]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(!result.error.is_empty());
+        assert!(!result.success);
+
+        ////////////////////////////////////////////////////////////////
+        // Unit process
+        ////////////////////////////////////////////////////////////////
+        let unit_process = UnitProcess {
+            name: "Unit example".to_owned(),
+            outputs: vec![Data {
+                name: "Example Dummy".to_owned(),
+                type_name: "Enum".to_owned(),
+                content: json!({}),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            inputs: vec![],
+            properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                delete: None,
+                modify: None,
+                add: Some(vec![unit_process]),
+                delete_links: None,
+                add_links: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+        contract_logic(&context, &mut result);
+
+        assert!(!result.error.is_empty());
+        assert!(!result.success);
+    }
+
+    // TODO Poner más ejemplos como Vec(Enum)...
+    #[test]
+    fn test_vec() {
+        ////////////////////////////////////////////////////////////////
+        // Type definition
+        ////////////////////////////////////////////////////////////////
+        let init_state = ProductionSystem {
+            name: "example".to_owned(),
+            version: 1,
+            unit_process: vec![],
+            custom_types: HashMap::new(),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![
+                    (
+                        "UserObject".to_owned(),
+                        Fields::Object(HashMap::from([(
+                            "value".to_owned(),
+                            DynamicType::Vec(Box::new(DynamicType::String)),
+                        )])),
+                    ),
+                    (
+                        "UserBasic".to_owned(),
+                        Fields::Basic(Box::new(DynamicType::Vec(Box::new(DynamicType::u64)))),
+                    ),
+                ]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(result.error.is_empty());
+        assert_eq!(
+            result.state.custom_types.get("UserObject").unwrap().clone(),
+            Fields::Object(HashMap::from([(
+                "value".to_owned(),
+                DynamicType::Vec(Box::new(DynamicType::String))
+            )]))
+        );
+        assert_eq!(
+            result.state.custom_types.get("UserBasic").unwrap().clone(),
+            Fields::Basic(Box::new(DynamicType::Vec(Box::new(DynamicType::u64)))),
+        );
+        assert_eq!(result.state.custom_types.len(), 2);
+
+        ////////////////////////////////////////////////////////////////
+        // Unit process
+        ////////////////////////////////////////////////////////////////
+        let unit_process = UnitProcess {
+            name: "Unit example".to_owned(),
+            outputs: vec![Data {
+                name: "Example Object".to_owned(),
+                type_name: "UserObject".to_owned(),
+                content: json!({"value": ["one", "two"]}),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            inputs: vec![Data {
+                name: "Example Basic".to_owned(),
+                type_name: "UserBasic".to_owned(),
+                content: json!([0, 1, 2, 3, 4, 5]),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                delete: None,
+                modify: None,
+                add: Some(vec![unit_process]),
+                delete_links: None,
+                add_links: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(result.state);
+        contract_logic(&context, &mut result);
+
+        println!("{}", result.error);
+        assert!(result.error.is_empty());
+
+        assert_eq!(result.state.unit_process.len(), 1);
+        assert_eq!(result.state.unit_process[0].name, "Unit example");
+        assert_eq!(result.state.unit_process[0].outputs.len(), 1);
+
+        let data = result.state.unit_process[0].outputs[0].clone();
+        assert_eq!(data.name, "Example Object");
+        assert_eq!(data.type_name, "UserObject");
+        assert_eq!(data.content, json!({"value": ["one", "two"]}));
+
+        let data = result.state.unit_process[0].inputs[0].clone();
+        assert_eq!(data.name, "Example Basic");
+        assert_eq!(data.type_name, "UserBasic");
+        assert_eq!(data.content, json!([0, 1, 2, 3, 4, 5]));
+
+        assert!(result.success);
+    }
+
+    #[test]
+    fn test_vec_fail() {
+        ////////////////////////////////////////////////////////////////
+        // Type definition
+        ////////////////////////////////////////////////////////////////
+        let init_state = ProductionSystem {
+            name: "example".to_owned(),
+            version: 1,
+            unit_process: vec![],
+            custom_types: HashMap::new(),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![(
+                    "UserObject".to_owned(),
+                    Fields::Object(HashMap::from([(
+                        "value".to_owned(),
+                        DynamicType::Vec(Box::new(DynamicType::Dummy)),
+                    )])),
+                )]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(!result.error.is_empty());
+        assert!(!result.success);
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![(
+                    "UserBasic".to_owned(),
+                    Fields::Basic(Box::new(DynamicType::Vec(Box::new(DynamicType::Dummy)))),
+                )]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(!result.error.is_empty());
+        assert!(!result.success);
+
+        ////////////////////////////////////////////////////////////////
+        ////////////////////////////////////////////////////////////////
+
+        let init_state = ProductionSystem {
+            name: "example".to_owned(),
+            version: 1,
+            unit_process: vec![],
+            custom_types: HashMap::new(),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![(
+                    "UserObject".to_owned(),
+                    Fields::Object(HashMap::from([(
+                        "value".to_owned(),
+                        DynamicType::Vec(Box::new(DynamicType::Option(Box::new(
+                            DynamicType::String,
+                        )))),
+                    )])),
+                )]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(!result.error.is_empty());
+        assert!(!result.success);
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![(
+                    "UserBasic".to_owned(),
+                    Fields::Basic(Box::new(DynamicType::Vec(Box::new(DynamicType::Option(
+                        Box::new(DynamicType::String),
+                    ))))),
+                )]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(!result.error.is_empty());
+        assert!(!result.success);
+
+        ////////////////////////////////////////////////////////////////
+        // Unit process
+        ////////////////////////////////////////////////////////////////
+        let unit_process = UnitProcess {
+            name: "Unit example".to_owned(),
+            outputs: vec![Data {
+                name: "Example Dummy".to_owned(),
+                type_name: "Vec".to_owned(),
+                content: json!([]),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            inputs: vec![],
+            properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                delete: None,
+                modify: None,
+                add: Some(vec![unit_process]),
+                delete_links: None,
+                add_links: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+        contract_logic(&context, &mut result);
+
+        assert!(!result.error.is_empty());
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_type() {
+        ////////////////////////////////////////////////////////////////
+        // Type definition
+        ////////////////////////////////////////////////////////////////
+        let init_state = ProductionSystem {
+            name: "example".to_owned(),
+            version: 1,
+            unit_process: vec![],
+            custom_types: HashMap::new(),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![
+                    (
+                        "DataType".to_owned(),
+                        Fields::Object(HashMap::from([
+                            ("text".to_owned(), DynamicType::String),
+                            ("value".to_owned(), DynamicType::u64),
+                        ])),
+                    ),
+                    (
+                        "VecType".to_owned(),
+                        Fields::Basic(Box::new(DynamicType::Vec(Box::new(DynamicType::String)))),
+                    ),
+                    (
+                        "UserObject".to_owned(),
+                        Fields::Object(HashMap::from([(
+                            "value".to_owned(),
+                            DynamicType::Type("DataType".to_owned()),
+                        )])),
+                    ),
+                    (
+                        "UserVec".to_owned(),
+                        Fields::Object(HashMap::from([(
+                            "value".to_owned(),
+                            DynamicType::Type("VecType".to_owned()),
+                        )])),
+                    ),
+                ]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+
+        contract_logic(&context, &mut result);
+
+        assert!(result.error.is_empty());
+        assert_eq!(
+            result.state.custom_types.get("VecType").unwrap().clone(),
+            Fields::Basic(Box::new(DynamicType::Vec(Box::new(DynamicType::String)))),
+        );
+        assert_eq!(
+            result.state.custom_types.get("UserVec").unwrap().clone(),
+            Fields::Object(HashMap::from([(
+                "value".to_owned(),
+                DynamicType::Type("VecType".to_owned()),
+            )])),
+        );
+        assert_eq!(
+            result.state.custom_types.get("UserObject").unwrap().clone(),
+            Fields::Object(HashMap::from([(
+                "value".to_owned(),
+                DynamicType::Type("DataType".to_owned())
+            )]))
+        );
+        assert_eq!(
+            result.state.custom_types.get("DataType").unwrap().clone(),
+            Fields::Object(HashMap::from([
+                ("text".to_owned(), DynamicType::String,),
+                ("value".to_owned(), DynamicType::u64,)
+            ])),
+        );
+        assert_eq!(result.state.custom_types.len(), 4);
+
+        ////////////////////////////////////////////////////////////////
+        // Unit process
+        ////////////////////////////////////////////////////////////////
+        let unit_process = UnitProcess {
+            name: "Unit example".to_owned(),
+            outputs: vec![Data {
+                name: "Example Object".to_owned(),
+                type_name: "UserObject".to_owned(),
+                content: json!({"value": {"text": "info", "value": 30}}),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            inputs: vec![Data {
+                name: "Example Vec".to_owned(),
+                type_name: "UserVec".to_owned(),
+                content: json!({"value": ["one", "two"]}),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                delete: None,
+                modify: None,
+                add: Some(vec![unit_process]),
+                delete_links: None,
+                add_links: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(result.state);
+        contract_logic(&context, &mut result);
+
+        println!("{}", result.error);
         assert!(result.error.is_empty());
+
+        assert_eq!(result.state.unit_process.len(), 1);
+        assert_eq!(result.state.unit_process[0].name, "Unit example");
+        assert_eq!(result.state.unit_process[0].outputs.len(), 1);
+
+        let data = result.state.unit_process[0].outputs[0].clone();
+        assert_eq!(data.name, "Example Object");
+        assert_eq!(data.type_name, "UserObject");
+        assert_eq!(
+            data.content,
+            json!({"value": {"text": "info", "value": 30}})
+        );
+
+        assert_eq!(result.state.unit_process[0].inputs.len(), 1);
+
+        let data = result.state.unit_process[0].inputs[0].clone();
+        assert_eq!(data.name, "Example Vec");
+        assert_eq!(data.type_name, "UserVec");
+        assert_eq!(data.content, json!({"value": ["one", "two"]}));
+
+        assert!(result.success);
+    }
+
+    #[test]
+    fn test_type_fail() {
+        ////////////////////////////////////////////////////////////////
+        // Type definition
+        ////////////////////////////////////////////////////////////////
+        let init_state = ProductionSystem {
+            name: "example".to_owned(),
+            version: 1,
+            unit_process: vec![],
+            custom_types: HashMap::new(),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        ////////////////////////////////////////////////////////////////
+        // Unit process
+        ////////////////////////////////////////////////////////////////
+        let unit_process = UnitProcess {
+            name: "Unit example".to_owned(),
+            outputs: vec![Data {
+                name: "Example Dummy".to_owned(),
+                type_name: "Type".to_owned(),
+                content: json!({}),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            inputs: vec![],
+            properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                delete: None,
+                modify: None,
+                add: Some(vec![unit_process]),
+                delete_links: None,
+                add_links: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+        contract_logic(&context, &mut result);
+
+        assert!(!result.error.is_empty());
+        assert!(!result.success);
+    }
+
+    // TODO REHACER
+    #[test]
+    fn change_operation_name() {
+        let init_state = ProductionSystem {
+            name: "wine process".to_owned(),
+            version: 1,
+            unit_process: vec![],
+            custom_types: HashMap::new(),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyProductionSystem {
+                name: Some("wine process 2".to_owned()),
+                delete_properties: None,
+                modify_properties: None,
+                add_properties: None,
+                validation: None,
+                permissions: None,
+                subject_id: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state);
+        contract_logic(&context, &mut result);
+
+        assert_eq!(result.state.version, 2);
+        assert_eq!(result.state.name, "wine process 2");
+        assert!(result.success);
+    }
+
+    #[test]
+    fn test_change_operation_init() {
+        let init_state = ProductionSystem {
+            name: "".to_owned(),
+            version: 0,
+            unit_process: vec![],
+            custom_types: HashMap::new(),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        };
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::Init {
+                name: "wine process".to_owned(),
+                unit_process: Some(vec![UnitProcess {
+                    name: "Unit example".to_owned(),
+                    outputs: vec![Data {
+                        name: "Example Object".to_owned(),
+                        type_name: "UserObject".to_owned(),
+                        content: json!({"name": "ExampleName"}),
+                        targets: None,
+                        metadata: None,
+                        annotations: None,
+                        registered: false,
+                    }],
+                    inputs: vec![Data {
+                        name: "Example Basic".to_owned(),
+                        type_name: "UserBasic".to_owned(),
+                        content: json!("ExampleBasic"),
+                        targets: None,
+                        metadata: None,
+                        annotations: None,
+                        registered: false,
+                    }],
+                    properties: vec![Properties {
+                        name: "Example String".to_owned(),
+                        type_name: "String".to_owned(),
+                        content: json!("ExampleString"),
+                        annotations: None,
+                    }],
+                    writers: None,
+                    annotations: None,
+                    balance: None,
+                }]),
+                properties: Some(vec![Properties {
+                    name: "Example Object".to_owned(),
+                    type_name: "UserObject".to_owned(),
+                    content: json!({"name": "ExampleName"}),
+                    annotations: None,
+                }]),
+                types: Some(vec![
+                    (
+                        "UserObject".to_owned(),
+                        Fields::Object(HashMap::from([("name".to_owned(), DynamicType::String)])),
+                    ),
+                    (
+                        "UserBasic".to_owned(),
+                        Fields::Basic(Box::new(DynamicType::String)),
+                    ),
+                ]),
+                validation: None,
+                permissions: None,
+                links: None,
+                subject_id: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state);
+        contract_logic(&context, &mut result);
+
+        assert_eq!(result.state.name, "wine process");
+        assert_eq!(result.state.version, 1);
+        assert_eq!(result.state.properties.len(), 1);
+        assert_eq!(
+            result.state.properties[0],
+            Properties {
+                name: "Example Object".to_owned(),
+                type_name: "UserObject".to_owned(),
+                content: json!({"name": "ExampleName"}),
+                annotations: None,
+            }
+        );
+        assert_eq!(result.state.unit_process.len(), 1);
+        assert_eq!(
+            result.state.unit_process[0],
+            UnitProcess {
+                name: "Unit example".to_owned(),
+                outputs: vec![Data {
+                    name: "Example Object".to_owned(),
+                    type_name: "UserObject".to_owned(),
+                    content: json!({"name": "ExampleName"}),
+                    targets: None,
+                    metadata: None,
+                    annotations: None,
+                    registered: false,
+                },],
+                inputs: vec![Data {
+                    name: "Example Basic".to_owned(),
+                    type_name: "UserBasic".to_owned(),
+                    content: json!("ExampleBasic"),
+                    targets: None,
+                    metadata: None,
+                    annotations: None,
+                    registered: false,
+                }],
+                properties: vec![Properties {
+                    name: "Example String".to_owned(),
+                    type_name: "String".to_owned(),
+                    content: json!("ExampleString"),
+                    annotations: None,
+                }],
+                writers: None,
+                annotations: None,
+                balance: None,
+            }
+        );
         assert_eq!(
             result.state.custom_types.get("UserObject").unwrap().clone(),
             Fields::Object(HashMap::from([("name".to_owned(), DynamicType::String)]))
@@ -1316,78 +7740,11 @@ mod tests {
             result.state.custom_types.get("UserBasic").unwrap().clone(),
             Fields::Basic(Box::new(DynamicType::String))
         );
-        assert_eq!(result.state.custom_types.len(), 2);
-
-        ////////////////////////////////////////////////////////////////
-        // Unit process
-        ////////////////////////////////////////////////////////////////
-        let unit_process = UnitProcess {
-            name: "Unit example".to_owned(),
-            outputs: vec![
-                Data {
-                    name: "Example Object".to_owned(),
-                    type_name: "UserObject".to_owned(),
-                    content: json!({"name": "ExampleName"}),
-                    targets: None,
-                    metadata: None,
-                },
-                Data {
-                    name: "Example String".to_owned(),
-                    type_name: "String".to_owned(),
-                    content: json!("ExampleString"),
-                    targets: None,
-                    metadata: None,
-                },
-            ],
-            inputs: vec![Data {
-                name: "Example Basic".to_owned(),
-                type_name: "UserBasic".to_owned(),
-                content: json!("ExampleBasic"),
-                targets: None,
-                metadata: Some(Metadata { type_name: "UserObject".to_owned(), content: json!({"name": "Metadata"}) }),
-            }],
-            properties: vec![],
-        };
-
-        let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
-                delete: None,
-                modify: None,
-                add: Some(vec![unit_process]),
-            }),
-            is_owner: false,
-        };
-
-        let mut result = sdk::ContractResult::new(result.state);
-        contract_logic(&context, &mut result);
-
-        println!("{}", result.error);
-        assert!(result.error.is_empty());
-
-        assert_eq!(result.state.unit_process.len(), 1);
-        assert_eq!(result.state.unit_process[0].name, "Unit example");
-        assert_eq!(result.state.unit_process[0].outputs.len(), 2);
-
-        let data = result.state.unit_process[0].outputs[0].clone();
-        assert_eq!(data.name, "Example Object");
-        assert_eq!(data.type_name, "UserObject");
-        assert_eq!(data.content, json!({"name": "ExampleName"}));
-        let data = result.state.unit_process[0].outputs[1].clone();
-        assert_eq!(data.name, "Example String");
-        assert_eq!(data.type_name, "String");
-        assert_eq!(data.content, json!("ExampleString"));
-
-        let data = result.state.unit_process[0].inputs[0].clone();
-        assert_eq!(data.name, "Example Basic");
-        assert_eq!(data.type_name, "UserBasic");
-        assert_eq!(data.content, json!("ExampleBasic"));
-        assert_eq!(data.metadata.unwrap(), Metadata { type_name: "UserObject".to_owned(), content: json!({"name": "Metadata"}) });
-
         assert!(result.success);
     }
 
     #[test]
-    fn test_string() {
+    fn test_add_change_delete() {
         ////////////////////////////////////////////////////////////////
         // Type definition
         ////////////////////////////////////////////////////////////////
@@ -1397,6 +7754,10 @@ mod tests {
             unit_process: vec![],
             custom_types: HashMap::new(),
             properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
         };
 
         let context = sdk::Context {
@@ -1412,8 +7773,10 @@ mod tests {
                         Fields::Basic(Box::new(DynamicType::String)),
                     ),
                 ]),
+                replace: None,
+                migrate: None,
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
         let mut result = sdk::ContractResult::new(init_state.clone());
@@ -1432,7 +7795,7 @@ mod tests {
         assert_eq!(result.state.custom_types.len(), 2);
 
         ////////////////////////////////////////////////////////////////
-        // Unit process
+        // Unit process add
         ////////////////////////////////////////////////////////////////
         let unit_process = UnitProcess {
             name: "Unit example".to_owned(),
@@ -1443,6 +7806,8 @@ mod tests {
                     content: json!({"name": "ExampleName"}),
                     targets: None,
                     metadata: None,
+                    annotations: None,
+                    registered: false,
                 },
                 Data {
                     name: "Example String".to_owned(),
@@ -1450,6 +7815,8 @@ mod tests {
                     content: json!("ExampleString"),
                     targets: None,
                     metadata: None,
+                    annotations: None,
+                    registered: false,
                 },
             ],
             inputs: vec![Data {
@@ -1458,8 +7825,13 @@ mod tests {
                 content: json!("ExampleBasic"),
                 targets: None,
                 metadata: None,
+                annotations: None,
+                registered: false,
             }],
             properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
         };
 
         let context = sdk::Context {
@@ -1467,8 +7839,10 @@ mod tests {
                 delete: None,
                 modify: None,
                 add: Some(vec![unit_process]),
+                delete_links: None,
+                add_links: None,
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
         let mut result = sdk::ContractResult::new(result.state);
@@ -1496,91 +7870,45 @@ mod tests {
         assert_eq!(data.content, json!("ExampleBasic"));
 
         assert!(result.success);
-    }
-
-    #[test]
-    fn test_i64() {
-        ////////////////////////////////////////////////////////////////
-        // Type definition
-        ////////////////////////////////////////////////////////////////
-        let init_state = ProductionSystem {
-            name: "example".to_owned(),
-            version: 1,
-            unit_process: vec![],
-            custom_types: HashMap::new(),
-            properties: vec![],
-        };
-
-        let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
-                delete: None,
-                add: Some(vec![
-                    (
-                        "UserObject".to_owned(),
-                        Fields::Object(HashMap::from([("value".to_owned(), DynamicType::i64)])),
-                    ),
-                    (
-                        "UserBasic".to_owned(),
-                        Fields::Basic(Box::new(DynamicType::i64)),
-                    ),
-                ]),
-            }),
-            is_owner: false,
-        };
-
-        let mut result = sdk::ContractResult::new(init_state.clone());
-
-        contract_logic(&context, &mut result);
-
-        assert!(result.error.is_empty());
-        assert_eq!(
-            result.state.custom_types.get("UserObject").unwrap().clone(),
-            Fields::Object(HashMap::from([("value".to_owned(), DynamicType::i64)]))
-        );
-        assert_eq!(
-            result.state.custom_types.get("UserBasic").unwrap().clone(),
-            Fields::Basic(Box::new(DynamicType::i64))
-        );
-        assert_eq!(result.state.custom_types.len(), 2);
 
         ////////////////////////////////////////////////////////////////
-        // Unit process
+        // Unit process modify
         ////////////////////////////////////////////////////////////////
         let unit_process = UnitProcess {
-            name: "Unit example".to_owned(),
-            outputs: vec![
-                Data {
-                    name: "Example Object".to_owned(),
-                    type_name: "UserObject".to_owned(),
-                    content: json!({"value": -5}),
-                    targets: None,
-                    metadata: None,
-                },
-                Data {
-                    name: "Example i64".to_owned(),
-                    type_name: "i64".to_owned(),
-                    content: json!(21412),
-                    targets: None,
-                    metadata: None,
-                },
-            ],
-            inputs: vec![Data {
-                name: "Example Basic".to_owned(),
+            name: "Unit example modify".to_owned(),
+            outputs: vec![Data {
+                name: "Example Basic modify".to_owned(),
                 type_name: "UserBasic".to_owned(),
-                content: json!(-132),
+                content: json!("ExampleBasic"),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            inputs: vec![Data {
+                name: "Example Object modify".to_owned(),
+                type_name: "UserObject".to_owned(),
+                content: json!({"name": "ExampleName"}),
                 targets: None,
                 metadata: None,
+                annotations: None,
+                registered: false,
             }],
             properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
         };
 
         let context = sdk::Context {
             event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
                 delete: None,
-                modify: None,
-                add: Some(vec![unit_process]),
+                modify: Some(vec![("Unit example".to_owned(), unit_process)]),
+                add: None,
+                delete_links: None,
+                add_links: None,
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
         let mut result = sdk::ContractResult::new(result.state);
@@ -1590,1527 +7918,4056 @@ mod tests {
         assert!(result.error.is_empty());
 
         assert_eq!(result.state.unit_process.len(), 1);
-        assert_eq!(result.state.unit_process[0].name, "Unit example");
-        assert_eq!(result.state.unit_process[0].outputs.len(), 2);
+        assert_eq!(result.state.unit_process[0].name, "Unit example modify");
+        assert_eq!(result.state.unit_process[0].outputs.len(), 1);
 
         let data = result.state.unit_process[0].outputs[0].clone();
-        assert_eq!(data.name, "Example Object");
-        assert_eq!(data.type_name, "UserObject");
-        assert_eq!(data.content, json!({"value": -5}));
-        let data = result.state.unit_process[0].outputs[1].clone();
-        assert_eq!(data.name, "Example i64");
-        assert_eq!(data.type_name, "i64");
-        assert_eq!(data.content, json!(21412),);
-
-        let data = result.state.unit_process[0].inputs[0].clone();
-        assert_eq!(data.name, "Example Basic");
+        assert_eq!(data.name, "Example Basic modify");
         assert_eq!(data.type_name, "UserBasic");
-        assert_eq!(data.content, json!(-132),);
-
-        assert!(result.success);
-    }
-
-    #[test]
-    fn test_u64() {
-        ////////////////////////////////////////////////////////////////
-        // Type definition
-        ////////////////////////////////////////////////////////////////
-        let init_state = ProductionSystem {
-            name: "example".to_owned(),
-            version: 1,
-            unit_process: vec![],
-            custom_types: HashMap::new(),
-            properties: vec![],
-        };
-
-        let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
-                delete: None,
-                add: Some(vec![
-                    (
-                        "UserObject".to_owned(),
-                        Fields::Object(HashMap::from([("value".to_owned(), DynamicType::u64)])),
-                    ),
-                    (
-                        "UserBasic".to_owned(),
-                        Fields::Basic(Box::new(DynamicType::u64)),
-                    ),
-                ]),
-            }),
-            is_owner: false,
-        };
-
-        let mut result = sdk::ContractResult::new(init_state.clone());
-
-        contract_logic(&context, &mut result);
+        assert_eq!(data.content, json!("ExampleBasic"));
 
-        assert!(result.error.is_empty());
-        assert_eq!(
-            result.state.custom_types.get("UserObject").unwrap().clone(),
-            Fields::Object(HashMap::from([("value".to_owned(), DynamicType::u64)]))
-        );
-        assert_eq!(
-            result.state.custom_types.get("UserBasic").unwrap().clone(),
-            Fields::Basic(Box::new(DynamicType::u64))
-        );
-        assert_eq!(result.state.custom_types.len(), 2);
+        let data = result.state.unit_process[0].inputs[0].clone();
+        assert_eq!(data.name, "Example Object modify");
+        assert_eq!(data.type_name, "UserObject");
+        assert_eq!(data.content, json!({"name": "ExampleName"}));
 
         ////////////////////////////////////////////////////////////////
-        // Unit process
-        ////////////////////////////////////////////////////////////////
-        let unit_process = UnitProcess {
-            name: "Unit example".to_owned(),
-            outputs: vec![
-                Data {
-                    name: "Example Object".to_owned(),
-                    type_name: "UserObject".to_owned(),
-                    content: json!({"value": 0}),
-                    targets: None,
-                    metadata: None,
-                },
-                Data {
-                    name: "Example u64".to_owned(),
-                    type_name: "u64".to_owned(),
-                    content: json!(21412),
-                    targets: None,
-                    metadata: None,
-                },
-            ],
-            inputs: vec![Data {
-                name: "Example Basic".to_owned(),
-                type_name: "UserBasic".to_owned(),
-                content: json!(132),
-                targets: None,
-                metadata: None,
-            }],
-            properties: vec![],
-        };
+        // Unit process modify
+        ////////////////////////////////////////////////////////////////
 
         let context = sdk::Context {
             event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
-                delete: None,
+                delete: Some(vec!["Unit example modify".to_owned()]),
                 modify: None,
-                add: Some(vec![unit_process]),
+                add: None,
+                delete_links: None,
+                add_links: None,
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
         let mut result = sdk::ContractResult::new(result.state);
         contract_logic(&context, &mut result);
 
-        println!("{}", result.error);
         assert!(result.error.is_empty());
+        assert_eq!(result.state.unit_process.len(), 0);
+        assert!(result.success);
+    }
 
-        assert_eq!(result.state.unit_process.len(), 1);
-        assert_eq!(result.state.unit_process[0].name, "Unit example");
-        assert_eq!(result.state.unit_process[0].outputs.len(), 2);
+    fn rollback_state() -> ProductionSystem {
+        let unit_process = |name: &str| UnitProcess {
+            name: name.to_owned(),
+            outputs: vec![Data {
+                name: "Example Object".to_owned(),
+                type_name: "UserObject".to_owned(),
+                content: json!({"name": "ExampleName"}),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            inputs: vec![Data {
+                name: "Example Basic".to_owned(),
+                type_name: "UserBasic".to_owned(),
+                content: json!("ExampleBasic"),
+                targets: None,
+                metadata: None,
+                annotations: None,
+                registered: false,
+            }],
+            properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
+        };
 
-        let data = result.state.unit_process[0].outputs[0].clone();
-        assert_eq!(data.name, "Example Object");
-        assert_eq!(data.type_name, "UserObject");
-        assert_eq!(data.content, json!({"value": 0}));
-        let data = result.state.unit_process[0].outputs[1].clone();
-        assert_eq!(data.name, "Example u64");
-        assert_eq!(data.type_name, "u64");
-        assert_eq!(data.content, json!(21412),);
+        ProductionSystem {
+            name: "example".to_owned(),
+            version: 1,
+            unit_process: vec![unit_process("Pressing"), unit_process("Fermentation")],
+            custom_types: HashMap::from([
+                (
+                    "UserObject".to_owned(),
+                    Fields::Object(HashMap::from([("name".to_owned(), DynamicType::String)])),
+                ),
+                (
+                    "UserBasic".to_owned(),
+                    Fields::Basic(Box::new(DynamicType::String)),
+                ),
+            ]),
+            properties: vec![Properties {
+                name: "Example String".to_owned(),
+                type_name: "String".to_owned(),
+                content: json!("ExampleString"),
+                annotations: None,
+            }],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        }
+    }
 
-        let data = result.state.unit_process[0].inputs[0].clone();
-        assert_eq!(data.name, "Example Basic");
-        assert_eq!(data.type_name, "UserBasic");
-        assert_eq!(data.content, json!(132),);
+    fn assert_rollback(init_state: &ProductionSystem, event: Events) {
+        let context = sdk::Context {
+            event,
+            is_owner: true,
+        };
 
-        assert!(result.success);
+        let mut result = sdk::ContractResult::new(init_state.clone());
+        contract_logic(&context, &mut result);
+
+        assert!(!result.error.is_empty());
+        assert!(!result.success);
+        assert_eq!(result.state.version, init_state.version);
+        assert_eq!(
+            serde_json::to_vec(&result.state).unwrap(),
+            serde_json::to_vec(init_state).unwrap()
+        );
     }
 
     #[test]
-    fn test_f64() {
-        ////////////////////////////////////////////////////////////////
-        // Type definition
-        ////////////////////////////////////////////////////////////////
+    fn test_rollback_init() {
         let init_state = ProductionSystem {
-            name: "example".to_owned(),
-            version: 1,
+            name: "".to_owned(),
+            version: 0,
             unit_process: vec![],
             custom_types: HashMap::new(),
             properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
         };
 
-        let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
-                delete: None,
-                add: Some(vec![
-                    (
-                        "UserObject".to_owned(),
-                        Fields::Object(HashMap::from([("value".to_owned(), DynamicType::f64)])),
-                    ),
-                    (
-                        "UserBasic".to_owned(),
-                        Fields::Basic(Box::new(DynamicType::f64)),
-                    ),
-                ]),
+        // Types and unit process are valid, the property type does not exist.
+        assert_rollback(
+            &init_state,
+            Events::ChangeProductionSystem(ChangeProductionSystem::Init {
+                name: "wine process".to_owned(),
+                unit_process: Some(vec![UnitProcess {
+                    name: "Unit example".to_owned(),
+                    outputs: vec![],
+                    inputs: vec![Data {
+                        name: "Example Basic".to_owned(),
+                        type_name: "UserBasic".to_owned(),
+                        content: json!("ExampleBasic"),
+                        targets: None,
+                        metadata: None,
+                        annotations: None,
+                        registered: false,
+                    }],
+                    properties: vec![],
+                    writers: None,
+                    annotations: None,
+                    balance: None,
+                }]),
+                properties: Some(vec![Properties {
+                    name: "Example Object".to_owned(),
+                    type_name: "Unknown".to_owned(),
+                    content: json!({"name": "ExampleName"}),
+                    annotations: None,
+                }]),
+                types: Some(vec![(
+                    "UserBasic".to_owned(),
+                    Fields::Basic(Box::new(DynamicType::String)),
+                )]),
+                validation: None,
+                permissions: None,
+                links: None,
+                subject_id: None,
             }),
-            is_owner: false,
-        };
+        );
+    }
 
-        let mut result = sdk::ContractResult::new(init_state.clone());
+    #[test]
+    fn test_rollback_modify_production_system() {
+        let init_state = rollback_state();
 
-        contract_logic(&context, &mut result);
+        // Name and deletion are valid, the modified property does not exist.
+        assert_rollback(
+            &init_state,
+            Events::ChangeProductionSystem(ChangeProductionSystem::ModifyProductionSystem {
+                name: Some("wine process 2".to_owned()),
+                delete_properties: Some(vec!["Example String".to_owned()]),
+                modify_properties: Some(vec![(
+                    "Unknown".to_owned(),
+                    Properties {
+                        name: "Unknown".to_owned(),
+                        type_name: "String".to_owned(),
+                        content: json!("ExampleString"),
+                        annotations: None,
+                    },
+                )]),
+                add_properties: None,
+                validation: None,
+                permissions: None,
+                subject_id: None,
+            }),
+        );
 
-        assert!(result.error.is_empty());
-        assert_eq!(
-            result.state.custom_types.get("UserObject").unwrap().clone(),
-            Fields::Object(HashMap::from([("value".to_owned(), DynamicType::f64)]))
+        // The new property duplicates an existing one.
+        assert_rollback(
+            &init_state,
+            Events::ChangeProductionSystem(ChangeProductionSystem::ModifyProductionSystem {
+                name: Some("wine process 2".to_owned()),
+                delete_properties: None,
+                modify_properties: None,
+                add_properties: Some(vec![Properties {
+                    name: "Example String".to_owned(),
+                    type_name: "String".to_owned(),
+                    content: json!("ExampleString"),
+                    annotations: None,
+                }]),
+                validation: None,
+                permissions: None,
+                subject_id: None,
+            }),
         );
-        assert_eq!(
-            result.state.custom_types.get("UserBasic").unwrap().clone(),
-            Fields::Basic(Box::new(DynamicType::f64))
+    }
+
+    #[test]
+    fn test_rollback_modify_types() {
+        let init_state = rollback_state();
+
+        // Deletion is valid, the added type references an unknown type.
+        assert_rollback(
+            &init_state,
+            Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: Some(vec!["UserBasic".to_owned()]),
+                add: Some(vec![(
+                    "UserObject".to_owned(),
+                    Fields::Object(HashMap::from([(
+                        "name".to_owned(),
+                        DynamicType::Type("Unknown".to_owned()),
+                    )])),
+                )]),
+                replace: None,
+                migrate: None,
+            }),
         );
-        assert_eq!(result.state.custom_types.len(), 2);
 
-        ////////////////////////////////////////////////////////////////
-        // Unit process
-        ////////////////////////////////////////////////////////////////
-        let unit_process = UnitProcess {
-            name: "Unit example".to_owned(),
-            outputs: vec![
-                Data {
+        // The second deletion does not exist.
+        assert_rollback(
+            &init_state,
+            Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: Some(vec!["UserBasic".to_owned(), "Unknown".to_owned()]),
+                add: None,
+                replace: None,
+                migrate: None,
+            }),
+        );
+    }
+
+    #[test]
+    fn test_rollback_modify_unit_process() {
+        let init_state = rollback_state();
+
+        // Both deletions are valid, the modified unit process no longer exists.
+        assert_rollback(
+            &init_state,
+            Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                delete: Some(vec!["Pressing".to_owned(), "Fermentation".to_owned()]),
+                modify: Some(vec![(
+                    "Pressing".to_owned(),
+                    UnitProcess {
+                        name: "Pressing".to_owned(),
+                        outputs: vec![],
+                        inputs: vec![],
+                        properties: vec![],
+                        writers: None,
+                        annotations: None,
+                        balance: None,
+                    },
+                )]),
+                add: None,
+                delete_links: None,
+                add_links: None,
+            }),
+        );
+
+        // The modification is valid, the added unit process duplicates a name.
+        assert_rollback(
+            &init_state,
+            Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                delete: None,
+                modify: Some(vec![(
+                    "Pressing".to_owned(),
+                    UnitProcess {
+                        name: "Pressing 2".to_owned(),
+                        outputs: vec![],
+                        inputs: vec![],
+                        properties: vec![],
+                        writers: None,
+                        annotations: None,
+                        balance: None,
+                    },
+                )]),
+                add: Some(vec![UnitProcess {
+                    name: "Fermentation".to_owned(),
+                    outputs: vec![],
+                    inputs: vec![],
+                    properties: vec![],
+                    writers: None,
+                    annotations: None,
+                    balance: None,
+                }]),
+                delete_links: None,
+                add_links: None,
+            }),
+        );
+    }
+
+    #[test]
+    fn test_rollback_register_data() {
+        let init_state = rollback_state();
+
+        // The first unit process is updated, the second one receives a wrong type.
+        assert_rollback(
+            &init_state,
+            Events::RegisterData(vec![
+                UnitData {
+                    name: "Pressing".to_owned(),
+                    inputs: Some(vec![RegisterData {
+                        name: "Example Basic".to_owned(),
+                        type_name: "UserBasic".to_owned(),
+                        content: json!("NewValue"),
+                        targets: None,
+                    }]),
+                    outputs: None,
+                    properties: None,
+                    validation: None,
+                },
+                UnitData {
+                    name: "Fermentation".to_owned(),
+                    inputs: Some(vec![RegisterData {
+                        name: "Example Basic".to_owned(),
+                        type_name: "UserBasic".to_owned(),
+                        content: json!(10),
+                        targets: None,
+                    }]),
+                    outputs: None,
+                    properties: None,
+                    validation: None,
+                },
+            ]),
+        );
+
+        // The unit process does not exist.
+        assert_rollback(
+            &init_state,
+            Events::RegisterData(vec![UnitData {
+                name: "Bottling".to_owned(),
+                inputs: Some(vec![RegisterData {
+                    name: "Example Basic".to_owned(),
+                    type_name: "UserBasic".to_owned(),
+                    content: json!("NewValue"),
+                    targets: None,
+                }]),
+                outputs: None,
+                properties: None,
+                validation: None,
+            }]),
+        );
+    }
+
+    #[test]
+    fn test_error_json() {
+        let init_state = rollback_state();
+
+        let context = sdk::Context {
+            event: Events::RegisterData(vec![UnitData {
+                name: "Fermentation".to_owned(),
+                inputs: None,
+                outputs: Some(vec![RegisterData {
                     name: "Example Object".to_owned(),
                     type_name: "UserObject".to_owned(),
-                    content: json!({"value": 0}),
+                    content: json!({"name": 10}),
                     targets: None,
-                    metadata: None,
-                },
-                Data {
-                    name: "Example f64".to_owned(),
-                    type_name: "f64".to_owned(),
-                    content: json!(21412.0),
+                }]),
+                properties: None,
+                validation: None,
+            }]),
+            is_owner: false,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+        contract_logic(&context, &mut result);
+
+        assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+        assert_eq!(error.path.as_deref(), Some("$.name"));
+        assert_eq!(error.expected.as_deref(), Some("String"));
+        assert_eq!(error.received.as_deref(), Some("number"));
+        assert_eq!(error.unit_process.as_deref(), Some("Fermentation"));
+        assert_eq!(error.element.as_deref(), Some("Example Object"));
+        assert!(error.to_string().starts_with(&error.message));
+        assert!(error.to_string().contains("unit process 'Fermentation'"));
+
+        let context = sdk::Context {
+            event: Events::RegisterData(vec![UnitData {
+                name: "Pressing".to_owned(),
+                inputs: Some(vec![RegisterData {
+                    name: "Example Basic".to_owned(),
+                    type_name: "String".to_owned(),
+                    content: json!("ExampleBasic"),
                     targets: None,
-                    metadata: None,
-                },
-            ],
-            inputs: vec![Data {
-                name: "Example Basic".to_owned(),
-                type_name: "UserBasic".to_owned(),
-                content: json!(-132.55),
-                targets: None,
-                metadata: None,
-            }],
-            properties: vec![],
+                }]),
+                outputs: None,
+                properties: None,
+                validation: None,
+            }]),
+            is_owner: false,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+        contract_logic(&context, &mut result);
+
+        assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+        assert_eq!(error.path, None);
+        assert_eq!(error.expected.as_deref(), Some("UserBasic"));
+        assert_eq!(error.received.as_deref(), Some("String"));
+        assert_eq!(error.unit_process.as_deref(), Some("Pressing"));
+        assert_eq!(error.element.as_deref(), Some("Example Basic"));
+
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: Some(vec![(
+                    "Reading".to_owned(),
+                    Fields::Object(HashMap::from([(
+                        "sensor id".to_owned(),
+                        DynamicType::Vec(Box::new(DynamicType::Type("Unknown".to_owned()))),
+                    )])),
+                )]),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state);
+        contract_logic(&context, &mut result);
+
+        assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::UnknownType);
+        assert_eq!(error.path.as_deref(), Some("$[\"sensor id\"]"));
+        assert_eq!(error.received.as_deref(), Some("Unknown"));
+        assert_eq!(error.element.as_deref(), Some("Reading"));
+    }
+
+    #[test]
+    fn test_all_errors() {
+        let mut init_state = rollback_state();
+        init_state.custom_types.insert(
+            "Reading".to_owned(),
+            Fields::Object(HashMap::from([
+                ("sensor".to_owned(), DynamicType::String),
+                ("values".to_owned(), DynamicType::Vec(Box::new(DynamicType::f64))),
+            ])),
+        );
+        init_state.unit_process[1].outputs.push(Data {
+            name: "Reading".to_owned(),
+            type_name: "Reading".to_owned(),
+            content: json!({"sensor": "T1", "values": []}),
+            targets: None,
+            metadata: None,
+            annotations: None,
+            registered: false,
+        });
+
+        let event = Events::RegisterData(vec![
+            UnitData {
+                name: "Pressing".to_owned(),
+                inputs: Some(vec![RegisterData {
+                    name: "Example Basic".to_owned(),
+                    type_name: "UserBasic".to_owned(),
+                    content: json!(10),
+                    targets: None,
+                }]),
+                outputs: None,
+                properties: None,
+                validation: None,
+            },
+            UnitData {
+                name: "Fermentation".to_owned(),
+                inputs: None,
+                outputs: Some(vec![RegisterData {
+                    name: "Reading".to_owned(),
+                    type_name: "Reading".to_owned(),
+                    content: json!({"values": [1.5, "two", 3, "four"], "unit": "C"}),
+                    targets: None,
+                }]),
+                properties: None,
+                validation: None,
+            },
+            UnitData {
+                name: "Bottling".to_owned(),
+                inputs: None,
+                outputs: None,
+                properties: None,
+                validation: None,
+            },
+        ]);
+
+        let context = sdk::Context {
+            event: event.clone(),
+            is_owner: true,
         };
 
+        let mut result = sdk::ContractResult::new(init_state.clone());
+        contract_logic(&context, &mut result);
+
+        assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_ne!(error.kind, ErrorKind::Multiple);
+        assert!(error.errors.is_empty());
+
+        init_state.validation = ValidationMode::AllErrors;
         let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
-                delete: None,
-                modify: None,
-                add: Some(vec![unit_process]),
-            }),
-            is_owner: false,
+            event: event.clone(),
+            is_owner: true,
         };
 
-        let mut result = sdk::ContractResult::new(result.state);
+        let mut result = sdk::ContractResult::new(init_state.clone());
         contract_logic(&context, &mut result);
 
-        println!("{}", result.error);
-        assert!(result.error.is_empty());
+        assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::Multiple);
+        assert_eq!(error.errors.len(), 6);
+
+        let mut found = error
+            .errors
+            .iter()
+            .map(|e| {
+                (
+                    e.kind,
+                    e.unit_process.clone().unwrap_or_default(),
+                    e.path.clone().unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<_>>();
+        found.sort_by(|a, b| (&a.1, &a.2).cmp(&(&b.1, &b.2)));
 
-        assert_eq!(result.state.unit_process.len(), 1);
-        assert_eq!(result.state.unit_process[0].name, "Unit example");
-        assert_eq!(result.state.unit_process[0].outputs.len(), 2);
+        assert_eq!(
+            found,
+            vec![
+                (ErrorKind::NotFound, "Bottling".to_owned(), "".to_owned()),
+                (ErrorKind::MissingField, "Fermentation".to_owned(), "$.sensor".to_owned()),
+                (ErrorKind::UnexpectedField, "Fermentation".to_owned(), "$.unit".to_owned()),
+                (ErrorKind::TypeMismatch, "Fermentation".to_owned(), "$.values[1]".to_owned()),
+                (ErrorKind::TypeMismatch, "Fermentation".to_owned(), "$.values[3]".to_owned()),
+                (ErrorKind::TypeMismatch, "Pressing".to_owned(), "$".to_owned()),
+            ]
+        );
+        assert!(error.to_string().contains("unit process 'Pressing'"));
 
-        let data = result.state.unit_process[0].outputs[0].clone();
-        assert_eq!(data.name, "Example Object");
-        assert_eq!(data.type_name, "UserObject");
-        assert_eq!(data.content, json!({"value": 0}));
-        let data = result.state.unit_process[0].outputs[1].clone();
-        assert_eq!(data.name, "Example f64");
-        assert_eq!(data.type_name, "f64");
-        assert_eq!(data.content, json!(21412.0),);
+        // Any issuer can ask for every error of its own batch.
+        init_state.validation = ValidationMode::FirstError;
+        let Events::RegisterData(mut units) = event else {
+            unreachable!()
+        };
+        units[1].validation = Some(ValidationMode::AllErrors);
+        let context = sdk::Context {
+            event: Events::RegisterData(units),
+            is_owner: false,
+        };
 
-        let data = result.state.unit_process[0].inputs[0].clone();
-        assert_eq!(data.name, "Example Basic");
-        assert_eq!(data.type_name, "UserBasic");
-        assert_eq!(data.content, json!(-132.55),);
+        let mut result = sdk::ContractResult::new(init_state);
+        contract_logic(&context, &mut result);
 
-        assert!(result.success);
+        assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::Multiple);
+        assert_eq!(error.errors.len(), 6);
     }
 
     #[test]
-    fn test_bool() {
-        ////////////////////////////////////////////////////////////////
-        // Type definition
-        ////////////////////////////////////////////////////////////////
+    fn test_all_errors_init() {
         let init_state = ProductionSystem {
-            name: "example".to_owned(),
-            version: 1,
+            name: "".to_owned(),
+            version: 0,
             unit_process: vec![],
             custom_types: HashMap::new(),
             properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
         };
 
         let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
-                delete: None,
-                add: Some(vec![
-                    (
-                        "UserObject".to_owned(),
-                        Fields::Object(HashMap::from([("value".to_owned(), DynamicType::bool)])),
-                    ),
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::Init {
+                name: "wine process".to_owned(),
+                unit_process: None,
+                properties: None,
+                types: Some(vec![
+                    ("String".to_owned(), Fields::Basic(Box::new(DynamicType::String))),
                     (
-                        "UserBasic".to_owned(),
-                        Fields::Basic(Box::new(DynamicType::bool)),
+                        "Reading".to_owned(),
+                        Fields::Object(HashMap::from([
+                            ("sensor".to_owned(), DynamicType::Dummy),
+                            ("value".to_owned(), DynamicType::Type("Unknown".to_owned())),
+                        ])),
                     ),
+                    ("Grape".to_owned(), Fields::Basic(Box::new(DynamicType::String))),
                 ]),
+                validation: Some(ValidationMode::AllErrors),
+                permissions: None,
+                links: None,
+                subject_id: None,
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
-        let mut result = sdk::ContractResult::new(init_state.clone());
-
+        let mut result = sdk::ContractResult::new(init_state);
         contract_logic(&context, &mut result);
 
-        assert!(result.error.is_empty());
-        assert_eq!(
-            result.state.custom_types.get("UserObject").unwrap().clone(),
-            Fields::Object(HashMap::from([("value".to_owned(), DynamicType::bool)]))
-        );
-        assert_eq!(
-            result.state.custom_types.get("UserBasic").unwrap().clone(),
-            Fields::Basic(Box::new(DynamicType::bool))
-        );
-        assert_eq!(result.state.custom_types.len(), 2);
+        assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::Multiple);
+
+        let found = error
+            .errors
+            .iter()
+            .map(|e| (e.kind, e.element.clone().unwrap_or_default()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(error.errors.len(), 3);
+        assert!(found.contains(&(ErrorKind::ReservedName, "String".to_owned())));
+        assert!(found.contains(&(ErrorKind::InvalidType, "Reading".to_owned())));
+        assert!(found.contains(&(ErrorKind::UnknownType, "Reading".to_owned())));
+    }
 
-        ////////////////////////////////////////////////////////////////
-        // Unit process
-        ////////////////////////////////////////////////////////////////
-        let unit_process = UnitProcess {
-            name: "Unit example".to_owned(),
-            outputs: vec![
-                Data {
-                    name: "Example Object".to_owned(),
-                    type_name: "UserObject".to_owned(),
-                    content: json!({"value": false}),
-                    targets: None,
-                    metadata: None,
-                },
-                Data {
-                    name: "Example bool".to_owned(),
-                    type_name: "bool".to_owned(),
-                    content: json!(true),
-                    targets: None,
-                    metadata: None,
-                },
-            ],
-            inputs: vec![Data {
+    #[test]
+    fn test_permissions() {
+        let init_state = rollback_state();
+
+        let modify_types = Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+            delete: None,
+            add: Some(vec![(
+                "Grape".to_owned(),
+                Fields::Basic(Box::new(DynamicType::String)),
+            )]),
+            replace: None,
+            migrate: None,
+        });
+        let register_data = Events::RegisterData(vec![UnitData {
+            name: "Pressing".to_owned(),
+            inputs: Some(vec![RegisterData {
                 name: "Example Basic".to_owned(),
                 type_name: "UserBasic".to_owned(),
-                content: json!(false),
+                content: json!("NewValue"),
                 targets: None,
-                metadata: None,
-            }],
-            properties: vec![],
-        };
+            }]),
+            outputs: None,
+            properties: None,
+            validation: None,
+        }]);
 
+        // By default only the owner can change the production system.
         let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
-                delete: None,
-                modify: None,
-                add: Some(vec![unit_process]),
-            }),
+            event: modify_types.clone(),
             is_owner: false,
         };
 
-        let mut result = sdk::ContractResult::new(result.state);
+        let mut result = sdk::ContractResult::new(init_state.clone());
         contract_logic(&context, &mut result);
 
-        println!("{}", result.error);
-        assert!(result.error.is_empty());
+        assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::Unauthorized);
+        assert!(error.message.contains("'ModifyTypes'"));
+        assert_eq!(result.state.version, 1);
 
-        assert_eq!(result.state.unit_process.len(), 1);
-        assert_eq!(result.state.unit_process[0].name, "Unit example");
-        assert_eq!(result.state.unit_process[0].outputs.len(), 2);
+        // Anyone can register data.
+        let context = sdk::Context {
+            event: register_data.clone(),
+            is_owner: false,
+        };
 
-        let data = result.state.unit_process[0].outputs[0].clone();
-        assert_eq!(data.name, "Example Object");
-        assert_eq!(data.type_name, "UserObject");
-        assert_eq!(data.content, json!({"value": false}));
-        let data = result.state.unit_process[0].outputs[1].clone();
-        assert_eq!(data.name, "Example bool");
-        assert_eq!(data.type_name, "bool");
-        assert_eq!(data.content, json!(true),);
+        let mut result = sdk::ContractResult::new(init_state.clone());
+        contract_logic(&context, &mut result);
+        assert!(result.success);
 
-        let data = result.state.unit_process[0].inputs[0].clone();
-        assert_eq!(data.name, "Example Basic");
-        assert_eq!(data.type_name, "UserBasic");
-        assert_eq!(data.content, json!(false),);
+        // The owner opens the types and closes the data registration.
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyProductionSystem {
+                name: None,
+                delete_properties: None,
+                modify_properties: None,
+                add_properties: None,
+                validation: None,
+                permissions: Some(Permissions {
+                    modify_types: Access::Anyone,
+                    register_data: Access::Owner,
+                    ..Permissions::default()
+                }),
+                subject_id: None,
+            }),
+            is_owner: true,
+        };
 
+        let mut result = sdk::ContractResult::new(init_state.clone());
+        contract_logic(&context, &mut result);
+        assert!(result.success);
+        let state = result.state;
+
+        let context = sdk::Context {
+            event: modify_types,
+            is_owner: false,
+        };
+
+        let mut result = sdk::ContractResult::new(state.clone());
+        contract_logic(&context, &mut result);
         assert!(result.success);
+        assert!(result.state.custom_types.contains_key("Grape"));
+
+        let context = sdk::Context {
+            event: register_data,
+            is_owner: false,
+        };
+
+        let mut result = sdk::ContractResult::new(state.clone());
+        contract_logic(&context, &mut result);
+
+        assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::Unauthorized);
+        assert_eq!(error.unit_process.as_deref(), Some("Pressing"));
     }
 
     #[test]
-    fn test_dummy_fail() {
-        ////////////////////////////////////////////////////////////////
-        // Type definition
-        ////////////////////////////////////////////////////////////////
+    fn test_permissions_init() {
         let init_state = ProductionSystem {
-            name: "example".to_owned(),
-            version: 1,
+            name: "".to_owned(),
+            version: 0,
             unit_process: vec![],
             custom_types: HashMap::new(),
             properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
         };
 
-        let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
-                delete: None,
-                add: Some(vec![(
-                    "UserObject".to_owned(),
-                    Fields::Object(HashMap::from([("value".to_owned(), DynamicType::Dummy)])),
-                )]),
+        let event = Events::ChangeProductionSystem(ChangeProductionSystem::Init {
+            name: "wine process".to_owned(),
+            unit_process: None,
+            properties: None,
+            types: None,
+            validation: None,
+            permissions: Some(Permissions {
+                register_data: Access::Owner,
+                ..Permissions::default()
             }),
+            links: None,
+            subject_id: None,
+        });
+
+        let context = sdk::Context {
+            event: event.clone(),
             is_owner: false,
         };
 
         let mut result = sdk::ContractResult::new(init_state.clone());
-
         contract_logic(&context, &mut result);
 
-        assert!(!result.error.is_empty());
         assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::Unauthorized);
 
         let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
-                delete: None,
-                add: Some(vec![(
-                    "UserBasic".to_owned(),
-                    Fields::Basic(Box::new(DynamicType::Dummy)),
-                )]),
-            }),
-            is_owner: false,
+            event,
+            is_owner: true,
         };
 
-        let mut result = sdk::ContractResult::new(init_state.clone());
-
+        let mut result = sdk::ContractResult::new(init_state);
         contract_logic(&context, &mut result);
 
-        assert!(!result.error.is_empty());
-        assert!(!result.success);
+        assert!(result.success);
+        assert_eq!(result.state.permissions.register_data, Access::Owner);
+        assert_eq!(result.state.permissions.modify_types, Access::Owner);
+    }
 
-        ////////////////////////////////////////////////////////////////
-        // Unit process
-        ////////////////////////////////////////////////////////////////
-        let unit_process = UnitProcess {
-            name: "Unit example".to_owned(),
-            outputs: vec![Data {
-                name: "Example Dummy".to_owned(),
-                type_name: "Dummy".to_owned(),
-                content: json!({}),
+    #[test]
+    fn test_unit_process_writers() {
+        let mut init_state = rollback_state();
+        init_state.unit_process[1].writers = Some(Access::Owner);
+
+        let register_data = |name: &str| UnitData {
+            name: name.to_owned(),
+            inputs: Some(vec![RegisterData {
+                name: "Example Basic".to_owned(),
+                type_name: "UserBasic".to_owned(),
+                content: json!("NewValue"),
                 targets: None,
-                metadata: None,
-            }],
-            inputs: vec![],
-            properties: vec![],
+            }]),
+            outputs: None,
+            properties: None,
+            validation: None,
         };
 
+        // Fermentation readings are restricted to the owner.
         let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
-                delete: None,
-                modify: None,
-                add: Some(vec![unit_process]),
-            }),
+            event: Events::RegisterData(vec![register_data("Pressing"), register_data("Fermentation")]),
             is_owner: false,
         };
 
         let mut result = sdk::ContractResult::new(init_state.clone());
         contract_logic(&context, &mut result);
 
-        assert!(!result.error.is_empty());
         assert!(!result.success);
-    }
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::Unauthorized);
+        assert_eq!(error.unit_process.as_deref(), Some("Fermentation"));
+        assert_eq!(result.state.unit_process[0].inputs[0].content, json!("ExampleBasic"));
 
-    #[test]
-    fn test_option_fail() {
-        ////////////////////////////////////////////////////////////////
-        // Type definition
-        ////////////////////////////////////////////////////////////////
-        let init_state = ProductionSystem {
-            name: "example".to_owned(),
-            version: 1,
-            unit_process: vec![],
-            custom_types: HashMap::new(),
-            properties: vec![],
+        let context = sdk::Context {
+            event: Events::RegisterData(vec![register_data("Fermentation")]),
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state.clone());
+        contract_logic(&context, &mut result);
+        assert!(result.success);
+        assert_eq!(result.state.unit_process[1].inputs[0].content, json!("NewValue"));
+
+        // Pressing stays open when the production system closes the registration.
+        init_state.permissions.register_data = Access::Owner;
+        init_state.unit_process[0].writers = Some(Access::Anyone);
+
+        let context = sdk::Context {
+            event: Events::RegisterData(vec![register_data("Pressing")]),
+            is_owner: false,
         };
 
+        let mut result = sdk::ContractResult::new(init_state);
+        contract_logic(&context, &mut result);
+        assert!(result.success);
+        assert_eq!(result.state.unit_process[0].inputs[0].content, json!("NewValue"));
+    }
+
+    #[test]
+    fn test_delete_type_in_use() {
+        let mut init_state = rollback_state();
+        init_state.custom_types.insert(
+            "Batch".to_owned(),
+            Fields::Object(HashMap::from([(
+                "labels".to_owned(),
+                DynamicType::Vec(Box::new(DynamicType::Type("UserBasic".to_owned()))),
+            )])),
+        );
+        init_state.properties.push(Properties {
+            name: "Label".to_owned(),
+            type_name: "UserBasic".to_owned(),
+            content: json!("Label"),
+            annotations: None,
+        });
+
         let context = sdk::Context {
             event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
-                delete: None,
-                add: Some(vec![(
-                    "UserObject".to_owned(),
-                    Fields::Object(HashMap::from([(
-                        "value".to_owned(),
-                        DynamicType::Option(Box::new(DynamicType::Dummy)),
-                    )])),
-                )]),
+                delete: Some(vec!["UserBasic".to_owned()]),
+                add: None,
+                replace: None,
+                migrate: None,
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
         let mut result = sdk::ContractResult::new(init_state.clone());
-
         contract_logic(&context, &mut result);
 
-        assert!(!result.error.is_empty());
         assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::InUse);
+        assert_eq!(error.element.as_deref(), Some("UserBasic"));
+        assert!(error.message.contains("type 'Batch'"));
+        assert!(
+            error
+                .message
+                .contains("unit process 'Pressing' input 'Example Basic'")
+        );
+        assert!(
+            error
+                .message
+                .contains("unit process 'Fermentation' input 'Example Basic'")
+        );
+        assert!(error.message.contains("property 'Label'"));
+        assert!(result.state.custom_types.contains_key("UserBasic"));
 
+        // Redefining the type keeps its dependants.
         let context = sdk::Context {
             event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
-                delete: None,
+                delete: Some(vec!["UserBasic".to_owned()]),
                 add: Some(vec![(
                     "UserBasic".to_owned(),
-                    Fields::Basic(Box::new(DynamicType::Option(Box::new(DynamicType::String)))),
+                    Fields::Basic(Box::new(DynamicType::String)),
                 )]),
+                replace: None,
+                migrate: None,
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
         let mut result = sdk::ContractResult::new(init_state.clone());
-
         contract_logic(&context, &mut result);
+        assert!(result.success);
 
-        assert!(!result.error.is_empty());
-        assert!(!result.success);
-
-        ////////////////////////////////////////////////////////////////
-        // Unit process
-        ////////////////////////////////////////////////////////////////
-        let unit_process = UnitProcess {
-            name: "Unit example".to_owned(),
-            outputs: vec![Data {
-                name: "Example Dummy".to_owned(),
-                type_name: "Option".to_owned(),
-                content: json!({}),
-                targets: None,
-                metadata: None,
-            }],
-            inputs: vec![],
-            properties: vec![],
-        };
-
+        // A type that is not used can be deleted.
         let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
-                delete: None,
-                modify: None,
-                add: Some(vec![unit_process]),
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: Some(vec!["Batch".to_owned()]),
+                add: None,
+                replace: None,
+                migrate: None,
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
-        let mut result = sdk::ContractResult::new(init_state.clone());
+        let mut result = sdk::ContractResult::new(init_state);
         contract_logic(&context, &mut result);
-
-        assert!(!result.error.is_empty());
-        assert!(!result.success);
+        assert!(result.success);
+        assert!(!result.state.custom_types.contains_key("Batch"));
     }
 
-    // TODO Poner más ejemplos como Enum(Vec)...
     #[test]
-    fn test_enum() {
-        ////////////////////////////////////////////////////////////////
-        // Type definition
-        ////////////////////////////////////////////////////////////////
-        let init_state = ProductionSystem {
-            name: "example".to_owned(),
-            version: 1,
-            unit_process: vec![],
-            custom_types: HashMap::new(),
-            properties: vec![],
-        };
+    fn test_delete_type_replace() {
+        let mut init_state = rollback_state();
+        init_state.custom_types.insert(
+            "Batch".to_owned(),
+            Fields::Object(HashMap::from([(
+                "labels".to_owned(),
+                DynamicType::Vec(Box::new(DynamicType::Type("UserBasic".to_owned()))),
+            )])),
+        );
 
         let context = sdk::Context {
             event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
-                delete: None,
-                add: Some(vec![
-                    (
-                        "UserObject".to_owned(),
-                        Fields::Object(HashMap::from([(
-                            "value".to_owned(),
-                            DynamicType::Enum(HashMap::from([
-                                ("Name".to_owned(), DynamicType::Dummy),
-                                ("Data".to_owned(), DynamicType::String),
-                            ])),
-                        )])),
-                    ),
-                    (
-                        "UserBasic".to_owned(),
-                        Fields::Basic(Box::new(DynamicType::Enum(HashMap::from([
-                            ("Name".to_owned(), DynamicType::Dummy),
-                            ("Data".to_owned(), DynamicType::String),
-                        ])))),
-                    ),
-                ]),
+                delete: Some(vec!["UserBasic".to_owned()]),
+                add: Some(vec![(
+                    "Label".to_owned(),
+                    Fields::Basic(Box::new(DynamicType::String)),
+                )]),
+                replace: Some(vec![("UserBasic".to_owned(), "Label".to_owned())]),
+                migrate: None,
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
         let mut result = sdk::ContractResult::new(init_state.clone());
-
         contract_logic(&context, &mut result);
 
-        assert!(result.error.is_empty());
+        assert!(result.success);
+        assert!(!result.state.custom_types.contains_key("UserBasic"));
         assert_eq!(
-            result.state.custom_types.get("UserObject").unwrap().clone(),
+            result.state.custom_types.get("Batch").unwrap().clone(),
             Fields::Object(HashMap::from([(
-                "value".to_owned(),
-                DynamicType::Enum(HashMap::from([
-                    ("Name".to_owned(), DynamicType::Dummy),
-                    ("Data".to_owned(), DynamicType::String)
-                ]))
+                "labels".to_owned(),
+                DynamicType::Vec(Box::new(DynamicType::Type("Label".to_owned()))),
             )]))
         );
-        assert_eq!(
-            result.state.custom_types.get("UserBasic").unwrap().clone(),
-            Fields::Basic(Box::new(DynamicType::Enum(HashMap::from([
-                ("Name".to_owned(), DynamicType::Dummy),
-                ("Data".to_owned(), DynamicType::String)
-            ]))))
-        );
-        assert_eq!(result.state.custom_types.len(), 2);
+        for unit_process in result.state.unit_process.iter() {
+            assert_eq!(unit_process.inputs[0].type_name, "Label");
+        }
 
-        ////////////////////////////////////////////////////////////////
-        // Unit process
-        ////////////////////////////////////////////////////////////////
-        let unit_process = UnitProcess {
-            name: "Unit example".to_owned(),
-            outputs: vec![Data {
-                name: "Example Object".to_owned(),
-                type_name: "UserObject".to_owned(),
-                content: json!({"value": {"Data": "info"}}),
-                targets: None,
-                metadata: None,
-            }],
-            inputs: vec![Data {
-                name: "Example Basic".to_owned(),
-                type_name: "UserBasic".to_owned(),
-                content: json!("Name"),
-                targets: None,
-                metadata: None,
-            }],
-            properties: vec![],
+        // The stored content must be valid for the replacement.
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: Some(vec!["UserBasic".to_owned()]),
+                add: None,
+                replace: Some(vec![("UserBasic".to_owned(), "UserObject".to_owned())]),
+                migrate: None,
+            }),
+            is_owner: true,
         };
 
+        let mut result = sdk::ContractResult::new(init_state.clone());
+        contract_logic(&context, &mut result);
+
+        assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+        assert_eq!(error.element.as_deref(), Some("Example Basic"));
+        assert!(result.state.custom_types.contains_key("UserBasic"));
+
+        // Only deleted types can be replaced.
         let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
-                delete: None,
-                modify: None,
-                add: Some(vec![unit_process]),
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: Some(vec!["Batch".to_owned()]),
+                add: None,
+                replace: Some(vec![("UserBasic".to_owned(), "String".to_owned())]),
+                migrate: None,
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
-        let mut result = sdk::ContractResult::new(result.state);
+        let mut result = sdk::ContractResult::new(init_state);
         contract_logic(&context, &mut result);
 
-        println!("{}", result.error);
-        assert!(result.error.is_empty());
+        assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::NotFound);
+    }
 
-        assert_eq!(result.state.unit_process.len(), 1);
-        assert_eq!(result.state.unit_process[0].name, "Unit example");
-        assert_eq!(result.state.unit_process[0].outputs.len(), 1);
+    #[test]
+    fn test_redefine_type() {
+        let init_state = rollback_state();
+
+        let redefine = || {
+            Some(vec![(
+                "UserObject".to_owned(),
+                Fields::Object(HashMap::from([
+                    ("name".to_owned(), DynamicType::String),
+                    ("liters".to_owned(), DynamicType::u64),
+                ])),
+            )])
+        };
 
-        let data = result.state.unit_process[0].outputs[0].clone();
-        assert_eq!(data.name, "Example Object");
-        assert_eq!(data.type_name, "UserObject");
-        assert_eq!(data.content, json!({"value": {"Data": "info"}}));
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: redefine(),
+                replace: None,
+                migrate: None,
+            }),
+            is_owner: true,
+        };
 
-        let data = result.state.unit_process[0].inputs[0].clone();
-        assert_eq!(data.name, "Example Basic");
-        assert_eq!(data.type_name, "UserBasic");
-        assert_eq!(data.content, json!("Name"));
+        let mut result = sdk::ContractResult::new(init_state.clone());
+        contract_logic(&context, &mut result);
 
-        assert!(result.success);
+        assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::MissingField);
+        assert_eq!(error.unit_process.as_deref(), Some("Pressing"));
+        assert_eq!(error.element.as_deref(), Some("Example Object"));
+        assert_eq!(error.path.as_deref(), Some("$.liters"));
+        assert_eq!(
+            serde_json::to_vec(&result.state).unwrap(),
+            serde_json::to_vec(&init_state).unwrap()
+        );
 
-        ////////////////////////////////////////////////////////////////
-        // Unit process register data
-        ////////////////////////////////////////////////////////////////
+        // Only one of the unit processes is migrated.
         let context = sdk::Context {
-            event: Events::RegisterData(vec![UnitData {
-                name: "Unit example".to_owned(),
-                outputs: Some(vec![RegisterData {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: redefine(),
+                replace: None,
+                migrate: Some(vec![Migration::Data {
+                    unit_process: "Pressing".to_owned(),
                     name: "Example Object".to_owned(),
-                    type_name: "UserObject".to_owned(),
-                    content: json!({"value": "Name"}),
-                    targets: None,
-                }]),
-                inputs: Some(vec![RegisterData {
-                    name: "Example Basic".to_owned(),
-                    type_name: "UserBasic".to_owned(),
-                    content: json!({"Data": "info"}),
-                    targets: None,
+                    content: json!({"name": "ExampleName", "liters": 100}),
                 }]),
-                properties: None,
-            }]),
-            is_owner: false,
+            }),
+            is_owner: true,
         };
 
-        let mut result = sdk::ContractResult::new(result.state);
+        let mut result = sdk::ContractResult::new(init_state.clone());
         contract_logic(&context, &mut result);
 
-        println!("{}", result.error);
-        assert!(result.error.is_empty());
-
-        assert_eq!(result.state.unit_process.len(), 1);
-        assert_eq!(result.state.unit_process[0].name, "Unit example");
-        assert_eq!(result.state.unit_process[0].outputs.len(), 1);
-
-        let data = result.state.unit_process[0].outputs[0].clone();
-        assert_eq!(data.name, "Example Object");
-        assert_eq!(data.type_name, "UserObject");
-        assert_eq!(data.content, json!({"value": "Name"}));
-
-        let data = result.state.unit_process[0].inputs[0].clone();
-        assert_eq!(data.name, "Example Basic");
-        assert_eq!(data.type_name, "UserBasic");
-        assert_eq!(data.content, json!({"Data": "info"}));
-
-        assert!(result.success);
-    }
-
-    #[test]
-    fn test_enum_fail() {
-        ////////////////////////////////////////////////////////////////
-        // Type definition
-        ////////////////////////////////////////////////////////////////
-        let init_state = ProductionSystem {
-            name: "example".to_owned(),
-            version: 1,
-            unit_process: vec![],
-            custom_types: HashMap::new(),
-            properties: vec![],
-        };
+        assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::MissingField);
+        assert_eq!(error.unit_process.as_deref(), Some("Fermentation"));
 
         let context = sdk::Context {
             event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
                 delete: None,
-                add: Some(vec![(
-                    "UserObject".to_owned(),
-                    Fields::Object(HashMap::from([(
-                        "value".to_owned(),
-                        DynamicType::Enum(HashMap::from([
-                            ("Name".to_owned(), DynamicType::Dummy),
-                            (
-                                "Data".to_owned(),
-                                DynamicType::Option(Box::new(DynamicType::String)),
-                            ),
-                        ])),
-                    )])),
-                )]),
+                add: redefine(),
+                replace: None,
+                migrate: Some(vec![
+                    Migration::Data {
+                        unit_process: "Pressing".to_owned(),
+                        name: "Example Object".to_owned(),
+                        content: json!({"name": "ExampleName", "liters": 100}),
+                    },
+                    Migration::Data {
+                        unit_process: "Fermentation".to_owned(),
+                        name: "Example Object".to_owned(),
+                        content: json!({"name": "ExampleName", "liters": 90}),
+                    },
+                ]),
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
-        let mut result = sdk::ContractResult::new(init_state.clone());
-
+        let mut result = sdk::ContractResult::new(init_state);
         contract_logic(&context, &mut result);
 
-        assert!(!result.error.is_empty());
-        assert!(!result.success);
+        assert!(result.success);
+        assert_eq!(result.state.version, 2);
+        assert_eq!(
+            result.state.unit_process[1].outputs[0].content,
+            json!({"name": "ExampleName", "liters": 90})
+        );
+    }
+
+    #[test]
+    fn test_migrate_not_found() {
+        let init_state = rollback_state();
 
         let context = sdk::Context {
             event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
                 delete: None,
                 add: Some(vec![(
                     "UserBasic".to_owned(),
-                    Fields::Basic(Box::new(DynamicType::Enum(HashMap::from([
-                        ("Name".to_owned(), DynamicType::Dummy),
-                        (
-                            "Data".to_owned(),
-                            DynamicType::Option(Box::new(DynamicType::String)),
-                        ),
-                    ])))),
+                    Fields::Basic(Box::new(DynamicType::u64)),
                 )]),
+                replace: None,
+                migrate: Some(vec![
+                    Migration::Data {
+                        unit_process: "Bottling".to_owned(),
+                        name: "Example Basic".to_owned(),
+                        content: json!(1),
+                    },
+                    Migration::Metadata {
+                        unit_process: "Pressing".to_owned(),
+                        name: "Example Basic".to_owned(),
+                        content: json!(1),
+                    },
+                    Migration::Property {
+                        unit_process: None,
+                        name: "Unknown".to_owned(),
+                        content: json!(1),
+                    },
+                ]),
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
         let mut result = sdk::ContractResult::new(init_state.clone());
+        contract_logic(&context, &mut result);
+
+        assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::NotFound);
+        assert_eq!(error.unit_process.as_deref(), Some("Bottling"));
 
+        let mut state = init_state.clone();
+        state.validation = ValidationMode::AllErrors;
+        let mut result = sdk::ContractResult::new(state);
         contract_logic(&context, &mut result);
 
-        assert!(!result.error.is_empty());
         assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::Multiple);
+        assert_eq!(error.errors.len(), 3);
+        assert!(
+            error
+                .errors
+                .iter()
+                .all(|error| error.kind == ErrorKind::NotFound)
+        );
+    }
 
-        ////////////////////////////////////////////////////////////////
-        // Unit process
-        ////////////////////////////////////////////////////////////////
-        let unit_process = UnitProcess {
-            name: "Unit example".to_owned(),
-            outputs: vec![Data {
-                name: "Example Dummy".to_owned(),
-                type_name: "Enum".to_owned(),
-                content: json!({}),
-                targets: None,
-                metadata: None,
+    fn migration_state() -> ProductionSystem {
+        ProductionSystem {
+            name: "example".to_owned(),
+            version: 1,
+            unit_process: vec![UnitProcess {
+                name: "Bottling".to_owned(),
+                inputs: vec![],
+                outputs: vec![Data {
+                    name: "Batch".to_owned(),
+                    type_name: "Batch".to_owned(),
+                    content: json!({"wines": [
+                        {"name": "Reserva", "grape": "Merlot", "style": "Red", "notes": "Oak"},
+                        {"name": "Joven", "grape": "Syrah", "style": null, "notes": ""}
+                    ]}),
+                    targets: None,
+                    metadata: None,
+                    annotations: None,
+                    registered: false,
+                }],
+                properties: vec![],
+                writers: None,
+                annotations: None,
+                balance: None,
             }],
-            inputs: vec![],
-            properties: vec![],
-        };
+            custom_types: HashMap::from([
+                (
+                    "Grape".to_owned(),
+                    Fields::Basic(Box::new(DynamicType::Enum(HashMap::from([
+                        ("Merlot".to_owned(), DynamicType::Dummy),
+                        ("Syrah".to_owned(), DynamicType::Dummy),
+                    ])))),
+                ),
+                (
+                    "Wine".to_owned(),
+                    Fields::Object(HashMap::from([
+                        ("name".to_owned(), DynamicType::String),
+                        ("grape".to_owned(), DynamicType::Type("Grape".to_owned())),
+                        (
+                            "style".to_owned(),
+                            DynamicType::Option(Box::new(DynamicType::Enum(HashMap::from([
+                                ("Red".to_owned(), DynamicType::Dummy),
+                                ("White".to_owned(), DynamicType::Dummy),
+                            ])))),
+                        ),
+                        ("notes".to_owned(), DynamicType::String),
+                    ])),
+                ),
+                (
+                    "Batch".to_owned(),
+                    Fields::Object(HashMap::from([(
+                        "wines".to_owned(),
+                        DynamicType::Vec(Box::new(DynamicType::Type("Wine".to_owned()))),
+                    )])),
+                ),
+            ]),
+            properties: vec![Properties {
+                name: "House Wine".to_owned(),
+                type_name: "Wine".to_owned(),
+                content: json!({"name": "Casa", "grape": "Merlot", "style": "White", "notes": "Dry"}),
+                annotations: None,
+            }],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
+        }
+    }
+
+    #[test]
+    fn test_migrate_operations() {
+        let init_state = migration_state();
 
         let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
                 delete: None,
-                modify: None,
-                add: Some(vec![unit_process]),
+                add: None,
+                replace: None,
+                migrate: Some(vec![
+                    Migration::RenameField {
+                        type_name: "Wine".to_owned(),
+                        from: "name".to_owned(),
+                        to: "label".to_owned(),
+                    },
+                    Migration::AddField {
+                        type_name: "Wine".to_owned(),
+                        field: "vintage".to_owned(),
+                        field_type: DynamicType::u64,
+                        default: json!(2024),
+                    },
+                    Migration::DropField {
+                        type_name: "Wine".to_owned(),
+                        field: "notes".to_owned(),
+                    },
+                    Migration::WrapOption {
+                        type_name: "Wine".to_owned(),
+                        field: "vintage".to_owned(),
+                    },
+                    Migration::RenameVariant {
+                        type_name: "Grape".to_owned(),
+                        field: None,
+                        from: "Merlot".to_owned(),
+                        to: "MerlotNoir".to_owned(),
+                    },
+                    Migration::RenameVariant {
+                        type_name: "Wine".to_owned(),
+                        field: Some("style".to_owned()),
+                        from: "Red".to_owned(),
+                        to: "Tinto".to_owned(),
+                    },
+                ]),
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
-        let mut result = sdk::ContractResult::new(init_state.clone());
+        let mut result = sdk::ContractResult::new(init_state);
         contract_logic(&context, &mut result);
 
-        assert!(!result.error.is_empty());
-        assert!(!result.success);
+        assert!(result.success);
+        assert_eq!(
+            result.state.custom_types.get("Wine").unwrap().clone(),
+            Fields::Object(HashMap::from([
+                ("label".to_owned(), DynamicType::String),
+                ("grape".to_owned(), DynamicType::Type("Grape".to_owned())),
+                (
+                    "style".to_owned(),
+                    DynamicType::Option(Box::new(DynamicType::Enum(HashMap::from([
+                        ("Tinto".to_owned(), DynamicType::Dummy),
+                        ("White".to_owned(), DynamicType::Dummy),
+                    ])))),
+                ),
+                (
+                    "vintage".to_owned(),
+                    DynamicType::Option(Box::new(DynamicType::u64)),
+                ),
+            ]))
+        );
+        assert_eq!(
+            result.state.custom_types.get("Grape").unwrap().clone(),
+            Fields::Basic(Box::new(DynamicType::Enum(HashMap::from([
+                ("MerlotNoir".to_owned(), DynamicType::Dummy),
+                ("Syrah".to_owned(), DynamicType::Dummy),
+            ]))))
+        );
+        assert_eq!(
+            result.state.unit_process[0].outputs[0].content,
+            json!({"wines": [
+                {"label": "Reserva", "grape": "MerlotNoir", "style": "Tinto", "vintage": 2024},
+                {"label": "Joven", "grape": "Syrah", "style": null, "vintage": 2024}
+            ]})
+        );
+        assert_eq!(
+            result.state.properties[0].content,
+            json!({"label": "Casa", "grape": "MerlotNoir", "style": "White", "vintage": 2024})
+        );
     }
 
-    // TODO Poner más ejemplos como Vec(Enum)...
     #[test]
-    fn test_vec() {
-        ////////////////////////////////////////////////////////////////
-        // Type definition
-        ////////////////////////////////////////////////////////////////
-        let init_state = ProductionSystem {
-            name: "example".to_owned(),
-            version: 1,
-            unit_process: vec![],
-            custom_types: HashMap::new(),
-            properties: vec![],
-        };
-
-        let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
-                delete: None,
-                add: Some(vec![
-                    (
-                        "UserObject".to_owned(),
-                        Fields::Object(HashMap::from([(
-                            "value".to_owned(),
-                            DynamicType::Vec(Box::new(DynamicType::String)),
-                        )])),
+    fn test_migrate_defaults() {
+        let mut init_state = migration_state();
+        init_state.custom_types.insert(
+            "Cellar".to_owned(),
+            Fields::Object(HashMap::from([
+                (
+                    "house".to_owned(),
+                    DynamicType::Default(
+                        Box::new(DynamicType::Type("Wine".to_owned())),
+                        json!({"name": "Casa", "grape": "Merlot", "style": "Red", "notes": "Dry"}),
                     ),
-                    (
-                        "UserBasic".to_owned(),
-                        Fields::Basic(Box::new(DynamicType::Vec(Box::new(DynamicType::u64)))),
+                ),
+                (
+                    "grapes".to_owned(),
+                    DynamicType::Default(
+                        Box::new(DynamicType::Vec(Box::new(DynamicType::Type(
+                            "Grape".to_owned(),
+                        )))),
+                        json!(["Merlot", "Syrah"]),
                     ),
+                ),
+            ])),
+        );
+
+        let state = apply(
+            init_state,
+            Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: None,
+                add: None,
+                replace: None,
+                migrate: Some(vec![
+                    Migration::RenameField {
+                        type_name: "Wine".to_owned(),
+                        from: "name".to_owned(),
+                        to: "label".to_owned(),
+                    },
+                    Migration::AddField {
+                        type_name: "Wine".to_owned(),
+                        field: "vintage".to_owned(),
+                        field_type: DynamicType::u64,
+                        default: json!(2024),
+                    },
+                    Migration::DropField {
+                        type_name: "Wine".to_owned(),
+                        field: "notes".to_owned(),
+                    },
+                    Migration::RenameVariant {
+                        type_name: "Grape".to_owned(),
+                        field: None,
+                        from: "Merlot".to_owned(),
+                        to: "MerlotNoir".to_owned(),
+                    },
+                    Migration::RenameVariant {
+                        type_name: "Wine".to_owned(),
+                        field: Some("style".to_owned()),
+                        from: "Red".to_owned(),
+                        to: "Tinto".to_owned(),
+                    },
                 ]),
             }),
-            is_owner: false,
+        )
+        .unwrap();
+
+        let Some(Fields::Object(cellar)) = state.custom_types.get("Cellar") else {
+            panic!("Cellar is not an object type");
         };
+        assert_eq!(
+            cellar.get("house"),
+            Some(&DynamicType::Default(
+                Box::new(DynamicType::Type("Wine".to_owned())),
+                json!({"label": "Casa", "grape": "MerlotNoir", "style": "Tinto", "vintage": 2024}),
+            ))
+        );
+        assert_eq!(
+            cellar.get("grapes"),
+            Some(&DynamicType::Default(
+                Box::new(DynamicType::Vec(Box::new(DynamicType::Type(
+                    "Grape".to_owned()
+                )))),
+                json!(["MerlotNoir", "Syrah"]),
+            ))
+        );
+    }
 
-        let mut result = sdk::ContractResult::new(init_state.clone());
+    #[test]
+    fn test_migrate_operations_error() {
+        let init_state = migration_state();
+
+        let migrations = vec![
+            (
+                Migration::RenameField {
+                    type_name: "Wine".to_owned(),
+                    from: "year".to_owned(),
+                    to: "vintage".to_owned(),
+                },
+                ErrorKind::NotFound,
+            ),
+            (
+                Migration::RenameField {
+                    type_name: "Wine".to_owned(),
+                    from: "name".to_owned(),
+                    to: "notes".to_owned(),
+                },
+                ErrorKind::Duplicate,
+            ),
+            (
+                Migration::AddField {
+                    type_name: "Grape".to_owned(),
+                    field: "origin".to_owned(),
+                    field_type: DynamicType::String,
+                    default: json!(""),
+                },
+                ErrorKind::InvalidType,
+            ),
+            (
+                Migration::AddField {
+                    type_name: "Wine".to_owned(),
+                    field: "vintage".to_owned(),
+                    field_type: DynamicType::Type("Vintage".to_owned()),
+                    default: json!(2024),
+                },
+                ErrorKind::UnknownType,
+            ),
+            (
+                Migration::AddField {
+                    type_name: "Wine".to_owned(),
+                    field: "vintage".to_owned(),
+                    field_type: DynamicType::u64,
+                    default: json!("2024"),
+                },
+                ErrorKind::TypeMismatch,
+            ),
+            (
+                Migration::DropField {
+                    type_name: "Cask".to_owned(),
+                    field: "notes".to_owned(),
+                },
+                ErrorKind::NotFound,
+            ),
+            (
+                Migration::DropField {
+                    type_name: "Batch".to_owned(),
+                    field: "wines".to_owned(),
+                },
+                ErrorKind::InvalidType,
+            ),
+            (
+                Migration::WrapOption {
+                    type_name: "Wine".to_owned(),
+                    field: "style".to_owned(),
+                },
+                ErrorKind::InvalidType,
+            ),
+            (
+                Migration::RenameVariant {
+                    type_name: "Wine".to_owned(),
+                    field: None,
+                    from: "Red".to_owned(),
+                    to: "Tinto".to_owned(),
+                },
+                ErrorKind::InvalidType,
+            ),
+            (
+                Migration::RenameVariant {
+                    type_name: "Wine".to_owned(),
+                    field: Some("name".to_owned()),
+                    from: "Red".to_owned(),
+                    to: "Tinto".to_owned(),
+                },
+                ErrorKind::InvalidType,
+            ),
+            (
+                Migration::RenameVariant {
+                    type_name: "Grape".to_owned(),
+                    field: None,
+                    from: "Syrah".to_owned(),
+                    to: "Merlot".to_owned(),
+                },
+                ErrorKind::Duplicate,
+            ),
+        ];
+
+        for (migration, kind) in migrations {
+            let context = sdk::Context {
+                event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                    delete: None,
+                    add: None,
+                    replace: None,
+                    migrate: Some(vec![migration]),
+                }),
+                is_owner: true,
+            };
+
+            let mut result = sdk::ContractResult::new(init_state.clone());
+            contract_logic(&context, &mut result);
+
+            assert!(!result.success);
+            let error: Error = serde_json::from_str(&result.error).unwrap();
+            assert_eq!(error.kind, kind);
+            assert_eq!(
+                serde_json::to_vec(&result.state).unwrap(),
+                serde_json::to_vec(&init_state).unwrap()
+            );
+        }
+    }
+
+    /// Registers `content` in an input of the custom type `fields` and
+    /// returns the stored content.
+    fn register_value(fields: Fields, content: Value) -> Result<Value, Error> {
+        let mut init_state = rollback_state();
+        init_state.custom_types.insert("Checked".to_owned(), fields);
+        init_state.unit_process[0].inputs.push(Data {
+            name: "Checked".to_owned(),
+            type_name: "Checked".to_owned(),
+            content: Value::Null,
+            targets: None,
+            metadata: None,
+            annotations: None,
+            registered: false,
+        });
+
+        let context = sdk::Context {
+            event: Events::RegisterData(vec![UnitData {
+                name: "Pressing".to_owned(),
+                inputs: Some(vec![RegisterData {
+                    name: "Checked".to_owned(),
+                    type_name: "Checked".to_owned(),
+                    content: content.clone(),
+                    targets: None,
+                }]),
+                outputs: None,
+                properties: None,
+                validation: None,
+            }]),
+            is_owner: false,
+        };
 
+        let mut result = sdk::ContractResult::new(init_state);
         contract_logic(&context, &mut result);
 
-        assert!(result.error.is_empty());
+        if result.success {
+            Ok(result.state.unit_process[0].inputs[1].content.clone())
+        } else {
+            Err(serde_json::from_str(&result.error).unwrap())
+        }
+    }
+
+    fn number(kind: NumberKind) -> NumberConstraints {
+        NumberConstraints {
+            kind,
+            min: None,
+            max: None,
+            exclusive_min: None,
+            exclusive_max: None,
+            multiple_of: None,
+            unit: None,
+        }
+    }
+
+    #[test]
+    fn test_number_constraints() {
+        let temperature = Fields::Object(HashMap::from([(
+            "celsius".to_owned(),
+            DynamicType::Number(NumberConstraints {
+                min: Some(0.0),
+                max: Some(40.0),
+                ..number(NumberKind::f64)
+            }),
+        )]));
+
+        assert!(register_value(temperature.clone(), json!({"celsius": 18.5})).is_ok());
+        assert!(register_value(temperature.clone(), json!({"celsius": 40})).is_ok());
+
+        let error = register_value(temperature.clone(), json!({"celsius": 45.2})).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Constraint);
+        assert_eq!(error.path.as_deref(), Some("$.celsius"));
+        assert_eq!(error.expected.as_deref(), Some("<= 40"));
+        assert_eq!(error.received.as_deref(), Some("45.2"));
+
+        let error = register_value(temperature, json!({"celsius": "20"})).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+
+        let dose = Fields::Basic(Box::new(DynamicType::Number(NumberConstraints {
+            exclusive_min: Some(0.0),
+            exclusive_max: Some(100.0),
+            multiple_of: Some(5.0),
+            ..number(NumberKind::u64)
+        })));
+
+        assert!(register_value(dose.clone(), json!(95)).is_ok());
+        for (content, expected) in [
+            (json!(0), "> 0"),
+            (json!(100), "< 100"),
+            (json!(12), "multiple of 5"),
+        ] {
+            let error = register_value(dose.clone(), content).unwrap_err();
+            assert_eq!(error.kind, ErrorKind::Constraint);
+            assert_eq!(error.expected.as_deref(), Some(expected));
+        }
+
+        let error = register_value(dose, json!(-5)).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+
+        let acidity = Fields::Basic(Box::new(DynamicType::Number(NumberConstraints {
+            multiple_of: Some(0.01),
+            ..number(NumberKind::f64)
+        })));
+
+        assert!(register_value(acidity.clone(), json!(3.45)).is_ok());
+        let error = register_value(acidity, json!(3.456)).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Constraint);
+    }
+
+    #[test]
+    fn test_number_constraints_definition() {
+        let init_state = rollback_state();
+
+        for constraints in [
+            NumberConstraints {
+                min: Some(40.0),
+                max: Some(0.0),
+                ..number(NumberKind::f64)
+            },
+            NumberConstraints {
+                min: Some(10.0),
+                exclusive_max: Some(10.0),
+                ..number(NumberKind::i64)
+            },
+            NumberConstraints {
+                multiple_of: Some(0.0),
+                ..number(NumberKind::u64)
+            },
+        ] {
+            let context = sdk::Context {
+                event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                    delete: None,
+                    add: Some(vec![(
+                        "Temperature".to_owned(),
+                        Fields::Basic(Box::new(DynamicType::Number(constraints))),
+                    )]),
+                    replace: None,
+                    migrate: None,
+                }),
+                is_owner: true,
+            };
+
+            let mut result = sdk::ContractResult::new(init_state.clone());
+            contract_logic(&context, &mut result);
+
+            assert!(!result.success);
+            let error: Error = serde_json::from_str(&result.error).unwrap();
+            assert_eq!(error.kind, ErrorKind::InvalidType);
+            assert_eq!(error.element.as_deref(), Some("Temperature"));
+        }
+
+        let json = json!({"Number": {"kind": "f64", "min": 0, "max": 40}});
+        let dynamic_type: DynamicType = serde_json::from_value(json).unwrap();
         assert_eq!(
-            result.state.custom_types.get("UserObject").unwrap().clone(),
-            Fields::Object(HashMap::from([(
-                "value".to_owned(),
-                DynamicType::Vec(Box::new(DynamicType::String))
-            )]))
+            dynamic_type,
+            DynamicType::Number(NumberConstraints {
+                min: Some(0.0),
+                max: Some(40.0),
+                ..number(NumberKind::f64)
+            })
         );
+    }
+
+    fn text(format: Option<TextFormat>) -> TextConstraints {
+        TextConstraints {
+            min_length: None,
+            max_length: None,
+            pattern: None,
+            format,
+        }
+    }
+
+    #[test]
+    fn test_text_constraints() {
+        let lot = Fields::Basic(Box::new(DynamicType::Text(TextConstraints {
+            min_length: Some(4),
+            max_length: Some(8),
+            pattern: Some("L[0-9]+".to_owned()),
+            ..text(None)
+        })));
+
+        assert!(register_value(lot.clone(), json!("L2024")).is_ok());
+        for (content, expected) in [
+            (json!("L1"), "length >= 4"),
+            (json!("L202409301"), "length <= 8"),
+            (json!("XL2024"), "pattern ^(?:L[0-9]+)$"),
+        ] {
+            let error = register_value(lot.clone(), content).unwrap_err();
+            assert_eq!(error.kind, ErrorKind::Constraint);
+            assert_eq!(error.expected.as_deref(), Some(expected));
+        }
+
+        let error = register_value(lot, json!(2024)).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+
+        // Lengths count characters, not bytes.
+        let name = Fields::Basic(Box::new(DynamicType::Text(TextConstraints {
+            max_length: Some(5),
+            ..text(None)
+        })));
+        assert!(register_value(name, json!("Añejo")).is_ok());
+    }
+
+    #[test]
+    fn test_text_formats() {
+        let cases = [
+            (TextFormat::Date, "2024-02-29", true),
+            (TextFormat::Date, "2023-02-29", false),
+            (TextFormat::Date, "2024-13-01", false),
+            (TextFormat::Date, "30/09/2024", false),
+            (TextFormat::Time, "14:05:00", true),
+            (TextFormat::Time, "14:05:00.250+02:00", true),
+            (TextFormat::Time, "24:00:00", false),
+            (TextFormat::DateTime, "2024-09-30T14:05:00Z", true),
+            (TextFormat::DateTime, "2024-09-30T14:05:00-05:00", true),
+            (TextFormat::DateTime, "2024-09-30T14:05:00", false),
+            (TextFormat::DateTime, "2024-09-30", false),
+            (
+                TextFormat::Uuid,
+                "123e4567-e89b-12d3-a456-426614174000",
+                true,
+            ),
+            (TextFormat::Uuid, "123e4567e89b12d3a456426614174000", false),
+            (TextFormat::Email, "cellar@bodega.example.com", true),
+            (TextFormat::Email, "cellar@bodega", false),
+            (TextFormat::Email, "cellar bodega@example.com", false),
+            (TextFormat::Gtin, "4006381333931", true),
+            (TextFormat::Gtin, "96385074", true),
+            (TextFormat::Gtin, "4006381333932", false),
+            (TextFormat::Gtin, "400638133393", false),
+            (TextFormat::Lot, "LOT-2024/09", true),
+            (TextFormat::Lot, "LOT 2024", false),
+            (TextFormat::Lot, "ABCDEFGHIJKLMNOPQRSTU", false),
+        ];
+
+        for (format, content, valid) in cases {
+            let fields = Fields::Basic(Box::new(DynamicType::Text(text(Some(format)))));
+            let result = register_value(fields, json!(content));
+            assert_eq!(result.is_ok(), valid, "{} {}", format, content);
+
+            if let Err(error) = result {
+                assert_eq!(error.kind, ErrorKind::Constraint);
+                assert_eq!(error.expected, Some(format!("format {}", format)));
+                assert_eq!(error.received.as_deref(), Some(content));
+            }
+        }
+    }
+
+    #[test]
+    fn test_text_constraints_definition() {
+        let init_state = rollback_state();
+
+        for constraints in [
+            TextConstraints {
+                min_length: Some(8),
+                max_length: Some(4),
+                ..text(None)
+            },
+            TextConstraints {
+                pattern: Some("L[0-9+".to_owned()),
+                ..text(None)
+            },
+        ] {
+            let context = sdk::Context {
+                event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                    delete: None,
+                    add: Some(vec![(
+                        "Lot".to_owned(),
+                        Fields::Object(HashMap::from([(
+                            "code".to_owned(),
+                            DynamicType::Text(constraints),
+                        )])),
+                    )]),
+                    replace: None,
+                    migrate: None,
+                }),
+                is_owner: true,
+            };
+
+            let mut result = sdk::ContractResult::new(init_state.clone());
+            contract_logic(&context, &mut result);
+
+            assert!(!result.success);
+            let error: Error = serde_json::from_str(&result.error).unwrap();
+            assert_eq!(error.kind, ErrorKind::InvalidType);
+            assert_eq!(error.element.as_deref(), Some("Lot"));
+        }
+
+        let json = json!({"Text": {"format": "Date"}});
+        let dynamic_type: DynamicType = serde_json::from_value(json).unwrap();
         assert_eq!(
-            result.state.custom_types.get("UserBasic").unwrap().clone(),
-            Fields::Basic(Box::new(DynamicType::Vec(Box::new(DynamicType::u64)))),
+            dynamic_type,
+            DynamicType::Text(text(Some(TextFormat::Date)))
         );
-        assert_eq!(result.state.custom_types.len(), 2);
+    }
 
-        ////////////////////////////////////////////////////////////////
-        // Unit process
-        ////////////////////////////////////////////////////////////////
-        let unit_process = UnitProcess {
-            name: "Unit example".to_owned(),
-            outputs: vec![Data {
-                name: "Example Object".to_owned(),
-                type_name: "UserObject".to_owned(),
-                content: json!({"value": ["one", "two"]}),
-                targets: None,
-                metadata: None,
-            }],
-            inputs: vec![Data {
-                name: "Example Basic".to_owned(),
-                type_name: "UserBasic".to_owned(),
-                content: json!([0, 1, 2, 3, 4, 5]),
-                targets: None,
-                metadata: None,
-            }],
-            properties: vec![],
-        };
+    #[test]
+    fn test_temporal_types() {
+        let fermentation = Fields::Object(HashMap::from([
+            ("harvest".to_owned(), DynamicType::Date),
+            ("start".to_owned(), DynamicType::Timestamp),
+            ("length".to_owned(), DynamicType::Duration),
+        ]));
+
+        assert!(
+            register_value(
+                fermentation.clone(),
+                json!({"harvest": "2024-09-12", "start": "2024-09-13T08:30:00.5+02:00", "length": "P12DT6H"})
+            )
+            .is_ok()
+        );
+
+        for (content, path, expected) in [
+            (
+                json!({"harvest": "2024-09-31", "start": "2024-09-13T08:30:00Z", "length": "P12D"}),
+                "$.harvest",
+                "Date",
+            ),
+            (
+                json!({"harvest": "2024-09-12", "start": "2024-09-13T08:30:00", "length": "P12D"}),
+                "$.start",
+                "Timestamp",
+            ),
+            (
+                json!({"harvest": "2024-09-12", "start": 1726209000, "length": "P12D"}),
+                "$.start",
+                "Timestamp",
+            ),
+            (
+                json!({"harvest": "2024-09-12", "start": "2024-09-13T08:30:00Z", "length": "P1M"}),
+                "$.length",
+                "Duration",
+            ),
+            (
+                json!({"harvest": "2024-09-12", "start": "2024-09-13T08:30:00Z", "length": "P12DT"}),
+                "$.length",
+                "Duration",
+            ),
+        ] {
+            let error = register_value(fermentation.clone(), content).unwrap_err();
+            assert_eq!(error.kind, ErrorKind::TypeMismatch);
+            assert_eq!(error.path.as_deref(), Some(path));
+            assert_eq!(error.expected.as_deref(), Some(expected));
+        }
+
+        // Built-in names can be used as the type of an element.
+        let mut init_state = rollback_state();
+        init_state.properties.push(Properties {
+            name: "Bottling".to_owned(),
+            type_name: "Timestamp".to_owned(),
+            content: json!("2024-10-01T10:00:00Z"),
+            annotations: None,
+        });
 
         let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
-                delete: None,
-                modify: None,
-                add: Some(vec![unit_process]),
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyProductionSystem {
+                name: None,
+                delete_properties: None,
+                modify_properties: Some(vec![(
+                    "Bottling".to_owned(),
+                    Properties {
+                        name: "Bottling".to_owned(),
+                        type_name: "Timestamp".to_owned(),
+                        content: json!("2024-10-01"),
+                        annotations: None,
+                    },
+                )]),
+                add_properties: None,
+                validation: None,
+                permissions: None,
+                subject_id: None,
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
-        let mut result = sdk::ContractResult::new(result.state);
+        let mut result = sdk::ContractResult::new(init_state);
         contract_logic(&context, &mut result);
 
-        println!("{}", result.error);
-        assert!(result.error.is_empty());
-
-        assert_eq!(result.state.unit_process.len(), 1);
-        assert_eq!(result.state.unit_process[0].name, "Unit example");
-        assert_eq!(result.state.unit_process[0].outputs.len(), 1);
-
-        let data = result.state.unit_process[0].outputs[0].clone();
-        assert_eq!(data.name, "Example Object");
-        assert_eq!(data.type_name, "UserObject");
-        assert_eq!(data.content, json!({"value": ["one", "two"]}));
-
-        let data = result.state.unit_process[0].inputs[0].clone();
-        assert_eq!(data.name, "Example Basic");
-        assert_eq!(data.type_name, "UserBasic");
-        assert_eq!(data.content, json!([0, 1, 2, 3, 4, 5]));
+        assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+        assert_eq!(error.element.as_deref(), Some("Bottling"));
+    }
 
-        assert!(result.success);
+    #[test]
+    fn test_temporal_ordering() {
+        use std::cmp::Ordering;
+
+        let cases = [
+            (
+                DynamicType::Timestamp,
+                json!("2024-09-30T14:00:00+02:00"),
+                json!("2024-09-30T12:00:00Z"),
+                Some(Ordering::Equal),
+            ),
+            (
+                DynamicType::Timestamp,
+                json!("2024-09-30T23:30:00-01:00"),
+                json!("2024-10-01T00:15:00Z"),
+                Some(Ordering::Greater),
+            ),
+            (
+                DynamicType::Timestamp,
+                json!("2024-09-30T12:00:00.25Z"),
+                json!("2024-09-30T12:00:00.3Z"),
+                Some(Ordering::Less),
+            ),
+            (
+                DynamicType::Date,
+                json!("1999-12-31"),
+                json!("2000-01-01"),
+                Some(Ordering::Less),
+            ),
+            (
+                DynamicType::Duration,
+                json!("P1W"),
+                json!("P6DT23H59M"),
+                Some(Ordering::Greater),
+            ),
+            (
+                DynamicType::Duration,
+                json!("PT90M"),
+                json!("PT1H30M"),
+                Some(Ordering::Equal),
+            ),
+            (
+                DynamicType::u64,
+                json!(10),
+                json!(9),
+                Some(Ordering::Greater),
+            ),
+            (
+                DynamicType::Date,
+                json!("2024-02-30"),
+                json!("2024-03-01"),
+                None,
+            ),
+            (DynamicType::String, json!("a"), json!("b"), None),
+        ];
+
+        for (dynamic_type, a, b, ordering) in cases {
+            assert_eq!(dynamic_type.compare(&a, &b), ordering, "{} {}", a, b);
+        }
     }
 
     #[test]
-    fn test_vec_fail() {
-        ////////////////////////////////////////////////////////////////
-        // Type definition
-        ////////////////////////////////////////////////////////////////
-        let init_state = ProductionSystem {
-            name: "example".to_owned(),
-            version: 1,
-            unit_process: vec![],
-            custom_types: HashMap::new(),
-            properties: vec![],
-        };
+    fn test_reserved_temporal_names() {
+        let init_state = rollback_state();
 
         let context = sdk::Context {
             event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
                 delete: None,
                 add: Some(vec![(
-                    "UserObject".to_owned(),
-                    Fields::Object(HashMap::from([(
-                        "value".to_owned(),
-                        DynamicType::Vec(Box::new(DynamicType::Dummy)),
-                    )])),
+                    "Timestamp".to_owned(),
+                    Fields::Basic(Box::new(DynamicType::String)),
                 )]),
+                replace: None,
+                migrate: None,
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
-        let mut result = sdk::ContractResult::new(init_state.clone());
-
+        let mut result = sdk::ContractResult::new(init_state);
         contract_logic(&context, &mut result);
 
-        assert!(!result.error.is_empty());
         assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::ReservedName);
+        assert_eq!(error.element.as_deref(), Some("Timestamp"));
+    }
+
+    #[test]
+    fn test_units_of_measure() {
+        let tank = Fields::Object(HashMap::from([
+            (
+                "volume".to_owned(),
+                DynamicType::Number(NumberConstraints {
+                    max: Some(1000.0),
+                    unit: Some("L".to_owned()),
+                    ..number(NumberKind::f64)
+                }),
+            ),
+            (
+                "temperature".to_owned(),
+                DynamicType::Number(NumberConstraints {
+                    min: Some(0.0),
+                    max: Some(40.0),
+                    unit: Some("°C".to_owned()),
+                    ..number(NumberKind::f64)
+                }),
+            ),
+            (
+                "grapes".to_owned(),
+                DynamicType::Number(NumberConstraints {
+                    unit: Some("kg".to_owned()),
+                    ..number(NumberKind::u64)
+                }),
+            ),
+        ]));
+
+        let content = register_value(
+            tank.clone(),
+            json!({
+                "volume": {"value": 5, "unit": "hL"},
+                "temperature": {"value": 68, "unit": "°F"},
+                "grapes": {"value": 1.2, "unit": "t"}
+            }),
+        )
+        .unwrap();
+        assert_eq!(content["volume"], json!(500.0));
+        assert!((content["temperature"].as_f64().unwrap() - 20.0).abs() < 1e-9);
+        assert_eq!(content["grapes"], json!(1200));
+
+        // Values in the unit of the type are stored as they are.
+        let content = json!({"volume": 750, "temperature": 18.5, "grapes": 900});
+        assert_eq!(
+            register_value(tank.clone(), content.clone()).unwrap(),
+            content
+        );
+
+        for (volume, kind) in [
+            (json!({"value": 5, "unit": "kg"}), ErrorKind::UnitMismatch),
+            (json!({"value": 5, "unit": "bbl"}), ErrorKind::UnitMismatch),
+            (json!({"value": 20, "unit": "hL"}), ErrorKind::Constraint),
+            (json!({"value": "5", "unit": "L"}), ErrorKind::TypeMismatch),
+            (
+                json!({"value": 5, "unit": "L", "note": ""}),
+                ErrorKind::TypeMismatch,
+            ),
+        ] {
+            let error = register_value(
+                tank.clone(),
+                json!({"volume": volume, "temperature": 18.5, "grapes": 900}),
+            )
+            .unwrap_err();
+            assert_eq!(error.kind, kind);
+            assert_eq!(error.path.as_deref(), Some("$.volume"));
+        }
+
+        let error = register_value(
+            tank,
+            json!({"volume": 750, "temperature": 18.5, "grapes": {"value": 1, "unit": "lb"}}),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+        assert_eq!(error.path.as_deref(), Some("$.grapes"));
 
         let context = sdk::Context {
             event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
                 delete: None,
                 add: Some(vec![(
-                    "UserBasic".to_owned(),
-                    Fields::Basic(Box::new(DynamicType::Vec(Box::new(DynamicType::Dummy)))),
+                    "Strength".to_owned(),
+                    Fields::Basic(Box::new(DynamicType::Number(NumberConstraints {
+                        unit: Some("proof".to_owned()),
+                        ..number(NumberKind::f64)
+                    }))),
                 )]),
+                replace: None,
+                migrate: None,
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
-        let mut result = sdk::ContractResult::new(init_state.clone());
-
+        let mut result = sdk::ContractResult::new(rollback_state());
         contract_logic(&context, &mut result);
 
-        assert!(!result.error.is_empty());
         assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::InvalidType);
+        assert_eq!(error.path.as_deref(), Some("$.unit"));
+    }
 
-        ////////////////////////////////////////////////////////////////
-        ////////////////////////////////////////////////////////////////
+    #[test]
+    fn test_decimal() {
+        let lot = Fields::Object(HashMap::from([
+            ("weight".to_owned(), DynamicType::Decimal { scale: 3 }),
+            ("cost".to_owned(), DynamicType::Decimal { scale: 2 }),
+            ("boxes".to_owned(), DynamicType::Decimal { scale: 0 }),
+        ]));
+
+        let content = register_value(
+            lot.clone(),
+            json!({"weight": 1250.5, "cost": "-0019.9", "boxes": 1.2e3}),
+        )
+        .unwrap();
+        assert_eq!(
+            content,
+            json!({"weight": "1250.500", "cost": "-19.90", "boxes": "1200"})
+        );
+
+        let content = register_value(
+            lot.clone(),
+            json!({"weight": "0.125", "cost": 7, "boxes": "3.000"}),
+        )
+        .unwrap();
+        assert_eq!(
+            content,
+            json!({"weight": "0.125", "cost": "7.00", "boxes": "3"})
+        );
+
+        // Strings and integers keep every digit.
+        let content = register_value(
+            lot.clone(),
+            json!({"weight": "1", "cost": "12345678901234567.89", "boxes": 12345678901234567_u64}),
+        )
+        .unwrap();
+        assert_eq!(
+            content,
+            json!({"weight": "1.000", "cost": "12345678901234567.89", "boxes": "12345678901234567"})
+        );
 
+        for (cost, kind) in [
+            (json!("19.999"), ErrorKind::Constraint),
+            (json!(0.001), ErrorKind::Constraint),
+            (json!("19,90"), ErrorKind::TypeMismatch),
+            (json!("1.5.0"), ErrorKind::TypeMismatch),
+            (json!(true), ErrorKind::TypeMismatch),
+            // 12345678901234567.89 is 12345678901234568 as an f64.
+            (json!(12345678901234567.89), ErrorKind::Constraint),
+        ] {
+            let error = register_value(
+                lot.clone(),
+                json!({"weight": "1", "cost": cost, "boxes": "1"}),
+            )
+            .unwrap_err();
+            assert_eq!(error.kind, kind);
+            assert_eq!(error.path.as_deref(), Some("$.cost"));
+        }
+
+        // Decimals can be used by the properties of the production system.
         let init_state = ProductionSystem {
-            name: "example".to_owned(),
-            version: 1,
+            name: "".to_owned(),
+            version: 0,
             unit_process: vec![],
             custom_types: HashMap::new(),
             properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
         };
 
-        let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
-                delete: None,
-                add: Some(vec![(
-                    "UserObject".to_owned(),
-                    Fields::Object(HashMap::from([(
-                        "value".to_owned(),
-                        DynamicType::Vec(Box::new(DynamicType::Option(Box::new(
-                            DynamicType::String,
-                        )))),
-                    )])),
+        let init = |content: Value| {
+            Events::ChangeProductionSystem(ChangeProductionSystem::Init {
+                name: "wine process".to_owned(),
+                unit_process: None,
+                properties: Some(vec![Properties {
+                    name: "Bottle price".to_owned(),
+                    type_name: "Price".to_owned(),
+                    content,
+                    annotations: None,
+                }]),
+                types: Some(vec![(
+                    "Price".to_owned(),
+                    Fields::Basic(Box::new(DynamicType::Decimal { scale: 2 })),
                 )]),
-            }),
-            is_owner: false,
+                validation: None,
+                permissions: None,
+                links: None,
+                subject_id: None,
+            })
         };
 
-        let mut result = sdk::ContractResult::new(init_state.clone());
-
-        contract_logic(&context, &mut result);
-
-        assert!(!result.error.is_empty());
-        assert!(!result.success);
-
         let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
-                delete: None,
-                add: Some(vec![(
-                    "UserBasic".to_owned(),
-                    Fields::Basic(Box::new(DynamicType::Vec(Box::new(DynamicType::Option(
-                        Box::new(DynamicType::String),
-                    ))))),
-                )]),
-            }),
-            is_owner: false,
+            event: init(json!("12.50")),
+            is_owner: true,
         };
 
         let mut result = sdk::ContractResult::new(init_state.clone());
-
         contract_logic(&context, &mut result);
+        assert!(result.success);
 
-        assert!(!result.error.is_empty());
-        assert!(!result.success);
+        assert_rollback(&init_state, init(json!("12.505")));
 
-        ////////////////////////////////////////////////////////////////
-        // Unit process
-        ////////////////////////////////////////////////////////////////
-        let unit_process = UnitProcess {
-            name: "Unit example".to_owned(),
-            outputs: vec![Data {
-                name: "Example Dummy".to_owned(),
-                type_name: "Vec".to_owned(),
-                content: json!([]),
-                targets: None,
-                metadata: None,
-            }],
-            inputs: vec![],
-            properties: vec![],
-        };
+        // Values are padded to the scale.
+        let price = |scale: u32| Fields::Basic(Box::new(DynamicType::Decimal { scale }));
+        assert!(add_type("Price", price(38)).is_none());
+        let error = add_type("Price", price(u32::MAX)).unwrap();
+        assert_eq!(error.kind, ErrorKind::InvalidType);
+        assert_eq!(error.expected.as_deref(), Some("scale <= 38"));
+        assert_eq!(error.received.as_deref(), Some("4294967295"));
+    }
 
+    /// Adds the custom type `name` to the rollback state and returns the
+    /// error of the event, if any.
+    fn add_type(name: &str, fields: Fields) -> Option<Error> {
         let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
                 delete: None,
-                modify: None,
-                add: Some(vec![unit_process]),
+                add: Some(vec![(name.to_owned(), fields)]),
+                replace: None,
+                migrate: None,
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
-        let mut result = sdk::ContractResult::new(init_state.clone());
+        let mut result = sdk::ContractResult::new(rollback_state());
         contract_logic(&context, &mut result);
 
-        assert!(!result.error.is_empty());
-        assert!(!result.success);
+        if result.success {
+            None
+        } else {
+            Some(serde_json::from_str(&result.error).unwrap())
+        }
     }
 
     #[test]
-    fn test_type() {
-        ////////////////////////////////////////////////////////////////
-        // Type definition
-        ////////////////////////////////////////////////////////////////
-        let init_state = ProductionSystem {
-            name: "example".to_owned(),
-            version: 1,
-            unit_process: vec![],
-            custom_types: HashMap::new(),
-            properties: vec![],
+    fn test_map() {
+        let readings = Fields::Basic(Box::new(DynamicType::Map(
+            Box::new(DynamicType::String),
+            Box::new(DynamicType::Number(NumberConstraints {
+                min: Some(-10.0),
+                max: Some(50.0),
+                ..number(NumberKind::f64)
+            })),
+        )));
+
+        assert!(register_value(readings.clone(), json!({"probe-1": 18.5, "probe-2": 19})).is_ok());
+        assert!(register_value(readings.clone(), json!({})).is_ok());
+
+        let error =
+            register_value(readings.clone(), json!({"probe-1": 18.5, "probe-2": 60})).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Constraint);
+        assert_eq!(error.path.as_deref(), Some("$[\"probe-2\"]"));
+
+        let error = register_value(readings, json!([18.5, 19])).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+        assert_eq!(error.expected.as_deref(), Some("Map"));
+
+        let barrels = Fields::Basic(Box::new(DynamicType::Map(
+            Box::new(DynamicType::u64),
+            Box::new(DynamicType::String),
+        )));
+        assert!(register_value(barrels.clone(), json!({"12": "Oak", "13": "Steel"})).is_ok());
+
+        let error = register_value(barrels, json!({"12": "Oak", "B13": "Steel"})).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+        assert_eq!(error.path.as_deref(), Some("$.B13"));
+        assert!(error.message.contains("Invalid map key 'B13'"));
+
+        let styles = Fields::Basic(Box::new(DynamicType::Map(
+            Box::new(DynamicType::Enum(HashMap::from([
+                ("Red".to_owned(), DynamicType::Dummy),
+                ("White".to_owned(), DynamicType::Dummy),
+            ]))),
+            Box::new(DynamicType::u64),
+        )));
+        assert!(register_value(styles.clone(), json!({"Red": 120, "White": 80})).is_ok());
+
+        let error = register_value(styles, json!({"Rose": 10})).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::UnknownVariant);
+    }
+
+    #[test]
+    fn test_map_definition() {
+        let map = |key: DynamicType, value: DynamicType| {
+            Fields::Basic(Box::new(DynamicType::Map(Box::new(key), Box::new(value))))
         };
 
-        let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
-                delete: None,
-                add: Some(vec![
-                    (
-                        "DataType".to_owned(),
-                        Fields::Object(HashMap::from([
-                            ("text".to_owned(), DynamicType::String),
-                            ("value".to_owned(), DynamicType::u64),
-                        ])),
-                    ),
-                    (
-                        "VecType".to_owned(),
-                        Fields::Basic(Box::new(DynamicType::Vec(Box::new(DynamicType::String)))),
-                    ),
-                    (
-                        "UserObject".to_owned(),
-                        Fields::Object(HashMap::from([(
-                            "value".to_owned(),
-                            DynamicType::Type("DataType".to_owned()),
-                        )])),
-                    ),
-                    (
-                        "UserVec".to_owned(),
-                        Fields::Object(HashMap::from([(
-                            "value".to_owned(),
-                            DynamicType::Type("VecType".to_owned()),
-                        )])),
+        assert!(add_type("Readings", map(DynamicType::Date, DynamicType::f64)).is_none());
+
+        for fields in [
+            map(DynamicType::f64, DynamicType::String),
+            map(
+                DynamicType::Type("UserBasic".to_owned()),
+                DynamicType::String,
+            ),
+            map(
+                DynamicType::Enum(HashMap::from([("Red".to_owned(), DynamicType::u64)])),
+                DynamicType::String,
+            ),
+            map(
+                DynamicType::String,
+                DynamicType::Option(Box::new(DynamicType::String)),
+            ),
+            map(DynamicType::String, DynamicType::Dummy),
+        ] {
+            let error = add_type("Readings", fields).unwrap();
+            assert_eq!(error.kind, ErrorKind::InvalidType);
+            assert_eq!(error.element.as_deref(), Some("Readings"));
+        }
+
+        let error = add_type(
+            "Readings",
+            map(DynamicType::String, DynamicType::Type("Unknown".to_owned())),
+        )
+        .unwrap();
+        assert_eq!(error.kind, ErrorKind::UnknownType);
+
+        assert!(
+            add_type(
+                "Assembly",
+                Fields::Object(HashMap::from([(
+                    "parts".to_owned(),
+                    DynamicType::Map(
+                        Box::new(DynamicType::String),
+                        Box::new(DynamicType::Type("Assembly".to_owned())),
                     ),
-                ]),
+                )])),
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_tuple_and_array() {
+        let position = Fields::Basic(Box::new(DynamicType::Tuple(vec![
+            DynamicType::Number(NumberConstraints {
+                min: Some(-90.0),
+                max: Some(90.0),
+                ..number(NumberKind::f64)
             }),
-            is_owner: false,
-        };
+            DynamicType::Number(NumberConstraints {
+                min: Some(-180.0),
+                max: Some(180.0),
+                ..number(NumberKind::f64)
+            }),
+        ])));
 
-        let mut result = sdk::ContractResult::new(init_state.clone());
+        assert!(register_value(position.clone(), json!([42.46, -2.44])).is_ok());
 
-        contract_logic(&context, &mut result);
+        let error = register_value(position.clone(), json!([42.46, -2.44, 450.0])).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Constraint);
+        assert_eq!(error.expected.as_deref(), Some("length 2"));
+        assert_eq!(error.received.as_deref(), Some("3"));
 
-        assert!(result.error.is_empty());
-        assert_eq!(
-            result.state.custom_types.get("VecType").unwrap().clone(),
-            Fields::Basic(Box::new(DynamicType::Vec(Box::new(DynamicType::String)))),
+        let error = register_value(position.clone(), json!([42.46, -200])).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Constraint);
+        assert_eq!(error.path.as_deref(), Some("$[1]"));
+
+        let error = register_value(position, json!({"lat": 42.46, "lon": -2.44})).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+        assert_eq!(error.expected.as_deref(), Some("Tuple"));
+
+        let colour = Fields::Basic(Box::new(DynamicType::Array(
+            3,
+            Box::new(DynamicType::Number(NumberConstraints {
+                max: Some(255.0),
+                ..number(NumberKind::u64)
+            })),
+        )));
+
+        assert!(register_value(colour.clone(), json!([128, 0, 32])).is_ok());
+
+        let error = register_value(colour.clone(), json!([128, 0])).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Constraint);
+        assert_eq!(error.expected.as_deref(), Some("length 3"));
+        assert_eq!(error.received.as_deref(), Some("2"));
+
+        let error = register_value(colour, json!([128, 300, 32])).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Constraint);
+        assert_eq!(error.path.as_deref(), Some("$[1]"));
+    }
+
+    #[test]
+    fn test_tuple_and_array_definition() {
+        assert!(
+            add_type(
+                "Colour",
+                Fields::Basic(Box::new(DynamicType::Array(3, Box::new(DynamicType::u64))))
+            )
+            .is_none()
         );
-        assert_eq!(
-            result.state.custom_types.get("UserVec").unwrap().clone(),
+
+        for fields in [
+            Fields::Basic(Box::new(DynamicType::Tuple(vec![]))),
+            Fields::Basic(Box::new(DynamicType::Tuple(vec![
+                DynamicType::f64,
+                DynamicType::Dummy,
+            ]))),
+            Fields::Basic(Box::new(DynamicType::Array(0, Box::new(DynamicType::u64)))),
+            Fields::Basic(Box::new(DynamicType::Array(
+                2,
+                Box::new(DynamicType::Option(Box::new(DynamicType::u64))),
+            ))),
+        ] {
+            let error = add_type("Colour", fields).unwrap();
+            assert_eq!(error.kind, ErrorKind::InvalidType);
+            assert_eq!(error.element.as_deref(), Some("Colour"));
+        }
+
+        let error = add_type(
+            "Colour",
+            Fields::Basic(Box::new(DynamicType::Tuple(vec![
+                DynamicType::u64,
+                DynamicType::Type("Unknown".to_owned()),
+            ]))),
+        )
+        .unwrap();
+        assert_eq!(error.kind, ErrorKind::UnknownType);
+        assert_eq!(error.path.as_deref(), Some("$[1]"));
+
+        let error = add_type(
+            "Segment",
             Fields::Object(HashMap::from([(
-                "value".to_owned(),
-                DynamicType::Type("VecType".to_owned()),
+                "ends".to_owned(),
+                DynamicType::Array(2, Box::new(DynamicType::Type("Segment".to_owned()))),
             )])),
-        );
-        assert_eq!(
-            result.state.custom_types.get("UserObject").unwrap().clone(),
+        )
+        .unwrap();
+        assert_eq!(error.kind, ErrorKind::CircularDependency);
+    }
+
+    #[test]
+    fn test_recursive_types() {
+        let part = Fields::Object(HashMap::from([
+            ("name".to_owned(), DynamicType::String),
+            (
+                "parts".to_owned(),
+                DynamicType::Vec(Box::new(DynamicType::Type("Checked".to_owned()))),
+            ),
+            (
+                "spare".to_owned(),
+                DynamicType::Option(Box::new(DynamicType::Type("Checked".to_owned()))),
+            ),
+        ]));
+
+        let content = json!({
+            "name": "Press",
+            "parts": [
+                {"name": "Drum", "parts": [{"name": "Membrane", "parts": []}]},
+                {"name": "Motor", "parts": [], "spare": {"name": "Motor", "parts": []}}
+            ]
+        });
+        assert_eq!(register_value(part.clone(), content.clone()), Ok(content));
+
+        let error = register_value(
+            part.clone(),
+            json!({"name": "Press", "parts": [{"name": "Drum", "parts": [{"parts": []}]}]}),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::MissingField);
+        assert_eq!(error.path.as_deref(), Some("$.parts[0].parts[0].name"));
+
+        let mut content = json!({"name": "Bolt", "parts": []});
+        for _ in 0..MAX_DEPTH {
+            content = json!({"name": "Bolt", "parts": [], "spare": content});
+        }
+        assert!(register_value(part.clone(), content.clone()).is_ok());
+
+        let content = json!({"name": "Bolt", "parts": [], "spare": content});
+        let error = register_value(part, content).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::TooDeep);
+    }
+
+    #[test]
+    fn test_recursive_types_definition() {
+        for c_type in [
+            DynamicType::Option(Box::new(DynamicType::Type("Assembly".to_owned()))),
+            DynamicType::Vec(Box::new(DynamicType::Type("Assembly".to_owned()))),
+        ] {
+            let assembly = Fields::Object(HashMap::from([
+                ("name".to_owned(), DynamicType::String),
+                ("parts".to_owned(), c_type),
+            ]));
+            assert!(add_type("Assembly", assembly).is_none());
+        }
+
+        let error = add_type(
+            "Assembly",
+            Fields::Object(HashMap::from([
+                ("name".to_owned(), DynamicType::String),
+                (
+                    "parent".to_owned(),
+                    DynamicType::Type("Assembly".to_owned()),
+                ),
+            ])),
+        )
+        .unwrap();
+        assert_eq!(error.kind, ErrorKind::CircularDependency);
+        assert_eq!(error.element.as_deref(), Some("Assembly"));
+
+        let error = add_type(
+            "Assembly",
             Fields::Object(HashMap::from([(
-                "value".to_owned(),
-                DynamicType::Type("DataType".to_owned())
-            )]))
+                "kind".to_owned(),
+                DynamicType::Enum(HashMap::from([
+                    ("Part".to_owned(), DynamicType::Dummy),
+                    (
+                        "Group".to_owned(),
+                        DynamicType::Tuple(vec![DynamicType::Type("Assembly".to_owned())]),
+                    ),
+                ])),
+            )])),
+        )
+        .unwrap();
+        assert_eq!(error.kind, ErrorKind::CircularDependency);
+    }
+
+    #[test]
+    fn test_default_values() {
+        let tank = Fields::Object(HashMap::from([
+            ("variety".to_owned(), DynamicType::String),
+            (
+                "volume".to_owned(),
+                DynamicType::Default(
+                    Box::new(DynamicType::Number(NumberConstraints {
+                        unit: Some("L".to_owned()),
+                        ..number(NumberKind::f64)
+                    })),
+                    json!({"value": 5, "unit": "hL"}),
+                ),
+            ),
+            (
+                "sealed".to_owned(),
+                DynamicType::Default(Box::new(DynamicType::bool), json!(false)),
+            ),
+            (
+                "notes".to_owned(),
+                DynamicType::Option(Box::new(DynamicType::String)),
+            ),
+        ]));
+
+        assert_eq!(
+            register_value(tank.clone(), json!({"variety": "Garnacha"})),
+            Ok(json!({"variety": "Garnacha", "volume": 500.0, "sealed": false}))
         );
         assert_eq!(
-            result.state.custom_types.get("DataType").unwrap().clone(),
-            Fields::Object(HashMap::from([
-                ("text".to_owned(), DynamicType::String,),
-                ("value".to_owned(), DynamicType::u64,)
-            ])),
+            register_value(
+                tank.clone(),
+                json!({"variety": "Garnacha", "volume": 300, "sealed": true})
+            ),
+            Ok(json!({"variety": "Garnacha", "volume": 300, "sealed": true}))
         );
-        assert_eq!(result.state.custom_types.len(), 4);
 
-        ////////////////////////////////////////////////////////////////
-        // Unit process
-        ////////////////////////////////////////////////////////////////
-        let unit_process = UnitProcess {
-            name: "Unit example".to_owned(),
-            outputs: vec![Data {
-                name: "Example Object".to_owned(),
-                type_name: "UserObject".to_owned(),
-                content: json!({"value": {"text": "info", "value": 30}}),
-                targets: None,
-                metadata: None,
-            }],
-            inputs: vec![Data {
-                name: "Example Vec".to_owned(),
-                type_name: "UserVec".to_owned(),
-                content: json!({"value": ["one", "two"]}),
-                targets: None,
-                metadata: None,
-            }],
-            properties: vec![],
-        };
+        let error = register_value(tank.clone(), json!({"sealed": true})).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::MissingField);
+        assert_eq!(error.path.as_deref(), Some("$.variety"));
 
-        let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
-                delete: None,
-                modify: None,
-                add: Some(vec![unit_process]),
-            }),
-            is_owner: false,
+        let error =
+            register_value(tank, json!({"variety": "Garnacha", "sealed": "no"})).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+        assert_eq!(error.path.as_deref(), Some("$.sealed"));
+    }
+
+    #[test]
+    fn test_default_values_definition() {
+        let tank = |c_type: DynamicType| {
+            Fields::Object(HashMap::from([
+                ("variety".to_owned(), DynamicType::String),
+                ("sealed".to_owned(), c_type),
+            ]))
         };
 
-        let mut result = sdk::ContractResult::new(result.state);
-        contract_logic(&context, &mut result);
+        let error = add_type(
+            "Tank",
+            tank(DynamicType::Default(
+                Box::new(DynamicType::bool),
+                json!("no"),
+            )),
+        )
+        .unwrap();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+        assert_eq!(error.path.as_deref(), Some("$.sealed"));
+        assert!(
+            error
+                .message
+                .starts_with("Check error: Invalid default value.")
+        );
 
-        println!("{}", result.error);
-        assert!(result.error.is_empty());
+        let error = add_type(
+            "Tank",
+            tank(DynamicType::Default(
+                Box::new(DynamicType::Number(NumberConstraints {
+                    min: Some(1.0),
+                    ..number(NumberKind::u64)
+                })),
+                json!(0),
+            )),
+        )
+        .unwrap();
+        assert_eq!(error.kind, ErrorKind::Constraint);
+
+        for fields in [
+            tank(DynamicType::Default(
+                Box::new(DynamicType::Option(Box::new(DynamicType::bool))),
+                Value::Null,
+            )),
+            tank(DynamicType::Vec(Box::new(DynamicType::Default(
+                Box::new(DynamicType::bool),
+                json!(false),
+            )))),
+            Fields::Basic(Box::new(DynamicType::Default(
+                Box::new(DynamicType::bool),
+                json!(false),
+            ))),
+        ] {
+            let error = add_type("Tank", fields).unwrap();
+            assert_eq!(error.kind, ErrorKind::InvalidType);
+            assert_eq!(error.element.as_deref(), Some("Tank"));
+        }
 
-        assert_eq!(result.state.unit_process.len(), 1);
-        assert_eq!(result.state.unit_process[0].name, "Unit example");
-        assert_eq!(result.state.unit_process[0].outputs.len(), 1);
+        assert!(
+            add_type(
+                "Tank",
+                tank(DynamicType::Default(
+                    Box::new(DynamicType::bool),
+                    json!(false)
+                ))
+            )
+            .is_none()
+        );
+    }
 
-        let data = result.state.unit_process[0].outputs[0].clone();
-        assert_eq!(data.name, "Example Object");
-        assert_eq!(data.type_name, "UserObject");
+    #[test]
+    fn test_annotations() {
+        let annotations = Annotations {
+            description: Some("Stainless steel fermentation tank.".to_owned()),
+            label: Some("Tank".to_owned()),
+            deprecated: false,
+        };
+
+        let tank: Fields = serde_json::from_value(json!({
+            "fields": {
+                "variety": {"Annotated": ["String", {"label": "Grape variety"}]},
+                "sealed": {"Annotated": [{"Default": ["bool", false]}, {"deprecated": true}]}
+            },
+            "annotations": {
+                "description": "Stainless steel fermentation tank.",
+                "label": "Tank"
+            }
+        }))
+        .unwrap();
         assert_eq!(
-            data.content,
-            json!({"value": {"text": "info", "value": 30}})
+            tank,
+            Fields::Annotated {
+                fields: Box::new(Fields::Object(HashMap::from([
+                    (
+                        "variety".to_owned(),
+                        DynamicType::Annotated(
+                            Box::new(DynamicType::String),
+                            Annotations {
+                                description: None,
+                                label: Some("Grape variety".to_owned()),
+                                deprecated: false,
+                            }
+                        )
+                    ),
+                    (
+                        "sealed".to_owned(),
+                        DynamicType::Annotated(
+                            Box::new(DynamicType::Default(
+                                Box::new(DynamicType::bool),
+                                json!(false)
+                            )),
+                            Annotations {
+                                description: None,
+                                label: None,
+                                deprecated: true,
+                            }
+                        )
+                    ),
+                ]))),
+                annotations: annotations.clone(),
+            }
         );
 
-        assert_eq!(result.state.unit_process[0].inputs.len(), 1);
-
-        let data = result.state.unit_process[0].inputs[0].clone();
-        assert_eq!(data.name, "Example Vec");
-        assert_eq!(data.type_name, "UserVec");
-        assert_eq!(data.content, json!({"value": ["one", "two"]}));
+        let plain: Fields = serde_json::from_value(json!({"fields": "String"})).unwrap();
+        assert_eq!(
+            plain,
+            Fields::Object(HashMap::from([("fields".to_owned(), DynamicType::String)]))
+        );
 
-        assert!(result.success);
+        assert_eq!(
+            register_value(tank.clone(), json!({"variety": "Garnacha"})),
+            Ok(json!({"variety": "Garnacha", "sealed": false}))
+        );
+        let error = register_value(tank, json!({"variety": 10})).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+        assert_eq!(error.path.as_deref(), Some("$.variety"));
+
+        let unit_process: UnitProcess = serde_json::from_value(json!({
+            "name": "Pressing",
+            "inputs": [{
+                "name": "Grapes",
+                "type_name": "u64",
+                "metadata": null,
+                "content": 0,
+                "targets": null,
+                "annotations": {"label": "Grapes (kg)"}
+            }],
+            "outputs": [],
+            "properties": [],
+            "annotations": {"description": "Pneumatic press."}
+        }))
+        .unwrap();
+        assert_eq!(
+            unit_process.annotations,
+            Some(Annotations {
+                description: Some("Pneumatic press.".to_owned()),
+                label: None,
+                deprecated: false,
+            })
+        );
+        assert_eq!(
+            unit_process.inputs[0].annotations,
+            Some(Annotations {
+                description: None,
+                label: Some("Grapes (kg)".to_owned()),
+                deprecated: false,
+            })
+        );
     }
 
     #[test]
-    fn test_type_fail() {
-        ////////////////////////////////////////////////////////////////
-        // Type definition
-        ////////////////////////////////////////////////////////////////
-        let init_state = ProductionSystem {
-            name: "example".to_owned(),
-            version: 1,
-            unit_process: vec![],
-            custom_types: HashMap::new(),
-            properties: vec![],
+    fn test_annotations_definition() {
+        let annotations = Annotations {
+            description: None,
+            label: Some("Tank".to_owned()),
+            deprecated: false,
         };
+        let tank = Fields::Object(HashMap::from([(
+            "variety".to_owned(),
+            DynamicType::Annotated(Box::new(DynamicType::String), annotations.clone()),
+        )]));
+
+        assert!(
+            add_type(
+                "Tank",
+                Fields::Annotated {
+                    fields: Box::new(tank.clone()),
+                    annotations: annotations.clone(),
+                }
+            )
+            .is_none()
+        );
 
-        ////////////////////////////////////////////////////////////////
-        // Unit process
-        ////////////////////////////////////////////////////////////////
-        let unit_process = UnitProcess {
-            name: "Unit example".to_owned(),
-            outputs: vec![Data {
-                name: "Example Dummy".to_owned(),
-                type_name: "Type".to_owned(),
-                content: json!({}),
-                targets: None,
-                metadata: None,
-            }],
-            inputs: vec![],
-            properties: vec![],
-        };
+        for fields in [
+            Fields::Annotated {
+                fields: Box::new(Fields::Annotated {
+                    fields: Box::new(tank),
+                    annotations: annotations.clone(),
+                }),
+                annotations: annotations.clone(),
+            },
+            Fields::Basic(Box::new(DynamicType::Annotated(
+                Box::new(DynamicType::String),
+                annotations.clone(),
+            ))),
+            Fields::Object(HashMap::from([(
+                "varieties".to_owned(),
+                DynamicType::Vec(Box::new(DynamicType::Annotated(
+                    Box::new(DynamicType::String),
+                    annotations.clone(),
+                ))),
+            )])),
+        ] {
+            let error = add_type("Tank", fields).unwrap();
+            assert_eq!(error.kind, ErrorKind::InvalidType);
+            assert_eq!(error.element.as_deref(), Some("Tank"));
+        }
+
+        let mut init_state = migration_state();
+        let wine = init_state.custom_types.remove("Wine").unwrap();
+        init_state.custom_types.insert(
+            "Wine".to_owned(),
+            Fields::Annotated {
+                fields: Box::new(wine),
+                annotations: annotations.clone(),
+            },
+        );
 
         let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
                 delete: None,
-                modify: None,
-                add: Some(vec![unit_process]),
+                add: None,
+                replace: None,
+                migrate: Some(vec![Migration::DropField {
+                    type_name: "Wine".to_owned(),
+                    field: "notes".to_owned(),
+                }]),
             }),
-            is_owner: false,
+            is_owner: true,
         };
 
-        let mut result = sdk::ContractResult::new(init_state.clone());
+        let mut result = sdk::ContractResult::new(init_state);
         contract_logic(&context, &mut result);
 
-        assert!(!result.error.is_empty());
-        assert!(!result.success);
+        assert!(result.success);
+        let Some(Fields::Annotated {
+            fields,
+            annotations: wine_annotations,
+        }) = result.state.custom_types.get("Wine")
+        else {
+            panic!("Wine lost its annotations");
+        };
+        assert_eq!(*wine_annotations, annotations);
+        assert!(
+            matches!(**fields, Fields::Object(ref hash_map) if !hash_map.contains_key("notes"))
+        );
     }
 
-    // TODO REHACER
     #[test]
-    fn change_operation_name() {
-        let init_state = ProductionSystem {
-            name: "wine process".to_owned(),
-            version: 1,
-            unit_process: vec![],
-            custom_types: HashMap::new(),
-            properties: vec![],
+    fn test_json_schema_types() {
+        let tank = Fields::Annotated {
+            fields: Box::new(Fields::Object(HashMap::from([
+                (
+                    "volume".to_owned(),
+                    DynamicType::Number(NumberConstraints {
+                        min: Some(0.0),
+                        max: Some(1000.0),
+                        unit: Some("L".to_owned()),
+                        ..number(NumberKind::f64)
+                    }),
+                ),
+                (
+                    "lot".to_owned(),
+                    DynamicType::Text(TextConstraints {
+                        max_length: Some(20),
+                        ..text(Some(TextFormat::Lot))
+                    }),
+                ),
+                (
+                    "price".to_owned(),
+                    DynamicType::Option(Box::new(DynamicType::Decimal { scale: 2 })),
+                ),
+                (
+                    "sealed".to_owned(),
+                    DynamicType::Default(Box::new(DynamicType::bool), json!(false)),
+                ),
+                (
+                    "wine".to_owned(),
+                    DynamicType::Annotated(
+                        Box::new(DynamicType::Type("Wine".to_owned())),
+                        Annotations {
+                            description: None,
+                            label: Some("Wine".to_owned()),
+                            deprecated: true,
+                        },
+                    ),
+                ),
+            ]))),
+            annotations: Annotations {
+                description: Some("Fermentation tank.".to_owned()),
+                label: None,
+                deprecated: false,
+            },
         };
 
-        let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyProductionSystem {
-                name: Some("wine process 2".to_owned()),
-                delete_properties: None,
-                modify_properties: None,
-                add_properties: None,
-            }),
-            is_owner: false,
-        };
+        assert_eq!(
+            tank.json_schema(),
+            json!({
+                "description": "Fermentation tank.",
+                "type": "object",
+                "properties": {
+                    "volume": {"type": "number", "minimum": 0.0, "maximum": 1000.0, "x-unit": "L"},
+                    "lot": {"type": "string", "maxLength": 20, "format": "lot"},
+                    "price": {"anyOf": [
+                        {"type": ["string", "number"], "pattern": "^-?[0-9]+(\\.[0-9]{1,2})?$"},
+                        {"type": "null"}
+                    ]},
+                    "sealed": {"type": "boolean", "default": false},
+                    "wine": {"$ref": "#/$defs/Wine", "title": "Wine", "deprecated": true}
+                },
+                "required": ["lot", "volume", "wine"],
+                "additionalProperties": false
+            })
+        );
 
-        let mut result = sdk::ContractResult::new(init_state);
-        contract_logic(&context, &mut result);
+        let readings = DynamicType::Map(
+            Box::new(DynamicType::u64),
+            Box::new(DynamicType::Tuple(vec![
+                DynamicType::Timestamp,
+                DynamicType::Array(3, Box::new(DynamicType::i64)),
+            ])),
+        );
+        assert_eq!(
+            readings.json_schema(),
+            json!({
+                "type": "object",
+                "propertyNames": {"pattern": "^[0-9]+$"},
+                "additionalProperties": {
+                    "type": "array",
+                    "prefixItems": [
+                        {"type": "string", "format": "date-time"},
+                        {"type": "array", "items": {"type": "integer"}, "minItems": 3, "maxItems": 3}
+                    ],
+                    "items": false,
+                    "minItems": 2
+                }
+            })
+        );
 
-        assert_eq!(result.state.version, 2);
-        assert_eq!(result.state.name, "wine process 2");
-        assert!(result.success);
+        let style = DynamicType::Enum(HashMap::from([
+            ("Red".to_owned(), DynamicType::Dummy),
+            (
+                "Blend".to_owned(),
+                DynamicType::Vec(Box::new(DynamicType::String)),
+            ),
+        ]));
+        assert_eq!(
+            style.json_schema(),
+            json!({"oneOf": [
+                {
+                    "type": "object",
+                    "properties": {"Blend": {"type": "array", "items": {"type": "string"}}},
+                    "required": ["Blend"],
+                    "additionalProperties": false
+                },
+                {"const": "Red"}
+            ]})
+        );
     }
 
     #[test]
-    fn test_change_operation_init() {
-        let init_state = ProductionSystem {
-            name: "".to_owned(),
-            version: 0,
-            unit_process: vec![],
-            custom_types: HashMap::new(),
-            properties: vec![],
-        };
+    fn test_json_schema_production_system() {
+        let mut state = rollback_state();
+        state.unit_process[0].inputs[0].annotations = Some(Annotations {
+            description: None,
+            label: Some("Example".to_owned()),
+            deprecated: false,
+        });
+
+        let schema = json_schema(serde_json::to_value(&state).unwrap()).unwrap();
+        assert_eq!(
+            schema["$schema"],
+            json!("https://json-schema.org/draft/2020-12/schema")
+        );
+        assert_eq!(schema["title"], json!("example"));
+        assert_eq!(
+            schema["$defs"],
+            json!({
+                "UserBasic": {"type": "string"},
+                "UserObject": {
+                    "type": "object",
+                    "properties": {"name": {"type": "string"}},
+                    "required": ["name"],
+                    "additionalProperties": false
+                }
+            })
+        );
+        assert_eq!(
+            schema["properties"]["unit_process"]["properties"]["Pressing"],
+            json!({
+                "type": "object",
+                "properties": {
+                    "inputs": {
+                        "type": "object",
+                        "properties": {
+                            "Example Basic": {"$ref": "#/$defs/UserBasic", "title": "Example"}
+                        },
+                        "additionalProperties": false
+                    },
+                    "outputs": {
+                        "type": "object",
+                        "properties": {"Example Object": {"$ref": "#/$defs/UserObject"}},
+                        "additionalProperties": false
+                    },
+                    "properties": {
+                        "type": "object",
+                        "properties": {},
+                        "additionalProperties": false
+                    }
+                },
+                "additionalProperties": false
+            })
+        );
+        assert_eq!(
+            schema["properties"]["properties"]["properties"]["Example String"],
+            json!({"type": "string"})
+        );
 
-        let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::Init {
-                name: "wine process".to_owned(),
-                unit_process: Some(vec![UnitProcess {
-                    name: "Unit example".to_owned(),
-                    outputs: vec![Data {
-                        name: "Example Object".to_owned(),
-                        type_name: "UserObject".to_owned(),
-                        content: json!({"name": "ExampleName"}),
-                        targets: None,
-                        metadata: None,
-                    }],
-                    inputs: vec![Data {
-                        name: "Example Basic".to_owned(),
-                        type_name: "UserBasic".to_owned(),
-                        content: json!("ExampleBasic"),
-                        targets: None,
-                        metadata: None,
-                    }],
-                    properties: vec![Properties {
-                        name: "Example String".to_owned(),
-                        type_name: "String".to_owned(),
-                        content: json!("ExampleString"),
-                    }],
-                }]),
-                properties: Some(vec![Properties {
-                    name: "Example Object".to_owned(),
-                    type_name: "UserObject".to_owned(),
-                    content: json!({"name": "ExampleName"}),
-                }]),
-                types: Some(vec![
-                    (
-                        "UserObject".to_owned(),
-                        Fields::Object(HashMap::from([("name".to_owned(), DynamicType::String)])),
+        let error = json_schema(json!({"name": "example"})).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+    }
+
+    #[test]
+    fn test_import_json_schema() {
+        let wine = Fields::Object(HashMap::from([(
+            "grape".to_owned(),
+            DynamicType::Enum(HashMap::from([
+                ("Tempranillo".to_owned(), DynamicType::Dummy),
+                ("Other".to_owned(), DynamicType::String),
+            ])),
+        )]));
+        let tank = Fields::Annotated {
+            fields: Box::new(Fields::Object(HashMap::from([
+                (
+                    "volume".to_owned(),
+                    DynamicType::Number(NumberConstraints {
+                        min: Some(0.0),
+                        max: Some(1000.0),
+                        unit: Some("L".to_owned()),
+                        ..number(NumberKind::f64)
+                    }),
+                ),
+                (
+                    "lot".to_owned(),
+                    DynamicType::Text(TextConstraints {
+                        max_length: Some(20),
+                        pattern: Some("[A-Z]+".to_owned()),
+                        ..text(Some(TextFormat::Lot))
+                    }),
+                ),
+                (
+                    "price".to_owned(),
+                    DynamicType::Option(Box::new(DynamicType::Decimal { scale: 2 })),
+                ),
+                (
+                    "sealed".to_owned(),
+                    DynamicType::Default(Box::new(DynamicType::bool), json!(false)),
+                ),
+                (
+                    "readings".to_owned(),
+                    DynamicType::Map(
+                        Box::new(DynamicType::u64),
+                        Box::new(DynamicType::Tuple(vec![
+                            DynamicType::Timestamp,
+                            DynamicType::Array(3, Box::new(DynamicType::i64)),
+                        ])),
                     ),
-                    (
-                        "UserBasic".to_owned(),
-                        Fields::Basic(Box::new(DynamicType::String)),
+                ),
+                (
+                    "wine".to_owned(),
+                    DynamicType::Annotated(
+                        Box::new(DynamicType::Type("Wine".to_owned())),
+                        Annotations {
+                            description: None,
+                            label: Some("Wine".to_owned()),
+                            deprecated: true,
+                        },
                     ),
-                ]),
-            }),
-            is_owner: false,
+                ),
+            ]))),
+            annotations: Annotations {
+                description: Some("Fermentation tank.".to_owned()),
+                label: None,
+                deprecated: false,
+            },
         };
 
-        let mut result = sdk::ContractResult::new(init_state);
-        contract_logic(&context, &mut result);
-
-        assert_eq!(result.state.name, "wine process");
-        assert_eq!(result.state.version, 1);
-        assert_eq!(result.state.properties.len(), 1);
+        let types = import_json_schema(&json!({
+            "$defs": {"Tank": tank.json_schema(), "Wine": wine.json_schema()}
+        }))
+        .unwrap();
         assert_eq!(
-            result.state.properties[0],
-            Properties {
-                name: "Example Object".to_owned(),
-                type_name: "UserObject".to_owned(),
-                content: json!({"name": "ExampleName"}),
-            }
+            types,
+            vec![("Tank".to_owned(), tank), ("Wine".to_owned(), wine)]
         );
-        assert_eq!(result.state.unit_process.len(), 1);
+
+        let types = import_json_schema(&json!({
+            "definitions": {
+                "Code": {"type": "string", "pattern": "^[A-Z]{3}", "minLength": 3},
+                "Count": {"type": "integer", "minimum": 0},
+                "Tags": {"type": "array", "items": {"type": "string", "format": "date"}},
+                "Level": {"type": ["integer", "null"], "maximum": 10},
+                "Color": {"type": "string", "enum": ["Red", "White"]}
+            }
+        }))
+        .unwrap();
         assert_eq!(
-            result.state.unit_process[0],
-            UnitProcess {
-                name: "Unit example".to_owned(),
-                outputs: vec![Data {
-                    name: "Example Object".to_owned(),
-                    type_name: "UserObject".to_owned(),
-                    content: json!({"name": "ExampleName"}),
-                    targets: None,
-                    metadata: None
-                },],
-                inputs: vec![Data {
-                    name: "Example Basic".to_owned(),
-                    type_name: "UserBasic".to_owned(),
-                    content: json!("ExampleBasic"),
-                    targets: None,
-                    metadata: None
-                }],
-                properties: vec![Properties {
-                    name: "Example String".to_owned(),
-                    type_name: "String".to_owned(),
-                    content: json!("ExampleString"),
-                }],
+            types,
+            vec![
+                (
+                    "Code".to_owned(),
+                    Fields::Basic(Box::new(DynamicType::Text(TextConstraints {
+                        min_length: Some(3),
+                        pattern: Some("(?s:.*)(?:^[A-Z]{3})(?s:.*)".to_owned()),
+                        ..text(None)
+                    })))
+                ),
+                (
+                    "Color".to_owned(),
+                    Fields::Basic(Box::new(DynamicType::Enum(HashMap::from([
+                        ("Red".to_owned(), DynamicType::Dummy),
+                        ("White".to_owned(), DynamicType::Dummy),
+                    ]))))
+                ),
+                (
+                    "Count".to_owned(),
+                    Fields::Basic(Box::new(DynamicType::u64))
+                ),
+                (
+                    "Level".to_owned(),
+                    Fields::Basic(Box::new(DynamicType::Option(Box::new(
+                        DynamicType::Number(NumberConstraints {
+                            max: Some(10.0),
+                            ..number(NumberKind::i64)
+                        })
+                    ))))
+                ),
+                (
+                    "Tags".to_owned(),
+                    Fields::Basic(Box::new(DynamicType::Vec(Box::new(DynamicType::Date))))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_json_schema_unsupported() {
+        let error = import_json_schema(&json!({
+            "$defs": {
+                "Tank": {
+                    "type": "object",
+                    "properties": {
+                        "volume": {"type": "number", "minimum": 0},
+                        "wine": {"type": "object", "properties": {"grape": {"type": "string"}}}
+                    }
+                }
             }
+        }))
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Unsupported);
+        assert_eq!(
+            error.path.as_deref(),
+            Some("$[\"$defs\"].Tank.properties.wine.properties")
         );
+        assert_eq!(error.element.as_deref(), Some("Tank"));
+
+        let error = import_json_schema(&json!({
+            "$defs": {"Code": {"type": "string", "contentEncoding": "base64"}}
+        }))
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Unsupported);
         assert_eq!(
-            result.state.custom_types.get("UserObject").unwrap().clone(),
-            Fields::Object(HashMap::from([("name".to_owned(), DynamicType::String)]))
+            error.path.as_deref(),
+            Some("$[\"$defs\"].Code.contentEncoding")
         );
+
+        let error = import_json_schema(&json!({
+            "$defs": {"Value": {"oneOf": [{"const": "None"}, {"type": "string"}]}}
+        }))
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Unsupported);
+        assert_eq!(error.path.as_deref(), Some("$[\"$defs\"].Value.oneOf[1]"));
+
+        let error = import_json_schema(&json!({
+            "$defs": {"Any": {"anyOf": [{"type": "string"}, {"type": "integer"}]}}
+        }))
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Unsupported);
+        assert_eq!(error.path.as_deref(), Some("$[\"$defs\"].Any.anyOf"));
+
+        let error = import_json_schema(&json!({
+            "$defs": {"Reference": {"$ref": "https://example.com/schema.json"}}
+        }))
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Unsupported);
         assert_eq!(
-            result.state.custom_types.get("UserBasic").unwrap().clone(),
-            Fields::Basic(Box::new(DynamicType::String))
+            error.path.as_deref(),
+            Some("$[\"$defs\"].Reference[\"$ref\"]")
         );
-        assert!(result.success);
+
+        let error = import_json_schema(&json!({
+            "$defs": {"Items": {"type": "array", "items": {"type": "integer"}, "maxItems": 5}}
+        }))
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Unsupported);
+        assert_eq!(error.path.as_deref(), Some("$[\"$defs\"].Items.maxItems"));
+
+        let error = import_json_schema(&json!({"$defs": {"Empty": true}})).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Unsupported);
+        assert_eq!(error.path.as_deref(), Some("$[\"$defs\"].Empty"));
+
+        let error = import_json_schema(&json!({"type": "object"})).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::MissingParameter);
+    }
+
+    fn flow_state() -> ProductionSystem {
+        let element = |name: &str, type_name: &str| Data {
+            name: name.to_owned(),
+            type_name: type_name.to_owned(),
+            content: json!(0.0),
+            targets: None,
+            metadata: None,
+            annotations: None,
+            registered: false,
+        };
+        let unit_process = |name: &str, inputs: Vec<Data>, outputs: Vec<Data>| UnitProcess {
+            name: name.to_owned(),
+            inputs,
+            outputs,
+            properties: vec![],
+            writers: None,
+            annotations: None,
+            balance: None,
+        };
+
+        ProductionSystem {
+            name: "wine process".to_owned(),
+            version: 1,
+            unit_process: vec![
+                unit_process(
+                    "Pressing",
+                    vec![element("grapes", "f64")],
+                    vec![element("must", "Volume")],
+                ),
+                unit_process(
+                    "Fermentation",
+                    vec![element("must", "Volume")],
+                    vec![element("wine", "Volume")],
+                ),
+                unit_process("Bottling", vec![element("wine", "Volume")], vec![]),
+            ],
+            custom_types: HashMap::from([(
+                "Volume".to_owned(),
+                Fields::Basic(Box::new(DynamicType::Number(NumberConstraints {
+                    unit: Some("L".to_owned()),
+                    ..number(NumberKind::f64)
+                }))),
+            )]),
+            properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![link("Pressing", "must", "Fermentation", "must")],
+            subject_id: None,
+        }
+    }
+
+    fn link(from: &str, output: &str, to: &str, input: &str) -> Link {
+        Link {
+            from: from.to_owned(),
+            output: output.to_owned(),
+            to: to.to_owned(),
+            input: input.to_owned(),
+            recirculation: false,
+            propagate: false,
+        }
+    }
+
+    fn apply(init_state: ProductionSystem, event: Events) -> Result<ProductionSystem, Error> {
+        let context = sdk::Context {
+            event,
+            is_owner: true,
+        };
+
+        let mut result = sdk::ContractResult::new(init_state);
+        contract_logic(&context, &mut result);
+
+        if result.success {
+            Ok(result.state)
+        } else {
+            Err(serde_json::from_str(&result.error).unwrap())
+        }
+    }
+
+    fn modify_links(
+        delete: Option<Vec<String>>,
+        delete_links: Option<Vec<Link>>,
+        add_links: Option<Vec<Link>>,
+    ) -> Events {
+        Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+            delete,
+            modify: None,
+            add: None,
+            delete_links,
+            add_links,
+        })
     }
 
     #[test]
-    fn test_add_change_delete() {
-        ////////////////////////////////////////////////////////////////
-        // Type definition
-        ////////////////////////////////////////////////////////////////
+    fn test_links() {
         let init_state = ProductionSystem {
-            name: "example".to_owned(),
-            version: 1,
+            name: "".to_owned(),
+            version: 0,
             unit_process: vec![],
             custom_types: HashMap::new(),
             properties: vec![],
+            validation: ValidationMode::FirstError,
+            permissions: Permissions::default(),
+            links: vec![],
+            subject_id: None,
         };
-
-        let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
-                delete: None,
-                add: Some(vec![
-                    (
-                        "UserObject".to_owned(),
-                        Fields::Object(HashMap::from([("name".to_owned(), DynamicType::String)])),
-                    ),
-                    (
-                        "UserBasic".to_owned(),
-                        Fields::Basic(Box::new(DynamicType::String)),
-                    ),
-                ]),
-            }),
-            is_owner: false,
+        let mut flow = flow_state();
+        let init = |state: &ProductionSystem, links: Vec<Link>| {
+            Events::ChangeProductionSystem(ChangeProductionSystem::Init {
+                name: "wine process".to_owned(),
+                unit_process: Some(state.unit_process.clone()),
+                types: Some(state.custom_types.clone().into_iter().collect()),
+                properties: None,
+                validation: None,
+                permissions: None,
+                links: Some(links),
+                subject_id: None,
+            })
         };
 
-        let mut result = sdk::ContractResult::new(init_state.clone());
+        let state = apply(
+            init_state.clone(),
+            init(
+                &flow,
+                vec![
+                    link("Pressing", "must", "Fermentation", "must"),
+                    link("Fermentation", "wine", "Bottling", "wine"),
+                ],
+            ),
+        )
+        .unwrap();
+        assert_eq!(
+            state.links,
+            vec![
+                link("Pressing", "must", "Fermentation", "must"),
+                link("Fermentation", "wine", "Bottling", "wine"),
+            ]
+        );
 
-        contract_logic(&context, &mut result);
+        // The grapes are an input of Pressing, not an output.
+        let error = apply(
+            init_state.clone(),
+            init(
+                &flow,
+                vec![link("Pressing", "grapes", "Fermentation", "must")],
+            ),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::NotFound);
+        assert_eq!(error.unit_process.as_deref(), Some("Pressing"));
+        assert_eq!(error.element.as_deref(), Some("grapes"));
+
+        flow.unit_process[1].inputs[0].type_name = "f64".to_owned();
+        let error = apply(
+            init_state.clone(),
+            init(
+                &flow,
+                vec![link("Pressing", "must", "Fermentation", "must")],
+            ),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+        assert_eq!(error.expected.as_deref(), Some("f64"));
+        assert_eq!(error.received.as_deref(), Some("Volume"));
+        assert_eq!(error.unit_process.as_deref(), Some("Fermentation"));
+        assert_eq!(error.element.as_deref(), Some("must"));
+    }
 
-        assert!(result.error.is_empty());
+    #[test]
+    fn test_modify_links() {
+        let state = apply(
+            flow_state(),
+            modify_links(
+                None,
+                None,
+                Some(vec![link("Fermentation", "wine", "Bottling", "wine")]),
+            ),
+        )
+        .unwrap();
+        assert_eq!(state.links.len(), 2);
+
+        let error = apply(
+            flow_state(),
+            modify_links(
+                None,
+                None,
+                Some(vec![link("Pressing", "must", "Fermentation", "must")]),
+            ),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Duplicate);
+
+        // Deleting a linked unit process leaves the link without its input.
+        let error = apply(
+            flow_state(),
+            modify_links(Some(vec!["Fermentation".to_owned()]), None, None),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::NotFound);
+        assert_eq!(error.unit_process.as_deref(), Some("Fermentation"));
+
+        let state = apply(
+            flow_state(),
+            modify_links(
+                Some(vec!["Fermentation".to_owned()]),
+                Some(vec![link("Pressing", "must", "Fermentation", "must")]),
+                None,
+            ),
+        )
+        .unwrap();
+        assert!(state.links.is_empty());
+
+        let error = apply(
+            flow_state(),
+            modify_links(
+                None,
+                Some(vec![link("Fermentation", "wine", "Bottling", "wine")]),
+                None,
+            ),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_flow_order() {
+        let mut init_state = flow_state();
+        init_state.unit_process.reverse();
+        init_state.unit_process[2].inputs.push(Data {
+            name: "lees".to_owned(),
+            type_name: "Volume".to_owned(),
+            content: json!(0.0),
+            targets: None,
+            metadata: None,
+            annotations: None,
+            registered: false,
+        });
+
+        let state = apply(
+            init_state.clone(),
+            modify_links(
+                None,
+                None,
+                Some(vec![link("Fermentation", "wine", "Bottling", "wine")]),
+            ),
+        )
+        .unwrap();
         assert_eq!(
-            result.state.custom_types.get("UserObject").unwrap().clone(),
-            Fields::Object(HashMap::from([("name".to_owned(), DynamicType::String)]))
+            state.topological_order(),
+            vec![
+                "Pressing".to_owned(),
+                "Fermentation".to_owned(),
+                "Bottling".to_owned()
+            ]
         );
         assert_eq!(
-            result.state.custom_types.get("UserBasic").unwrap().clone(),
-            Fields::Basic(Box::new(DynamicType::String))
+            topological_order(serde_json::to_value(&state).unwrap()).unwrap(),
+            state.topological_order()
         );
-        assert_eq!(result.state.custom_types.len(), 2);
 
-        ////////////////////////////////////////////////////////////////
-        // Unit process add
-        ////////////////////////////////////////////////////////////////
-        let unit_process = UnitProcess {
-            name: "Unit example".to_owned(),
-            outputs: vec![
-                Data {
-                    name: "Example Object".to_owned(),
-                    type_name: "UserObject".to_owned(),
-                    content: json!({"name": "ExampleName"}),
-                    targets: None,
-                    metadata: None,
-                },
-                Data {
-                    name: "Example String".to_owned(),
-                    type_name: "String".to_owned(),
-                    content: json!("ExampleString"),
+        // Fermentation sends part of the wine back to Pressing.
+        let error = apply(
+            init_state.clone(),
+            modify_links(
+                None,
+                None,
+                Some(vec![link("Fermentation", "wine", "Pressing", "lees")]),
+            ),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::CircularDependency);
+
+        let state = apply(
+            init_state.clone(),
+            modify_links(
+                None,
+                None,
+                Some(vec![Link {
+                    recirculation: true,
+                    ..link("Fermentation", "wine", "Pressing", "lees")
+                }]),
+            ),
+        )
+        .unwrap();
+        assert_eq!(
+            state.topological_order(),
+            vec![
+                "Bottling".to_owned(),
+                "Pressing".to_owned(),
+                "Fermentation".to_owned()
+            ]
+        );
+
+        // Links are deleted by their ends.
+        let state = apply(
+            state,
+            modify_links(
+                None,
+                Some(vec![link("Fermentation", "wine", "Pressing", "lees")]),
+                None,
+            ),
+        )
+        .unwrap();
+        assert_eq!(
+            state.links,
+            vec![link("Pressing", "must", "Fermentation", "must")]
+        );
+    }
+
+    fn register(
+        name: &str,
+        inputs: Option<Vec<(&str, Value)>>,
+        outputs: Option<Vec<(&str, Value)>>,
+    ) -> UnitData {
+        let data = |elements: Vec<(&str, Value)>| {
+            elements
+                .into_iter()
+                .map(|(name, content)| RegisterData {
+                    name: name.to_owned(),
+                    type_name: if name == "grapes" { "f64" } else { "Volume" }.to_owned(),
+                    content,
                     targets: None,
-                    metadata: None,
-                },
-            ],
-            inputs: vec![Data {
-                name: "Example Basic".to_owned(),
-                type_name: "UserBasic".to_owned(),
-                content: json!("ExampleBasic"),
-                targets: None,
-                metadata: None,
-            }],
-            properties: vec![],
+                })
+                .collect()
         };
 
-        let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+        UnitData {
+            name: name.to_owned(),
+            inputs: inputs.map(data),
+            outputs: outputs.map(data),
+            properties: None,
+            validation: None,
+        }
+    }
+
+    #[test]
+    fn test_propagate() {
+        let mut init_state = flow_state();
+        init_state.links[0].propagate = true;
+
+        // The must leaves Pressing in hectolitres and enters Fermentation in
+        // the litres of its type.
+        let state = apply(
+            init_state.clone(),
+            Events::RegisterData(vec![register(
+                "Pressing",
+                Some(vec![("grapes", json!(1000.0))]),
+                Some(vec![("must", json!({"value": 7, "unit": "hL"}))]),
+            )]),
+        )
+        .unwrap();
+        assert_eq!(state.unit_process[0].outputs[0].content, json!(700.0));
+        assert_eq!(state.unit_process[1].inputs[0].content, json!(700.0));
+
+        let error = apply(
+            init_state.clone(),
+            Events::RegisterData(vec![
+                register("Pressing", None, Some(vec![("must", json!(700.0))])),
+                register("Fermentation", Some(vec![("must", json!(650.0))]), None),
+            ]),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Duplicate);
+        assert_eq!(error.unit_process.as_deref(), Some("Fermentation"));
+        assert_eq!(error.element.as_deref(), Some("must"));
+
+        // Links without propagate only describe the flow.
+        let state = apply(
+            flow_state(),
+            Events::RegisterData(vec![register(
+                "Pressing",
+                None,
+                Some(vec![("must", json!(700.0))]),
+            )]),
+        )
+        .unwrap();
+        assert_eq!(state.unit_process[1].inputs[0].content, json!(0.0));
+    }
+
+    fn balance(inputs: &[&str], outputs: &[&str], losses: f64, tolerance: f64) -> BalanceRule {
+        BalanceRule {
+            inputs: inputs.iter().map(|x| x.to_string()).collect(),
+            outputs: outputs.iter().map(|x| x.to_string()).collect(),
+            losses,
+            tolerance,
+        }
+    }
+
+    #[test]
+    fn test_balance() {
+        let mut init_state = flow_state();
+        init_state.unit_process[1].balance = Some(balance(&["must"], &["wine"], 0.1, 0.05));
+
+        // The outputs have no value yet.
+        let state = apply(
+            init_state.clone(),
+            Events::RegisterData(vec![register(
+                "Fermentation",
+                Some(vec![("must", json!(1000.0))]),
+                None,
+            )]),
+        )
+        .unwrap();
+
+        let error = apply(
+            state.clone(),
+            Events::RegisterData(vec![register(
+                "Fermentation",
+                None,
+                Some(vec![("wine", json!(1400.0))]),
+            )]),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Unbalanced);
+        assert_eq!(error.unit_process.as_deref(), Some("Fermentation"));
+        assert_eq!(error.expected.as_deref(), Some("900 ± 50"));
+        assert_eq!(error.received.as_deref(), Some("1400"));
+
+        let state = apply(
+            state,
+            Events::RegisterData(vec![register(
+                "Fermentation",
+                None,
+                Some(vec![("wine", json!({"value": 9, "unit": "hL"}))]),
+            )]),
+        )
+        .unwrap();
+        assert_eq!(state.unit_process[1].outputs[0].content, json!(900.0));
+
+        // The inputs of the next batch come before its outputs.
+        let next = apply(
+            state.clone(),
+            Events::RegisterData(vec![register(
+                "Fermentation",
+                Some(vec![("must", json!(2000.0))]),
+                None,
+            )]),
+        )
+        .unwrap();
+        assert!(!next.unit_process[1].outputs[0].registered);
+
+        let error = apply(
+            next.clone(),
+            Events::RegisterData(vec![register(
+                "Fermentation",
+                None,
+                Some(vec![("wine", json!(900.0))]),
+            )]),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Unbalanced);
+        assert_eq!(error.expected.as_deref(), Some("1800 ± 100"));
+
+        assert!(
+            apply(
+                next,
+                Events::RegisterData(vec![register(
+                    "Fermentation",
+                    None,
+                    Some(vec![("wine", json!(1800.0))]),
+                )]),
+            )
+            .is_ok()
+        );
+
+        // Propagated inputs start a new batch too.
+        let mut state = state;
+        state.links[0].propagate = true;
+        let state = apply(
+            state,
+            Events::RegisterData(vec![register(
+                "Pressing",
+                None,
+                Some(vec![("must", json!(1500.0))]),
+            )]),
+        )
+        .unwrap();
+        assert_eq!(state.unit_process[1].inputs[0].content, json!(1500.0));
+        assert!(!state.unit_process[1].outputs[0].registered);
+
+        let error = apply(
+            state,
+            Events::RegisterData(vec![register(
+                "Fermentation",
+                None,
+                Some(vec![("wine", json!(900.0))]),
+            )]),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Unbalanced);
+        assert_eq!(error.unit_process.as_deref(), Some("Fermentation"));
+        assert_eq!(error.expected.as_deref(), Some("1350 ± 75"));
+    }
+
+    #[test]
+    fn test_balance_zero() {
+        let mut init_state = flow_state();
+        init_state.unit_process[1].balance = Some(balance(&["must"], &["wine"], 0.0, 0.0));
+
+        // Nothing came out.
+        let error = apply(
+            init_state.clone(),
+            Events::RegisterData(vec![register(
+                "Fermentation",
+                Some(vec![("must", json!(1000.0))]),
+                Some(vec![("wine", json!(0.0))]),
+            )]),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Unbalanced);
+        assert_eq!(error.expected.as_deref(), Some("1000 ± 0"));
+        assert_eq!(error.received.as_deref(), Some("0"));
+
+        // Nothing went in.
+        let error = apply(
+            init_state.clone(),
+            Events::RegisterData(vec![register(
+                "Fermentation",
+                Some(vec![("must", json!(0.0))]),
+                Some(vec![("wine", json!(1400.0))]),
+            )]),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Unbalanced);
+        assert_eq!(error.expected.as_deref(), Some("0 ± 0"));
+        assert_eq!(error.received.as_deref(), Some("1400"));
+
+        let state = apply(
+            init_state,
+            Events::RegisterData(vec![register(
+                "Fermentation",
+                Some(vec![("must", json!(0.0))]),
+                Some(vec![("wine", json!(0.0))]),
+            )]),
+        )
+        .unwrap();
+        assert!(state.unit_process[1].inputs[0].registered);
+        assert!(state.unit_process[1].outputs[0].registered);
+    }
+
+    #[test]
+    fn test_balance_definition() {
+        let modify = |balance: BalanceRule| {
+            let mut unit_process = flow_state().unit_process[0].clone();
+            unit_process.balance = Some(balance);
+            apply(
+                flow_state(),
+                Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                    delete: None,
+                    modify: Some(vec![("Pressing".to_owned(), unit_process)]),
+                    add: None,
+                    delete_links: None,
+                    add_links: None,
+                }),
+            )
+        };
+
+        let error = modify(balance(&["grapes", "grapes"], &["must"], 0.0, 0.0)).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Duplicate);
+        assert_eq!(error.unit_process.as_deref(), Some("Pressing"));
+
+        let error = modify(balance(&["grapes"], &["wine"], 0.0, 0.0)).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::NotFound);
+        assert_eq!(error.element.as_deref(), Some("wine"));
+
+        let error = modify(balance(&["grapes"], &["must"], 1.5, 0.0)).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Constraint);
+        assert_eq!(error.received.as_deref(), Some("1.5"));
+
+        // Grapes are plain numbers and the must is in litres.
+        let error = modify(balance(&["grapes"], &["must"], 0.3, 0.05)).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::UnitMismatch);
+        assert_eq!(error.expected.as_deref(), Some("no unit"));
+        assert_eq!(error.received.as_deref(), Some("volume"));
+        assert_eq!(error.element.as_deref(), Some("must"));
+
+        // Temperatures do not add up, even in the same unit.
+        let mut init_state = flow_state();
+        init_state.custom_types.insert(
+            "Temperature".to_owned(),
+            Fields::Basic(Box::new(DynamicType::Number(NumberConstraints {
+                unit: Some("°C".to_owned()),
+                ..number(NumberKind::f64)
+            }))),
+        );
+        let mut unit_process = init_state.unit_process[2].clone();
+        unit_process.inputs[0].type_name = "Temperature".to_owned();
+        unit_process.outputs = vec![Data {
+            name: "bottle".to_owned(),
+            ..unit_process.inputs[0].clone()
+        }];
+        unit_process.balance = Some(balance(&["wine"], &["bottle"], 0.0, 0.0));
+        let error = apply(
+            init_state,
+            Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
                 delete: None,
-                modify: None,
-                add: Some(vec![unit_process]),
+                modify: Some(vec![("Bottling".to_owned(), unit_process)]),
+                add: None,
+                delete_links: None,
+                add_links: None,
             }),
-            is_owner: false,
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::UnitMismatch);
+        assert_eq!(error.expected.as_deref(), Some("mass or volume"));
+        assert_eq!(error.received.as_deref(), Some("temperature"));
+        assert_eq!(error.element.as_deref(), Some("wine"));
+
+        // Only the contract marks an element as registered.
+        let mut unit_process = flow_state().unit_process[0].clone();
+        unit_process.inputs[0].registered = true;
+        let error = apply(
+            flow_state(),
+            Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
+                delete: None,
+                modify: Some(vec![("Pressing".to_owned(), unit_process)]),
+                add: None,
+                delete_links: None,
+                add_links: None,
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidEvent);
+        assert_eq!(error.element.as_deref(), Some("grapes"));
+    }
+
+    #[test]
+    fn test_targets() {
+        let governance_id = "JQHVnUJ1aInKzEVA3kM4xEF8zItQXdLBGRgRiHQPZxZs";
+        let winery = "JUMpjpG7cPWB4XOQpqzHCgF2wbN3D6e2eaNJmhb-KFoA";
+        let target = |subject_id: &str, type_name: Option<&str>| Target {
+            governance_id: governance_id.to_owned(),
+            subject_id: subject_id.to_owned(),
+            unit_process: "Reception".to_owned(),
+            type_name: type_name.map(str::to_owned),
+        };
+        let register = |init_state: ProductionSystem, targets: Vec<Target>| {
+            apply(
+                init_state,
+                Events::RegisterData(vec![UnitData {
+                    name: "Fermentation".to_owned(),
+                    inputs: None,
+                    outputs: Some(vec![RegisterData {
+                        name: "wine".to_owned(),
+                        type_name: "Volume".to_owned(),
+                        content: json!(900.0),
+                        targets: Some(targets),
+                    }]),
+                    properties: None,
+                    validation: None,
+                }]),
+            )
+        };
+        let register_targets = |targets: Vec<Target>| {
+            let mut init_state = flow_state();
+            init_state.subject_id = Some("MLcG8xELxBCl7U4hXLbZkHpDCGBfYMhVwaP3T2a0rqyk".to_owned());
+            register(init_state, targets)
         };
 
-        let mut result = sdk::ContractResult::new(result.state);
-        contract_logic(&context, &mut result);
+        // The subject that holds the data must be known first.
+        let error = register(flow_state(), vec![target(winery, None)]).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidTarget);
+        assert_eq!(error.expected.as_deref(), Some("subject_id"));
+        assert_eq!(error.element.as_deref(), Some("wine"));
+        assert!(register(flow_state(), vec![]).is_ok());
+
+        let state = apply(
+            flow_state(),
+            Events::ChangeProductionSystem(ChangeProductionSystem::ModifyProductionSystem {
+                name: None,
+                delete_properties: None,
+                modify_properties: None,
+                add_properties: None,
+                validation: None,
+                permissions: None,
+                subject_id: Some("MLcG8xELxBCl7U4hXLbZkHpDCGBfYMhVwaP3T2a0rqyk".to_owned()),
+            }),
+        )
+        .unwrap();
+        assert!(register(state.clone(), vec![target(winery, None)]).is_ok());
+
+        let error = apply(
+            state,
+            Events::ChangeProductionSystem(ChangeProductionSystem::ModifyProductionSystem {
+                name: None,
+                delete_properties: None,
+                modify_properties: None,
+                add_properties: None,
+                validation: None,
+                permissions: None,
+                subject_id: Some("winery".to_owned()),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Constraint);
+        assert_eq!(error.received.as_deref(), Some("winery"));
 
-        println!("{}", result.error);
-        assert!(result.error.is_empty());
+        // The type of the element is recorded for the receiving subject.
+        let state = register_targets(vec![target(winery, None)]).unwrap();
+        assert_eq!(
+            state.unit_process[1].outputs[0].targets,
+            Some(vec![target(winery, Some("Volume"))])
+        );
 
-        assert_eq!(result.state.unit_process.len(), 1);
-        assert_eq!(result.state.unit_process[0].name, "Unit example");
-        assert_eq!(result.state.unit_process[0].outputs.len(), 2);
+        let error = register_targets(vec![target("winery", None)]).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidTarget);
+        assert_eq!(error.path.as_deref(), Some("$.targets[0]"));
+        assert_eq!(error.received.as_deref(), Some("winery"));
+        assert_eq!(error.element.as_deref(), Some("wine"));
+        assert_eq!(error.unit_process.as_deref(), Some("Fermentation"));
+
+        let error = register_targets(vec![target(
+            "MLcG8xELxBCl7U4hXLbZkHpDCGBfYMhVwaP3T2a0rqyk",
+            None,
+        )])
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidTarget);
+
+        let error = register_targets(vec![target(winery, None), target(winery, Some("Volume"))])
+            .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Duplicate);
+        assert_eq!(error.path.as_deref(), Some("$.targets[1]"));
+
+        let error = register_targets(vec![target(winery, Some("Wine"))]).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+        assert_eq!(error.expected.as_deref(), Some("Wine"));
+        assert_eq!(error.received.as_deref(), Some("Volume"));
+
+        // 512-bit digests have a two character code.
+        let sha512 = format!("0M{}", "a".repeat(86));
+        assert!(register_targets(vec![target(&sha512, None)]).is_ok());
+        assert!(register_targets(vec![target(&sha512[1..], None)]).is_err());
+    }
 
-        let data = result.state.unit_process[0].outputs[0].clone();
-        assert_eq!(data.name, "Example Object");
-        assert_eq!(data.type_name, "UserObject");
-        assert_eq!(data.content, json!({"name": "ExampleName"}));
-        let data = result.state.unit_process[0].outputs[1].clone();
-        assert_eq!(data.name, "Example String");
-        assert_eq!(data.type_name, "String");
-        assert_eq!(data.content, json!("ExampleString"));
+    #[test]
+    fn test_permissions_owner() {
+        let mut init_state = rollback_state();
+        init_state.permissions.modify_production_system = Access::Anyone;
 
-        let data = result.state.unit_process[0].inputs[0].clone();
-        assert_eq!(data.name, "Example Basic");
-        assert_eq!(data.type_name, "UserBasic");
-        assert_eq!(data.content, json!("ExampleBasic"));
+        let context = sdk::Context {
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyProductionSystem {
+                name: None,
+                delete_properties: None,
+                modify_properties: None,
+                add_properties: None,
+                validation: None,
+                permissions: Some(Permissions {
+                    modify_types: Access::Anyone,
+                    modify_unit_process: Access::Anyone,
+                    ..Permissions::default()
+                }),
+                subject_id: None,
+            }),
+            is_owner: false,
+        };
 
-        assert!(result.success);
+        let mut result = sdk::ContractResult::new(init_state.clone());
+        contract_logic(&context, &mut result);
 
-        ////////////////////////////////////////////////////////////////
-        // Unit process modify
-        ////////////////////////////////////////////////////////////////
-        let unit_process = UnitProcess {
-            name: "Unit example modify".to_owned(),
-            outputs: vec![Data {
-                name: "Example Basic modify".to_owned(),
-                type_name: "UserBasic".to_owned(),
-                content: json!("ExampleBasic"),
-                targets: None,
-                metadata: None,
-            }],
-            inputs: vec![Data {
-                name: "Example Object modify".to_owned(),
-                type_name: "UserObject".to_owned(),
-                content: json!({"name": "ExampleName"}),
-                targets: None,
-                metadata: None,
-            }],
-            properties: vec![],
-        };
+        assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::Unauthorized);
+        assert_eq!(result.state.permissions, init_state.permissions);
 
+        // Other changes are still open to anyone.
         let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
-                delete: None,
-                modify: Some(vec![("Unit example".to_owned(), unit_process)]),
-                add: None,
+            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyProductionSystem {
+                name: Some("wine process".to_owned()),
+                delete_properties: None,
+                modify_properties: None,
+                add_properties: None,
+                validation: None,
+                permissions: None,
+                subject_id: None,
             }),
             is_owner: false,
         };
 
-        let mut result = sdk::ContractResult::new(result.state);
+        let mut result = sdk::ContractResult::new(init_state);
         contract_logic(&context, &mut result);
+        assert!(result.success);
+        assert_eq!(result.state.name, "wine process");
+    }
 
-        println!("{}", result.error);
-        assert!(result.error.is_empty());
+    #[test]
+    fn test_propagate_writers() {
+        let mut init_state = flow_state();
+        init_state.links[0].propagate = true;
+        init_state.unit_process[1].writers = Some(Access::Owner);
 
-        assert_eq!(result.state.unit_process.len(), 1);
-        assert_eq!(result.state.unit_process[0].name, "Unit example modify");
-        assert_eq!(result.state.unit_process[0].outputs.len(), 1);
+        let event = Events::RegisterData(vec![register(
+            "Pressing",
+            None,
+            Some(vec![("must", json!(5.0))]),
+        )]);
 
-        let data = result.state.unit_process[0].outputs[0].clone();
-        assert_eq!(data.name, "Example Basic modify");
-        assert_eq!(data.type_name, "UserBasic");
-        assert_eq!(data.content, json!("ExampleBasic"));
+        let context = sdk::Context {
+            event: event.clone(),
+            is_owner: false,
+        };
+        let mut result = sdk::ContractResult::new(init_state.clone());
+        contract_logic(&context, &mut result);
 
-        let data = result.state.unit_process[0].inputs[0].clone();
-        assert_eq!(data.name, "Example Object modify");
-        assert_eq!(data.type_name, "UserObject");
-        assert_eq!(data.content, json!({"name": "ExampleName"}));
+        assert!(!result.success);
+        let error: Error = serde_json::from_str(&result.error).unwrap();
+        assert_eq!(error.kind, ErrorKind::Unauthorized);
+        assert_eq!(error.unit_process.as_deref(), Some("Fermentation"));
+        assert_eq!(result.state.unit_process[1].inputs[0].content, json!(0.0));
 
-        ////////////////////////////////////////////////////////////////
-        // Unit process modify
-        ////////////////////////////////////////////////////////////////
+        let state = apply(init_state, event).unwrap();
+        assert_eq!(state.unit_process[1].inputs[0].content, json!(5.0));
+    }
 
-        let context = sdk::Context {
-            event: Events::ChangeProductionSystem(ChangeProductionSystem::ModifyUnitProcess {
-                delete: Some(vec!["Unit example modify".to_owned()]),
-                modify: None,
+    #[test]
+    fn test_replace_type_checks() {
+        let mut init_state = rollback_state();
+        init_state.custom_types.insert(
+            "Lot".to_owned(),
+            Fields::Object(HashMap::from([(
+                "label".to_owned(),
+                DynamicType::Option(Box::new(DynamicType::Type("UserBasic".to_owned()))),
+            )])),
+        );
+        init_state.custom_types.insert(
+            "Batch".to_owned(),
+            Fields::Object(HashMap::from([(
+                "label".to_owned(),
+                DynamicType::Type("UserBasic".to_owned()),
+            )])),
+        );
+        let replace = |from: &str, to: &str| {
+            Events::ChangeProductionSystem(ChangeProductionSystem::ModifyTypes {
+                delete: Some(vec![from.to_owned()]),
                 add: None,
-            }),
-            is_owner: false,
+                replace: Some(vec![(from.to_owned(), to.to_owned())]),
+                migrate: None,
+            })
         };
 
-        let mut result = sdk::ContractResult::new(result.state);
-        contract_logic(&context, &mut result);
+        let error = apply(init_state.clone(), replace("UserBasic", "Nope")).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::UnknownType);
+        assert_eq!(error.received.as_deref(), Some("Nope"));
+        assert_eq!(error.element.as_deref(), Some("UserBasic"));
+
+        // Batch would reference itself outside an Option, Vec or Map.
+        let error = apply(init_state.clone(), replace("UserBasic", "Batch")).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::CircularDependency);
+
+        // A built-in type takes the place of the elements, but not of the
+        // references in the custom types.
+        let error = apply(init_state.clone(), replace("UserBasic", "String")).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::UnknownType);
+        init_state.custom_types.remove("Lot");
+        init_state.custom_types.remove("Batch");
+        let state = apply(init_state, replace("UserBasic", "String")).unwrap();
+        assert_eq!(state.unit_process[0].inputs[0].type_name, "String");
+    }
 
-        assert!(result.error.is_empty());
-        assert_eq!(result.state.unit_process.len(), 0);
-        assert!(result.success);
+    #[test]
+    fn test_duration_overflow() {
+        let duration = Fields::Basic(Box::new(DynamicType::Duration));
+        let huge = "P99999999999999999999999999999W";
+
+        let error = register_value(duration.clone(), json!(huge)).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+        assert_eq!(
+            DynamicType::Duration.compare(&json!(huge), &json!("P1W")),
+            None
+        );
+
+        let error = register_value(
+            Fields::Basic(Box::new(DynamicType::Map(
+                Box::new(DynamicType::Duration),
+                Box::new(DynamicType::bool),
+            ))),
+            json!({ huge: true }),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::TypeMismatch);
+
+        assert_eq!(
+            register_value(duration, json!("P2DT1H")).unwrap(),
+            json!("P2DT1H")
+        );
     }
 }